@@ -0,0 +1,382 @@
+// src/analysis/adherence.rs
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::analysis::model::{
+    AdherenceReport, AdherenceSummary, DayAdherence, RouteAdherence, TripAdherence, TripStatus,
+    VehicleObservation,
+};
+use crate::analysis::{
+    group_by_route, load_derived_route, load_observations, load_schedule, reconstruct_trips,
+    scheduled_trips, service_calendar_for, terminal_directions,
+};
+use crate::calendar::model::ServiceCalendar;
+use crate::config::{
+    ADHERENCE_EARLY_SECS, ADHERENCE_LATE_SECS, ADHERENCE_MATCH_AFTER_SECS,
+    ADHERENCE_MATCH_BEFORE_SECS,
+};
+use crate::registry::model::RouteRegistry;
+use crate::registry::normalize_route_no;
+use crate::route::model::DerivedFeature;
+use crate::schedule::day_type::fallback_chain;
+use crate::schedule::service_time::ServiceTime;
+use crate::utils::ensure_dir;
+
+/// An observed departure from a terminal
+struct ObservedDeparture {
+    direction: String,
+    departed_at: NaiveDateTime,
+    vehicle_no: String,
+}
+
+/// Compares observed terminal departures against the scraped timetables.
+///
+/// 1. Reconstructs vehicle trips per TAGO route and takes the visits of each terminal
+///    (the first stop, and the first stop after the turning point) as departures.
+/// 2. Maps each terminal to a schedule direction by name.
+/// 3. For every observed day, matches scheduled departures to observed ones in order,
+///    classifying each trip as early, on time, late or missed.
+/// 4. Saves the report to `adherence/report.json`.
+///
+pub async fn run_adherence(
+    input_dir: PathBuf,
+    routes_dir: PathBuf,
+    schedules_dir: PathBuf,
+    specific_route: Option<String>,
+    output_dir: PathBuf,
+) -> Result<()> {
+    let derived_dir = routes_dir.join("derived_routes");
+    let adherence_dir = output_dir.join("adherence");
+
+    ensure_dir(&adherence_dir)?;
+
+    info!("[Loading observations from {:?}]", input_dir);
+
+    let observations = load_observations(&input_dir)?;
+    info!("Loaded {} observations.", observations.len());
+
+    let calendar = service_calendar_for(&observations);
+    let registry = RouteRegistry::build(&routes_dir, &schedules_dir)?;
+
+    // Group TAGO route variants under their normalized route number, which keys the registry.
+    let mut by_route_no: BTreeMap<String, Vec<(DerivedFeature, Vec<VehicleObservation>)>> =
+        BTreeMap::new();
+    for (route_id, route_obs) in group_by_route(observations) {
+        let route = match load_derived_route(&derived_dir, &route_id) {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(route = %route_id, "Skipping {}: {}", route_id, e);
+                continue;
+            }
+        };
+
+        if let Some(ref target) = specific_route
+            && normalize_route_no(&route.properties.route_no) != normalize_route_no(target)
+        {
+            continue;
+        }
+
+        by_route_no
+            .entry(normalize_route_no(&route.properties.route_no))
+            .or_default()
+            .push((route, route_obs));
+    }
+
+    info!("[Analyzing adherence for {} routes]", by_route_no.len());
+
+    let mut routes = Vec::new();
+
+    for (route_no, variants) in by_route_no {
+        let Some(schedule_file) = registry
+            .entry(&route_no)
+            .and_then(|e| e.schedule_file.clone())
+        else {
+            warn!(
+                route = %route_no,
+                "Skipping {}: no schedule in the route registry", route_no
+            );
+            continue;
+        };
+
+        let schedule = match load_schedule(&schedules_dir.join(schedule_file)) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!(route = %route_no, "Skipping {}: no schedule ({})", route_no, e);
+                continue;
+            }
+        };
+
+        let route_adherence = analyze_route(&route_no, &schedule, &variants, &calendar);
+        info!(
+            route = %route_no,
+            "✓ {}: {} scheduled, {} on time, {} early, {} late, {} missed",
+            route_no,
+            route_adherence.summary.scheduled,
+            route_adherence.summary.on_time,
+            route_adherence.summary.early,
+            route_adherence.summary.late,
+            route_adherence.summary.missed
+        );
+        routes.push(route_adherence);
+    }
+
+    let mut summary = AdherenceSummary::default();
+    for r in &routes {
+        summary.add(&r.summary);
+    }
+
+    info!(
+        "On-time performance: {:.1}% ({} of {} scheduled trips)",
+        summary.on_time_rate * 100.0,
+        summary.on_time,
+        summary.scheduled
+    );
+
+    let report = AdherenceReport {
+        generated_at: Local::now().to_rfc3339(),
+        early_threshold_secs: ADHERENCE_EARLY_SECS,
+        late_threshold_secs: ADHERENCE_LATE_SECS,
+        summary,
+        routes,
+    };
+
+    let path = adherence_dir.join("report.json");
+    fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+
+    info!("✓ Adherence report saved to {:?}", path);
+
+    Ok(())
+}
+
+/// Scheduled departure times per direction for a day type, following the day type
+/// fallback chain (e.g., holiday -> sunday -> weekend -> general).
+fn scheduled_departures(schedule: &Value, day_type: &str) -> HashMap<String, Vec<ServiceTime>> {
+    let mut result: HashMap<String, Vec<ServiceTime>> = HashMap::new();
+
+    let Some(key) = fallback_chain(day_type)
+        .into_iter()
+        .find(|key| schedule["schedule"][*key].is_object())
+    else {
+        return result;
+    };
+
+    for (direction, trips) in scheduled_trips(schedule, key) {
+        result.insert(direction, trips.into_iter().map(|t| t.time).collect());
+    }
+
+    result
+}
+
+fn analyze_route(
+    route_no: &str,
+    schedule: &Value,
+    variants: &[(DerivedFeature, Vec<VehicleObservation>)],
+    calendar: &ServiceCalendar,
+) -> RouteAdherence {
+    // Observed departures and observation coverage (first, last) per service day
+    let mut departures: BTreeMap<NaiveDate, Vec<ObservedDeparture>> = BTreeMap::new();
+    let mut coverage: BTreeMap<NaiveDate, (NaiveDateTime, NaiveDateTime)> = BTreeMap::new();
+
+    for (route, route_obs) in variants {
+        for obs in route_obs {
+            let t = obs.observed_at.naive_local();
+            let entry = coverage
+                .entry(ServiceTime::service_date_of(t))
+                .or_insert((t, t));
+            entry.0 = entry.0.min(t);
+            entry.1 = entry.1.max(t);
+        }
+
+        let terminals = terminal_directions(route, schedule);
+        for trip in reconstruct_trips(route, route_obs) {
+            for visit in &trip.visits {
+                if let Some((_, direction)) = terminals.iter().find(|(i, _)| *i == visit.stop_idx) {
+                    let departed_at = visit.visited_at.naive_local();
+                    departures
+                        .entry(ServiceTime::service_date_of(departed_at))
+                        .or_default()
+                        .push(ObservedDeparture {
+                            direction: direction.clone(),
+                            departed_at,
+                            vehicle_no: trip.vehicle_no.clone(),
+                        });
+                }
+            }
+        }
+    }
+
+    let mut days = Vec::new();
+    let mut summary = AdherenceSummary::default();
+
+    for (date, (first_seen, last_seen)) in coverage {
+        let day_type = calendar.day_type(date);
+        let observed = departures.remove(&date).unwrap_or_default();
+
+        let mut trips = Vec::new();
+        let mut matched_count = 0usize;
+
+        let mut scheduled: Vec<_> = scheduled_departures(schedule, &day_type)
+            .into_iter()
+            .collect();
+        scheduled.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (direction, times) in scheduled {
+            let mut candidates: Vec<&ObservedDeparture> = observed
+                .iter()
+                .filter(|o| o.direction == direction)
+                .collect();
+            candidates.sort_by_key(|o| o.departed_at);
+
+            // Only trips scheduled while vehicles were being observed can be judged.
+            let times: Vec<ServiceTime> = times
+                .into_iter()
+                .filter(|t| t.on(date) >= first_seen && t.on(date) <= last_seen)
+                .collect();
+            let instants: Vec<NaiveDateTime> = times.iter().map(|t| t.on(date)).collect();
+
+            let matches = match_departures(&instants, &candidates);
+            for ((time, scheduled_at), matched) in times.iter().zip(&instants).zip(matches) {
+                let trip = match matched {
+                    Some(o) => {
+                        matched_count += 1;
+                        let deviation = (o.departed_at - *scheduled_at).num_seconds();
+                        TripAdherence {
+                            direction: direction.clone(),
+                            scheduled: time.to_string(),
+                            observed: Some(o.departed_at.format("%H:%M:%S").to_string()),
+                            deviation_secs: Some(deviation),
+                            vehicle_no: Some(o.vehicle_no.clone()),
+                            status: classify(deviation),
+                        }
+                    }
+                    None => TripAdherence {
+                        direction: direction.clone(),
+                        scheduled: time.to_string(),
+                        observed: None,
+                        deviation_secs: None,
+                        vehicle_no: None,
+                        status: TripStatus::Missed,
+                    },
+                };
+                trips.push(trip);
+            }
+        }
+
+        if trips.is_empty() {
+            continue;
+        }
+
+        trips.sort_by(|a, b| a.scheduled.cmp(&b.scheduled));
+        let day_summary = AdherenceSummary::from_trips(&trips);
+        summary.add(&day_summary);
+
+        days.push(DayAdherence {
+            date: date.format("%Y-%m-%d").to_string(),
+            day_type,
+            summary: day_summary,
+            unscheduled: observed.len().saturating_sub(matched_count),
+            trips,
+        });
+    }
+
+    let mut route_ids: Vec<String> = variants
+        .iter()
+        .map(|(r, _)| r.properties.route_id.clone())
+        .collect();
+    route_ids.sort();
+
+    RouteAdherence {
+        route_no: route_no.to_string(),
+        route_ids,
+        summary,
+        days,
+    }
+}
+
+/// Matches scheduled times (sorted) to observed departures (sorted) in order.
+///
+/// Each scheduled departure takes the first unused observed departure inside its
+/// match window; observed departures are never reused and never go back in time.
+fn match_departures<'a>(
+    scheduled: &[NaiveDateTime],
+    observed: &[&'a ObservedDeparture],
+) -> Vec<Option<&'a ObservedDeparture>> {
+    let mut result = Vec::with_capacity(scheduled.len());
+    let mut next = 0usize;
+
+    for (i, s) in scheduled.iter().enumerate() {
+        let window_start = *s - chrono::Duration::seconds(ADHERENCE_MATCH_BEFORE_SECS);
+        let mut window_end = *s + chrono::Duration::seconds(ADHERENCE_MATCH_AFTER_SECS);
+
+        // A late bus is not credited to the next scheduled trip once that one is due.
+        if let Some(following) = scheduled.get(i + 1) {
+            let midpoint = *s + (*following - *s) / 2;
+            window_end =
+                window_end.min(midpoint.max(*s + chrono::Duration::seconds(ADHERENCE_LATE_SECS)));
+        }
+
+        while next < observed.len() && observed[next].departed_at < window_start {
+            next += 1;
+        }
+
+        if next < observed.len() && observed[next].departed_at <= window_end {
+            result.push(Some(observed[next]));
+            next += 1;
+        } else {
+            result.push(None);
+        }
+    }
+
+    result
+}
+
+fn classify(deviation_secs: i64) -> TripStatus {
+    if deviation_secs < -ADHERENCE_EARLY_SECS {
+        TripStatus::Early
+    } else if deviation_secs > ADHERENCE_LATE_SECS {
+        TripStatus::Late
+    } else {
+        TripStatus::OnTime
+    }
+}
+
+impl AdherenceSummary {
+    fn from_trips(trips: &[TripAdherence]) -> Self {
+        let mut summary = Self::default();
+        for trip in trips {
+            summary.scheduled += 1;
+            match trip.status {
+                TripStatus::Early => summary.early += 1,
+                TripStatus::OnTime => summary.on_time += 1,
+                TripStatus::Late => summary.late += 1,
+                TripStatus::Missed => summary.missed += 1,
+            }
+        }
+        summary.update_rate();
+        summary
+    }
+
+    fn add(&mut self, other: &AdherenceSummary) {
+        self.scheduled += other.scheduled;
+        self.early += other.early;
+        self.on_time += other.on_time;
+        self.late += other.late;
+        self.missed += other.missed;
+        self.update_rate();
+    }
+
+    fn update_rate(&mut self) {
+        self.on_time_rate = if self.scheduled == 0 {
+            0.0
+        } else {
+            (self.on_time as f64 / self.scheduled as f64 * 1000.0).round() / 1000.0
+        };
+    }
+}
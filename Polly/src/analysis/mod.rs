@@ -0,0 +1,380 @@
+// src/analysis/mod.rs
+
+mod adherence;
+pub mod model;
+mod travel_time;
+
+pub use adherence::run_adherence;
+pub use travel_time::run_travel_times;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::analysis::model::{ScheduledDeparture, StopVisit, VehicleObservation, VehicleTrip};
+use crate::calendar::model::{CalendarConfig, ServiceCalendar};
+use crate::config::{TRIP_MAX_GAP_SECS, TRIP_MAX_OFFSET_M};
+use crate::route::model::{DerivedFeature, DerivedFeatureCollection};
+use crate::schedule::service_time::ServiceTime;
+use crate::utils::{
+    geo::{cumulative_distances, locate_on_polyline},
+    parse_flexible_string,
+};
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Loads every archived observation from `*.jsonl` files in `dir`.
+///
+/// Each line is one `getRouteAcctoBusLcList` item with an additional `collected_at`
+/// RFC 3339 timestamp. Malformed lines are skipped and counted.
+pub fn load_observations(dir: &Path) -> Result<Vec<VehicleObservation>> {
+    let mut observations = Vec::new();
+    let mut skipped = 0usize;
+
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<Value>(line)
+                .ok()
+                .and_then(|v| parse_observation(&v))
+            {
+                Some(obs) => observations.push(obs),
+                None => skipped += 1,
+            }
+        }
+    }
+
+    if skipped > 0 {
+        warn!(skipped, "Skipped {} malformed observation lines.", skipped);
+    }
+
+    Ok(observations)
+}
+
+fn parse_observation(item: &Value) -> Option<VehicleObservation> {
+    let observed_at = DateTime::parse_from_rfc3339(item["collected_at"].as_str()?).ok()?;
+
+    let as_f64 = |v: &Value| v.as_f64().or_else(|| v.as_str()?.parse().ok());
+    let node_ord = item["nodeord"]
+        .as_i64()
+        .or_else(|| item["nodeord"].as_str()?.parse().ok())?;
+
+    let route_id = item["routeid"].as_str()?.to_string();
+    let vehicle_no = parse_flexible_string(&item["vehicleno"]);
+    if vehicle_no == "UNKNOWN" {
+        return None;
+    }
+
+    Some(VehicleObservation {
+        route_id,
+        vehicle_no,
+        node_id: item["nodeid"].as_str().unwrap_or("").to_string(),
+        node_ord,
+        gps_lat: as_f64(&item["gpslati"])?,
+        gps_long: as_f64(&item["gpslong"])?,
+        observed_at,
+    })
+}
+
+/// Loads the derived GeoJSON of a route from `derived_routes/`.
+pub fn load_derived_route(derived_dir: &Path, route_id: &str) -> Result<DerivedFeature> {
+    let path = derived_dir.join(format!("{}.geojson", route_id));
+    let content = fs::read_to_string(&path)?;
+    let collection: DerivedFeatureCollection = serde_json::from_str(&content)?;
+
+    collection
+        .features
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("{:?} contains no features", path))
+}
+
+/// Groups observations by route ID.
+pub fn group_by_route(
+    observations: Vec<VehicleObservation>,
+) -> HashMap<String, Vec<VehicleObservation>> {
+    let mut grouped: HashMap<String, Vec<VehicleObservation>> = HashMap::new();
+    for obs in observations {
+        grouped.entry(obs.route_id.clone()).or_default().push(obs);
+    }
+    grouped
+}
+
+/// Builds the service calendar covering every observation, used to map dates to
+/// schedule day types (public holidays run the weekend timetable).
+pub fn service_calendar_for(observations: &[VehicleObservation]) -> ServiceCalendar {
+    let years = observations.iter().map(|o| o.observed_at.year());
+    let first = years.clone().min().unwrap_or_default();
+    let last = years.max().unwrap_or_default();
+
+    ServiceCalendar::new(&CalendarConfig::default(), first..=last)
+}
+
+// ============================================================================
+// Schedules
+// ============================================================================
+
+/// Loads the schedule JSON written by the schedule processor.
+pub fn load_schedule(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Departures per direction of one day type key of a schedule (no fallback), sorted.
+pub fn scheduled_trips(
+    schedule: &Value,
+    day_key: &str,
+) -> HashMap<String, Vec<ScheduledDeparture>> {
+    let mut result: HashMap<String, Vec<ScheduledDeparture>> = HashMap::new();
+
+    let Some(table) = schedule["schedule"][day_key].as_object() else {
+        return result;
+    };
+
+    for (hour, by_direction) in table {
+        let Some(by_direction) = by_direction.as_object() else {
+            continue;
+        };
+        for (direction, minutes) in by_direction {
+            for m in minutes.as_array().into_iter().flatten() {
+                let Some(time) = m["minute"]
+                    .as_str()
+                    .and_then(|min| ServiceTime::from_hour_minute(hour, min).ok())
+                else {
+                    continue;
+                };
+
+                let mut attributes = m.as_object().cloned().unwrap_or_default();
+                attributes.remove("minute");
+                let note_id = attributes
+                    .remove("noteId")
+                    .and_then(|v| v.as_str().map(str::to_string));
+
+                result
+                    .entry(direction.clone())
+                    .or_default()
+                    .push(ScheduledDeparture {
+                        time,
+                        note_id,
+                        attributes,
+                    });
+            }
+        }
+    }
+
+    for trips in result.values_mut() {
+        trips.sort_by_key(|t| t.time);
+    }
+
+    result
+}
+
+/// Maps the route's terminal stops to schedule directions.
+///
+/// Returns `(stop index, direction)` pairs for the origin terminal and, for routes
+/// with a turning point, the first stop of the return leg.
+pub fn terminal_directions(route: &DerivedFeature, schedule: &Value) -> Vec<(usize, String)> {
+    let stops = &route.properties.stops;
+    let directions: Vec<String> = schedule["directions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d.as_str().map(str::to_string))
+        .collect();
+
+    if stops.is_empty() || directions.is_empty() {
+        return Vec::new();
+    }
+
+    let mut terminals = vec![0];
+    if let Some(i) = (1..stops.len()).find(|&i| stops[i].up_down != stops[i - 1].up_down) {
+        terminals.push(i);
+    }
+
+    // The origin terminal is listed first in the schedule description ("A ↔ B").
+    let origin = schedule["description"]
+        .as_str()
+        .and_then(|d| d.split('↔').next())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+
+    let normalize = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    let matches = |stop: &str, dir: &str| {
+        let (stop, dir) = (normalize(stop), normalize(dir));
+        !dir.is_empty() && (stop.contains(&dir) || dir.contains(&stop))
+    };
+
+    let mut result: Vec<(usize, String)> = Vec::new();
+    for (leg, &idx) in terminals.iter().enumerate() {
+        let by_name = directions
+            .iter()
+            .find(|d| matches(&stops[idx].name, d))
+            .cloned();
+
+        let fallback = || {
+            if leg == 0 && directions.contains(&origin) {
+                Some(origin.clone())
+            } else {
+                directions
+                    .iter()
+                    .find(|d| !result.iter().any(|(_, taken)| taken == *d) && **d != origin)
+                    .cloned()
+            }
+        };
+
+        if let Some(dir) = by_name.or_else(fallback)
+            && !result.iter().any(|(_, taken)| *taken == dir)
+        {
+            result.push((idx, dir));
+        }
+    }
+
+    result
+}
+
+// ============================================================================
+// Trip Reconstruction
+// ============================================================================
+
+/// Distance along the route path (meters) of every stop, via `stop_to_coord`.
+pub fn stop_distances(route: &DerivedFeature) -> Vec<f64> {
+    let cumulative = cumulative_distances(&route.geometry.coordinates);
+    route
+        .properties
+        .indices
+        .stop_to_coord
+        .iter()
+        .map(|&idx| cumulative.get(idx).copied().unwrap_or(0.0))
+        .collect()
+}
+
+/// Reconstructs stop visits per vehicle trip from a route's observations.
+///
+/// TAGO reports the order (`nodeord`) of the last stop a vehicle passed. Each
+/// observation is placed on the route path between that stop and the next one, and
+/// the time a stop was passed is interpolated between the two observations whose
+/// positions bracket it. A vehicle dwelling at a stop is treated as passing it when
+/// it is last seen there, so terminal visits are departure times. A vehicle starts a new trip when its stop order goes back
+/// or when it has not been seen for `TRIP_MAX_GAP_SECS`.
+pub fn reconstruct_trips(
+    route: &DerivedFeature,
+    observations: &[VehicleObservation],
+) -> Vec<VehicleTrip> {
+    let coords = &route.geometry.coordinates;
+    let cumulative = cumulative_distances(coords);
+    let stop_to_coord = &route.properties.indices.stop_to_coord;
+    let stop_dist = stop_distances(route);
+
+    let stops = &route.properties.stops;
+    let ord_to_idx: HashMap<i64, usize> =
+        stops.iter().enumerate().map(|(i, s)| (s.ord, i)).collect();
+
+    let mut by_vehicle: HashMap<&str, Vec<&VehicleObservation>> = HashMap::new();
+    for obs in observations {
+        by_vehicle.entry(&obs.vehicle_no).or_default().push(obs);
+    }
+
+    let mut trips = Vec::new();
+
+    for (vehicle_no, mut list) in by_vehicle {
+        list.sort_by_key(|o| o.observed_at);
+        list.dedup_by_key(|o| o.observed_at);
+
+        // (stop index, progress along path, time) of the previous observation
+        let mut prev: Option<(usize, f64, DateTime<_>)> = None;
+        let mut visits: Vec<StopVisit> = Vec::new();
+
+        for obs in list {
+            // Fall back to the node ID when the order does not match the derived route.
+            let Some(stop_idx) = ord_to_idx
+                .get(&obs.node_ord)
+                .copied()
+                .or_else(|| stops.iter().position(|s| s.id == obs.node_id))
+            else {
+                continue;
+            };
+
+            // Place the vehicle between its last passed stop and the next one.
+            let lower = stop_dist[stop_idx];
+            let upper = stop_dist.get(stop_idx + 1).copied().unwrap_or(lower);
+            let progress = stop_to_coord
+                .get(stop_idx + 1)
+                .and_then(|&to| {
+                    locate_on_polyline(
+                        (obs.gps_long, obs.gps_lat),
+                        coords,
+                        &cumulative,
+                        stop_to_coord[stop_idx],
+                        to,
+                    )
+                })
+                .filter(|&(_, offset)| offset <= TRIP_MAX_OFFSET_M)
+                .map_or(lower, |(along, _)| along.clamp(lower, upper));
+
+            let new_trip = match prev {
+                Some((prev_idx, _, prev_time)) => {
+                    stop_idx < prev_idx
+                        || (obs.observed_at - prev_time).num_seconds() > TRIP_MAX_GAP_SECS
+                }
+                None => true,
+            };
+
+            if new_trip {
+                if visits.len() >= 2 {
+                    trips.push(VehicleTrip {
+                        vehicle_no: vehicle_no.to_string(),
+                        visits: std::mem::take(&mut visits),
+                    });
+                }
+                visits.clear();
+                prev = Some((stop_idx, progress, obs.observed_at));
+                continue;
+            }
+
+            let (_, prev_progress, prev_time) = prev.unwrap();
+
+            // GPS noise may move a vehicle slightly backwards; progress never decreases.
+            let progress = progress.max(prev_progress);
+
+            if progress > prev_progress {
+                let span = (obs.observed_at - prev_time).num_milliseconds() as f64;
+                for (k, &d) in stop_dist.iter().enumerate() {
+                    if d >= prev_progress && d < progress {
+                        let ratio = (d - prev_progress) / (progress - prev_progress);
+                        let offset = chrono::Duration::milliseconds((span * ratio) as i64);
+                        visits.push(StopVisit {
+                            stop_idx: k,
+                            visited_at: prev_time + offset,
+                        });
+                    }
+                }
+            }
+
+            prev = Some((stop_idx, progress, obs.observed_at));
+        }
+
+        if visits.len() >= 2 {
+            trips.push(VehicleTrip {
+                vehicle_no: vehicle_no.to_string(),
+                visits,
+            });
+        }
+    }
+
+    trips.sort_by_key(|t| t.visits.first().map(|v| v.visited_at));
+    trips
+}
@@ -0,0 +1,166 @@
+// src/analysis/model.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::schedule::service_time::ServiceTime;
+
+// ============================================================================
+// Input Models (Read from observations/)
+// ============================================================================
+
+/// A single archived vehicle position, as returned by `getRouteAcctoBusLcList`
+/// plus the time at which it was collected.
+#[derive(Debug, Clone)]
+pub struct VehicleObservation {
+    pub route_id: String,
+    pub vehicle_no: String,
+    pub node_id: String,
+    pub node_ord: i64,
+    pub gps_lat: f64,
+    pub gps_long: f64,
+    pub observed_at: DateTime<FixedOffset>,
+}
+
+// ============================================================================
+// Reconstructed Models
+// ============================================================================
+
+/// Estimated time at which a vehicle passed a stop of its route.
+#[derive(Debug, Clone)]
+pub struct StopVisit {
+    /// Index of the stop in the derived route's stop list
+    pub stop_idx: usize,
+    pub visited_at: DateTime<FixedOffset>,
+}
+
+/// One pass of a vehicle over (part of) its route's stop sequence.
+#[derive(Debug, Clone)]
+pub struct VehicleTrip {
+    pub vehicle_no: String,
+    pub visits: Vec<StopVisit>,
+}
+
+/// A departure from a schedule file
+#[derive(Debug, Clone)]
+pub struct ScheduledDeparture {
+    pub time: ServiceTime,
+    pub note_id: Option<String>,
+    // Trip attributes interpreted from the note (e.g., "via", "lowFloor")
+    pub attributes: Map<String, Value>,
+}
+
+// ============================================================================
+// Output Models (Saved to travel_times/)
+// ============================================================================
+
+/// Travel time distributions for every stop pair of a route
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TravelTimeFile {
+    pub route_id: String,
+    pub route_no: String,
+    pub generated_at: String,
+    pub observation_count: usize,
+    pub trip_count: usize,
+    pub segments: Vec<SegmentTravelTime>,
+}
+
+/// Travel time between two consecutive stops
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentTravelTime {
+    pub from_stop: String,
+    pub to_stop: String,
+    pub from_ord: i64,
+    pub to_ord: i64,
+    pub distance: f64,
+    pub overall: Option<TravelTimeStats>,
+    // Day type -> Hour ("07") -> Stats
+    pub by_day_type: BTreeMap<String, BTreeMap<String, TravelTimeStats>>,
+}
+
+/// Summary of a travel time distribution (all values in seconds)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TravelTimeStats {
+    pub samples: usize,
+    pub min: f64,
+    pub p10: f64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+// ============================================================================
+// Output Models (Saved to adherence/)
+// ============================================================================
+
+/// Schedule adherence report across all analyzed routes
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdherenceReport {
+    pub generated_at: String,
+    pub early_threshold_secs: i64,
+    pub late_threshold_secs: i64,
+    pub summary: AdherenceSummary,
+    pub routes: Vec<RouteAdherence>,
+}
+
+/// Adherence of a single route number, per observed day
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteAdherence {
+    pub route_no: String,
+    pub route_ids: Vec<String>,
+    pub summary: AdherenceSummary,
+    pub days: Vec<DayAdherence>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayAdherence {
+    pub date: String,
+    pub day_type: String,
+    pub summary: AdherenceSummary,
+    // Observed departures that matched no scheduled trip
+    pub unscheduled: usize,
+    pub trips: Vec<TripAdherence>,
+}
+
+/// A scheduled terminal departure and the observed departure matched to it
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TripAdherence {
+    pub direction: String,
+    pub scheduled: String,        // "HH:MM"
+    pub observed: Option<String>, // "HH:MM:SS"
+    pub deviation_secs: Option<i64>,
+    pub vehicle_no: Option<String>,
+    pub status: TripStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TripStatus {
+    Early,
+    OnTime,
+    Late,
+    Missed,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdherenceSummary {
+    pub scheduled: usize,
+    pub early: usize,
+    pub on_time: usize,
+    pub late: usize,
+    pub missed: usize,
+    // Share of scheduled trips that departed on time (0.0 - 1.0)
+    pub on_time_rate: f64,
+}
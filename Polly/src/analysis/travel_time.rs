@@ -0,0 +1,194 @@
+// src/analysis/travel_time.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{Local, Timelike};
+use tracing::{info, warn};
+
+use crate::analysis::model::{SegmentTravelTime, TravelTimeFile, TravelTimeStats, VehicleTrip};
+use crate::analysis::{
+    group_by_route, load_derived_route, load_observations, reconstruct_trips, service_calendar_for,
+    stop_distances,
+};
+use crate::calendar::model::ServiceCalendar;
+use crate::config::{TRAVEL_TIME_MAX_SEGMENT_SECS, TRAVEL_TIME_MIN_BUCKET_SAMPLES};
+use crate::route::model::DerivedFeature;
+use crate::utils::ensure_dir;
+
+/// Estimates stop-to-stop travel time distributions from archived vehicle positions.
+///
+/// 1. Loads every observation from `input_dir` and groups them by route.
+/// 2. Reconstructs stop visits per vehicle trip using the route's derived geometry.
+/// 3. Collects travel times between consecutive stops, bucketed by day type and hour.
+/// 4. Saves percentile summaries per route to `travel_times/<route_id>.json`.
+///
+pub async fn run_travel_times(
+    input_dir: PathBuf,
+    routes_dir: PathBuf,
+    specific_route: Option<String>,
+    output_dir: PathBuf,
+) -> Result<()> {
+    let derived_dir = routes_dir.join("derived_routes");
+    let travel_dir = output_dir.join("travel_times");
+
+    ensure_dir(&travel_dir)?;
+
+    info!("[Loading observations from {:?}]", input_dir);
+
+    let observations = load_observations(&input_dir)?;
+    info!("Loaded {} observations.", observations.len());
+
+    let calendar = service_calendar_for(&observations);
+    let grouped = group_by_route(observations);
+    let mut route_ids: Vec<_> = grouped.keys().cloned().collect();
+    route_ids.sort();
+
+    info!("[Estimating travel times for {} routes]", route_ids.len());
+
+    for route_id in route_ids {
+        let route = match load_derived_route(&derived_dir, &route_id) {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(route = %route_id, "Skipping {}: {}", route_id, e);
+                continue;
+            }
+        };
+
+        if let Some(ref target) = specific_route
+            && route.properties.route_no != *target
+            && route.properties.route_id != *target
+        {
+            continue;
+        }
+
+        let route_obs = &grouped[&route_id];
+        let trips = reconstruct_trips(&route, route_obs);
+        let file = build_travel_time_file(&route, route_obs.len(), &trips, &calendar);
+
+        let path = travel_dir.join(format!("{}.json", route_id));
+        fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+
+        info!(
+            route = %route_id,
+            "✓ {} ({}): {} trips, {} segments with data",
+            route.properties.route_no,
+            route_id,
+            trips.len(),
+            file.segments.iter().filter(|s| s.overall.is_some()).count()
+        );
+    }
+
+    info!("✓ Travel time estimation complete.");
+
+    Ok(())
+}
+
+fn build_travel_time_file(
+    route: &DerivedFeature,
+    observation_count: usize,
+    trips: &[VehicleTrip],
+    calendar: &ServiceCalendar,
+) -> TravelTimeFile {
+    let stops = &route.properties.stops;
+    let stop_dist = stop_distances(route);
+    let segment_count = stops.len().saturating_sub(1);
+
+    // Segment index -> samples (seconds), overall and by (day type, hour)
+    let mut overall: Vec<Vec<f64>> = vec![Vec::new(); segment_count];
+    let mut bucketed: Vec<BTreeMap<(String, String), Vec<f64>>> =
+        vec![BTreeMap::new(); segment_count];
+
+    for trip in trips {
+        for pair in trip.visits.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            if b.stop_idx != a.stop_idx + 1 {
+                continue;
+            }
+
+            let secs = (b.visited_at - a.visited_at).num_milliseconds() as f64 / 1000.0;
+            if secs <= 0.0 || secs > TRAVEL_TIME_MAX_SEGMENT_SECS {
+                continue;
+            }
+
+            let day_type = calendar.day_type(a.visited_at.date_naive());
+            let hour = format!("{:02}", a.visited_at.hour());
+
+            overall[a.stop_idx].push(secs);
+            bucketed[a.stop_idx]
+                .entry((day_type, hour))
+                .or_default()
+                .push(secs);
+        }
+    }
+
+    let segments = (0..segment_count)
+        .map(|i| {
+            let mut by_day_type: BTreeMap<String, BTreeMap<String, TravelTimeStats>> =
+                BTreeMap::new();
+            for ((day_type, hour), samples) in &mut bucketed[i] {
+                if samples.len() < TRAVEL_TIME_MIN_BUCKET_SAMPLES {
+                    continue;
+                }
+                if let Some(stats) = TravelTimeStats::from_samples(samples) {
+                    by_day_type
+                        .entry(day_type.clone())
+                        .or_default()
+                        .insert(hour.clone(), stats);
+                }
+            }
+
+            SegmentTravelTime {
+                from_stop: stops[i].id.clone(),
+                to_stop: stops[i + 1].id.clone(),
+                from_ord: stops[i].ord,
+                to_ord: stops[i + 1].ord,
+                distance: ((stop_dist[i + 1] - stop_dist[i]) * 10.0).round() / 10.0,
+                overall: TravelTimeStats::from_samples(&mut overall[i]),
+                by_day_type,
+            }
+        })
+        .collect();
+
+    TravelTimeFile {
+        route_id: route.properties.route_id.clone(),
+        route_no: route.properties.route_no.clone(),
+        generated_at: Local::now().to_rfc3339(),
+        observation_count,
+        trip_count: trips.len(),
+        segments,
+    }
+}
+
+impl TravelTimeStats {
+    /// Summarizes samples (seconds). Returns `None` when there are no samples.
+    pub fn from_samples(samples: &mut [f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_by(|a, b| a.total_cmp(b));
+
+        let round = |v: f64| (v * 10.0).round() / 10.0;
+        Some(Self {
+            samples: samples.len(),
+            min: round(samples[0]),
+            p10: round(percentile(samples, 0.10)),
+            p25: round(percentile(samples, 0.25)),
+            median: round(percentile(samples, 0.50)),
+            p75: round(percentile(samples, 0.75)),
+            p90: round(percentile(samples, 0.90)),
+            max: round(samples[samples.len() - 1]),
+        })
+    }
+}
+
+/// Linearly interpolated percentile of sorted samples (`q` in 0.0..=1.0)
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
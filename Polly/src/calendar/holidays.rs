@@ -0,0 +1,330 @@
+// src/calendar/holidays.rs
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::calendar::model::Holiday;
+
+// ============================================================================
+// Lunar Holiday Table
+// ============================================================================
+
+// Solar dates (month, day) of lunar 1/1 (Seollal), 4/8 (Buddha's Birthday) and
+// 8/15 (Chuseok), computed from new moons and principal solar terms at KST (UTC+9),
+// which is how the Korea Astronomy and Space Science Institute defines the calendar.
+type MonthDay = (u32, u32);
+
+// (Year, Seollal, Buddha's Birthday, Chuseok)
+const LUNAR_HOLIDAYS: &[(i32, MonthDay, MonthDay, MonthDay)] = &[
+    (2016, (2, 8), (5, 14), (9, 15)),
+    (2017, (1, 28), (5, 3), (10, 4)),
+    (2018, (2, 16), (5, 22), (9, 24)),
+    (2019, (2, 5), (5, 12), (9, 13)),
+    (2020, (1, 25), (4, 30), (10, 1)),
+    (2021, (2, 12), (5, 19), (9, 21)),
+    (2022, (2, 1), (5, 8), (9, 10)),
+    (2023, (1, 22), (5, 27), (9, 29)),
+    (2024, (2, 10), (5, 15), (9, 17)),
+    (2025, (1, 29), (5, 5), (10, 6)),
+    (2026, (2, 17), (5, 24), (9, 25)),
+    (2027, (2, 7), (5, 13), (9, 15)),
+    (2028, (1, 27), (5, 2), (10, 3)),
+    (2029, (2, 13), (5, 20), (9, 22)),
+    (2030, (2, 3), (5, 9), (9, 12)),
+    (2031, (1, 23), (5, 28), (10, 1)),
+    (2032, (2, 11), (5, 16), (9, 19)),
+    (2033, (1, 31), (5, 6), (9, 8)),
+    (2034, (2, 19), (5, 25), (9, 27)),
+    (2035, (2, 8), (5, 15), (9, 16)),
+    (2036, (1, 28), (5, 3), (10, 4)),
+    (2037, (2, 15), (5, 22), (9, 24)),
+    (2038, (2, 4), (5, 11), (9, 13)),
+    (2039, (1, 24), (4, 30), (10, 2)),
+    (2040, (2, 12), (5, 18), (9, 21)),
+    (2041, (2, 1), (5, 7), (9, 10)),
+    (2042, (1, 22), (5, 26), (9, 28)),
+    (2043, (2, 10), (5, 16), (9, 17)),
+];
+
+/// Substitute holiday rule applied when a holiday overlaps a weekend or another holiday
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubstituteRule {
+    None,
+    // Seollal and Chuseok: overlaps with Sunday or another holiday
+    SundayOrHoliday,
+    // Children's Day, national days, Buddha's Birthday, Christmas:
+    // overlaps with Saturday, Sunday or another holiday
+    WeekendOrHoliday,
+}
+
+/// Returns whether the lunar holiday table covers `year`.
+pub fn is_supported_year(year: i32) -> bool {
+    LUNAR_HOLIDAYS.iter().any(|(y, ..)| *y == year)
+}
+
+/// Lists Korean public holidays (관공서의 공휴일) of a year, including substitute
+/// holidays (대체공휴일). Elections and one-off temporary holidays are not included.
+///
+/// Returns an empty list when the lunar holiday table does not cover `year`.
+pub fn korean_holidays(year: i32) -> Vec<Holiday> {
+    let Some(&(_, seollal, buddha, chuseok)) = LUNAR_HOLIDAYS.iter().find(|(y, ..)| *y == year)
+    else {
+        return Vec::new();
+    };
+
+    let date = |(m, d): MonthDay| NaiveDate::from_ymd_opt(year, m, d).unwrap();
+
+    // Substitute holidays for national days started in 2021-08, and for Buddha's
+    // Birthday and Christmas in 2023-05.
+    let national_rule = |d: NaiveDate| {
+        if d >= NaiveDate::from_ymd_opt(2021, 8, 1).unwrap() {
+            SubstituteRule::WeekendOrHoliday
+        } else {
+            SubstituteRule::None
+        }
+    };
+    let late_rule = |d: NaiveDate| {
+        if d >= NaiveDate::from_ymd_opt(2023, 5, 1).unwrap() {
+            SubstituteRule::WeekendOrHoliday
+        } else {
+            SubstituteRule::None
+        }
+    };
+
+    // Each group is a set of consecutive days sharing one substitute holiday.
+    let mut groups: Vec<(&str, Vec<NaiveDate>, SubstituteRule)> = Vec::new();
+
+    let new_year = date((1, 1));
+    groups.push(("신정", vec![new_year], SubstituteRule::None));
+
+    let seollal = date(seollal);
+    groups.push((
+        "설날",
+        vec![
+            seollal - Duration::days(1),
+            seollal,
+            seollal + Duration::days(1),
+        ],
+        SubstituteRule::SundayOrHoliday,
+    ));
+
+    for (name, md) in [
+        ("삼일절", (3, 1)),
+        ("광복절", (8, 15)),
+        ("개천절", (10, 3)),
+        ("한글날", (10, 9)),
+    ] {
+        let d = date(md);
+        groups.push((name, vec![d], national_rule(d)));
+    }
+
+    let children = date((5, 5));
+    groups.push((
+        "어린이날",
+        vec![children],
+        if year >= 2014 {
+            SubstituteRule::WeekendOrHoliday
+        } else {
+            SubstituteRule::None
+        },
+    ));
+
+    let buddha = date(buddha);
+    groups.push(("부처님오신날", vec![buddha], late_rule(buddha)));
+
+    groups.push(("현충일", vec![date((6, 6))], SubstituteRule::None));
+
+    let chuseok = date(chuseok);
+    groups.push((
+        "추석",
+        vec![
+            chuseok - Duration::days(1),
+            chuseok,
+            chuseok + Duration::days(1),
+        ],
+        SubstituteRule::SundayOrHoliday,
+    ));
+
+    let christmas = date((12, 25));
+    groups.push(("성탄절", vec![christmas], late_rule(christmas)));
+
+    let mut holidays: Vec<Holiday> = groups
+        .iter()
+        .flat_map(|(name, days, _)| {
+            days.iter().map(|d| Holiday {
+                date: *d,
+                name: name.to_string(),
+            })
+        })
+        .collect();
+
+    // Assign substitute holidays in date order so they never collide with each other.
+    // Two holidays on the same day yield a single substitute, for the later group.
+    groups.sort_by_key(|(_, days, _)| days[0]);
+    let mut claimed: Vec<NaiveDate> = Vec::new();
+    for (name, days, rule) in &groups {
+        let overlaps = *rule != SubstituteRule::None
+            && days.iter().any(|d| {
+                let weekend = match rule {
+                    SubstituteRule::SundayOrHoliday => d.weekday() == Weekday::Sun,
+                    _ => matches!(d.weekday(), Weekday::Sat | Weekday::Sun),
+                };
+                weekend || claimed.contains(d)
+            });
+        claimed.extend(days.iter().copied());
+
+        if !overlaps {
+            continue;
+        }
+
+        // The first day after the holiday that is neither a weekend nor a holiday
+        let mut candidate = *days.last().unwrap() + Duration::days(1);
+        while matches!(candidate.weekday(), Weekday::Sat | Weekday::Sun)
+            || holidays.iter().any(|h| h.date == candidate)
+        {
+            candidate += Duration::days(1);
+        }
+
+        holidays.push(Holiday {
+            date: candidate,
+            name: format!("대체공휴일({})", name),
+        });
+    }
+
+    holidays.sort_by_key(|h| h.date);
+    holidays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md(d: NaiveDate) -> String {
+        d.format("%m-%d").to_string()
+    }
+
+    /// Dates ("MM-DD") of the holidays named `name` in `year`
+    fn dates_of(year: i32, name: &str) -> Vec<String> {
+        korean_holidays(year)
+            .iter()
+            .filter(|h| h.name == name)
+            .map(|h| md(h.date))
+            .collect()
+    }
+
+    /// Substitute holidays of `year` as "MM-DD name"
+    fn substitutes(year: i32) -> Vec<String> {
+        korean_holidays(year)
+            .iter()
+            .filter(|h| h.name.starts_with("대체공휴일"))
+            .map(|h| format!("{} {}", md(h.date), h.name))
+            .collect()
+    }
+
+    #[test]
+    fn lunar_holidays_fall_on_their_published_dates() {
+        assert_eq!(dates_of(2024, "설날"), ["02-09", "02-10", "02-11"]);
+        assert_eq!(dates_of(2024, "추석"), ["09-16", "09-17", "09-18"]);
+        assert_eq!(dates_of(2025, "부처님오신날"), ["05-05"]);
+        assert_eq!(dates_of(2025, "추석"), ["10-05", "10-06", "10-07"]);
+        assert_eq!(dates_of(2026, "설날"), ["02-16", "02-17", "02-18"]);
+        assert_eq!(dates_of(2026, "추석"), ["09-24", "09-25", "09-26"]);
+    }
+
+    #[test]
+    fn lunar_table_is_consistent() {
+        for &(year, seollal, buddha, chuseok) in LUNAR_HOLIDAYS {
+            let date = |(m, d): MonthDay| NaiveDate::from_ymd_opt(year, m, d).unwrap();
+            let (seollal, buddha, chuseok) = (date(seollal), date(buddha), date(chuseok));
+
+            // Lunar new year falls between 1/21 and 2/20
+            assert!(
+                (date((1, 21))..=date((2, 20))).contains(&seollal),
+                "{}",
+                year
+            );
+            // 3 months 7 days and 7 months 14 days later (months of 29 or 30 days), plus one
+            // month when a leap month comes between
+            let to_buddha = (buddha - seollal).num_days();
+            let to_chuseok = (chuseok - seollal).num_days();
+            assert!(
+                (94..=97).contains(&to_buddha) || (123..=127).contains(&to_buddha),
+                "{}: {} days to Buddha's Birthday",
+                year,
+                to_buddha
+            );
+            assert!(
+                (217..=224).contains(&to_chuseok) || (246..=254).contains(&to_chuseok),
+                "{}: {} days to Chuseok",
+                year,
+                to_chuseok
+            );
+        }
+        assert!(is_supported_year(2016) && is_supported_year(2043));
+        assert!(korean_holidays(2015).is_empty());
+    }
+
+    #[test]
+    fn substitutes_before_the_2021_rules_cover_only_seollal_chuseok_and_children() {
+        // Seollal ends on a Sunday, and Chuseok's first day is National Foundation Day.
+        assert_eq!(
+            substitutes(2017),
+            ["01-30 대체공휴일(설날)", "10-06 대체공휴일(추석)"]
+        );
+    }
+
+    #[test]
+    fn buddhas_birthday_on_a_saturday_moves_to_monday() {
+        // 2023-05-27 is a Saturday; Chuseok ending on a Saturday gets no substitute.
+        assert_eq!(
+            substitutes(2023),
+            ["01-24 대체공휴일(설날)", "05-29 대체공휴일(부처님오신날)"]
+        );
+    }
+
+    #[test]
+    fn temporary_holidays_are_not_listed() {
+        // 2024-10-01 (Armed Forces Day) was a one-off holiday, not a substitute.
+        assert!(korean_holidays(2024).iter().all(|h| md(h.date) != "10-01"));
+        assert_eq!(
+            substitutes(2024),
+            ["02-12 대체공휴일(설날)", "05-06 대체공휴일(어린이날)"]
+        );
+    }
+
+    #[test]
+    fn colliding_holidays_share_one_substitute() {
+        // Children's Day and Buddha's Birthday on 05-05, Chuseok starting on a Sunday
+        assert_eq!(
+            substitutes(2025),
+            [
+                "03-03 대체공휴일(삼일절)",
+                "05-06 대체공휴일(부처님오신날)",
+                "10-08 대체공휴일(추석)",
+            ]
+        );
+    }
+
+    #[test]
+    fn holidays_on_weekends_move_to_the_next_weekday() {
+        // Seollal overlaps a Sunday; national days and Christmas fall on weekends.
+        assert_eq!(
+            substitutes(2027),
+            [
+                "02-09 대체공휴일(설날)",
+                "08-16 대체공휴일(광복절)",
+                "10-04 대체공휴일(개천절)",
+                "10-11 대체공휴일(한글날)",
+                "12-27 대체공휴일(성탄절)",
+            ]
+        );
+        // Children's Day on a Saturday, Buddha's Birthday and Chuseok's last day on Sundays
+        assert_eq!(
+            substitutes(2029),
+            [
+                "05-07 대체공휴일(어린이날)",
+                "05-21 대체공휴일(부처님오신날)",
+                "09-24 대체공휴일(추석)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+// src/calendar/mod.rs
+
+mod holidays;
+pub mod model;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use tracing::{info, warn};
+
+use crate::calendar::holidays::{is_supported_year, korean_holidays};
+use crate::calendar::model::{
+    CalendarConfig, Holiday, ServiceCalendar, ServiceCalendarFile, ServiceDay,
+};
+use crate::schedule::day_type;
+use crate::utils::ensure_dir;
+
+/// Main entry point for the service calendar generator.
+///
+/// Resolves the schedule day type of every date from `from` for `days` days, using
+/// Korean public holidays (including lunar and substitute holidays) and the vacation
+/// periods and extra holidays from `vacations_path`, and saves it to `calendar.json`.
+///
+pub async fn run(
+    from: Option<NaiveDate>,
+    days: u32,
+    vacations_path: Option<PathBuf>,
+    output_dir: PathBuf,
+) -> Result<()> {
+    ensure_dir(&output_dir)?;
+
+    let config = match vacations_path {
+        Some(path) => load_config(&path)?,
+        None => CalendarConfig::default(),
+    };
+
+    let from = from.unwrap_or_else(|| Local::now().date_naive());
+    let to = from + Duration::days(days.saturating_sub(1) as i64);
+
+    for year in from.year()..=to.year() {
+        if !is_supported_year(year) {
+            warn!(
+                year,
+                "Lunar holidays for {} are unknown; only fixed-date holidays apply.", year
+            );
+        }
+    }
+
+    let calendar = ServiceCalendar::new(&config, from.year()..=to.year());
+
+    let mut entries = BTreeMap::new();
+    let mut date = from;
+    while date <= to {
+        entries.insert(
+            date.format("%Y-%m-%d").to_string(),
+            calendar.service_day(date),
+        );
+        date += Duration::days(1);
+    }
+
+    let holiday_count = entries.values().filter(|d| d.holiday.is_some()).count();
+
+    let file = ServiceCalendarFile {
+        generated_at: Local::now().to_rfc3339(),
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        days: entries,
+    };
+
+    let path = output_dir.join("calendar.json");
+    fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+
+    info!(
+        "✓ Saved service calendar ({} to {}, {} holidays) to {:?}",
+        file.from, file.to, holiday_count, path
+    );
+
+    Ok(())
+}
+
+/// Loads the calendar configuration (JSON).
+pub fn load_config(path: &Path) -> Result<CalendarConfig> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read calendar config {:?}", path))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+impl ServiceCalendar {
+    /// Builds a calendar with the public holidays of `years` plus configured holidays.
+    pub fn new(config: &CalendarConfig, years: RangeInclusive<i32>) -> Self {
+        let mut holidays: HashMap<NaiveDate, Holiday> = HashMap::new();
+
+        for year in years {
+            for h in korean_holidays(year) {
+                holidays.entry(h.date).or_insert(h);
+            }
+        }
+
+        for extra in &config.extra_holidays {
+            holidays.entry(extra.date).or_insert(Holiday {
+                date: extra.date,
+                name: extra.name.clone(),
+            });
+        }
+
+        Self {
+            holidays,
+            vacations: config.vacations.clone(),
+        }
+    }
+
+    /// Resolves the service day of a date.
+    ///
+    /// Vacation periods take precedence (`vacation_weekday` / `vacation_weekend`), then
+    /// public holidays (`holiday`), then the day of the week (`saturday`, `sunday`,
+    /// `weekday`). Consumers resolve missing timetables with `day_type::fallback_chain`.
+    pub fn service_day(&self, date: NaiveDate) -> ServiceDay {
+        let holiday = self.holidays.get(&date).map(|h| h.name.clone());
+        let vacation = self
+            .vacations
+            .iter()
+            .find(|v| v.start <= date && date <= v.end)
+            .map(|v| v.name.clone());
+
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let day_type = match (vacation.is_some(), holiday.is_some(), date.weekday()) {
+            (true, true, _) => day_type::VACATION_WEEKEND,
+            (true, false, _) if weekend => day_type::VACATION_WEEKEND,
+            (true, false, _) => day_type::VACATION_WEEKDAY,
+            (false, true, _) => day_type::HOLIDAY,
+            (false, false, Weekday::Sat) => day_type::SATURDAY,
+            (false, false, Weekday::Sun) => day_type::SUNDAY,
+            _ => day_type::WEEKDAY,
+        };
+
+        ServiceDay {
+            day_type: day_type.to_string(),
+            weekday: date.weekday().to_string(),
+            holiday,
+            vacation,
+        }
+    }
+
+    /// Shorthand for the schedule day type of a date.
+    pub fn day_type(&self, date: NaiveDate) -> String {
+        self.service_day(date).day_type
+    }
+}
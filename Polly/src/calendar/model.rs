@@ -0,0 +1,78 @@
+// src/calendar/model.rs
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Configuration (Read from --vacations)
+// ============================================================================
+
+/// Calendar configuration: school vacation periods and additional holidays
+/// (e.g., election days or temporary holidays announced by the government).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarConfig {
+    #[serde(default)]
+    pub vacations: Vec<VacationPeriod>,
+    #[serde(default)]
+    pub extra_holidays: Vec<ExtraHoliday>,
+}
+
+/// A school vacation period (inclusive)
+#[derive(Debug, Clone, Deserialize)]
+pub struct VacationPeriod {
+    pub name: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtraHoliday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+// ============================================================================
+// Calendar Models
+// ============================================================================
+
+/// A public holiday
+#[derive(Debug, Clone)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+/// Holidays and vacation periods used to resolve the service day type of a date
+pub struct ServiceCalendar {
+    pub holidays: HashMap<NaiveDate, Holiday>,
+    pub vacations: Vec<VacationPeriod>,
+}
+
+// ============================================================================
+// Output Models (Saved to calendar.json)
+// ============================================================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceCalendarFile {
+    pub generated_at: String,
+    pub from: String,
+    pub to: String,
+    // "YYYY-MM-DD" -> Service day
+    pub days: BTreeMap<String, ServiceDay>,
+}
+
+/// The schedule day type that applies to a date, and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDay {
+    pub day_type: String,
+    pub weekday: String, // "Mon" ... "Sun"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holiday: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vacation: Option<String>,
+}
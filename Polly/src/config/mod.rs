@@ -0,0 +1,480 @@
+// src/config/mod.rs
+
+pub mod model;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use toml::{Table, Value};
+use tracing::warn;
+
+use crate::config::model::PollyConfig;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+// Trip reconstruction from archived vehicle observations.
+// A gap longer than this between two observations of a vehicle starts a new trip.
+pub const TRIP_MAX_GAP_SECS: i64 = 20 * 60;
+// Observations further than this from the route path fall back to the last passed stop.
+pub const TRIP_MAX_OFFSET_M: f64 = 200.0;
+
+// Travel time estimation
+pub const TRAVEL_TIME_MAX_SEGMENT_SECS: f64 = 30.0 * 60.0;
+pub const TRAVEL_TIME_MIN_BUCKET_SAMPLES: usize = 3;
+
+// Schedule adherence: departures within [-EARLY, +LATE] seconds are on time,
+// and observed departures are only matched within the wider match window.
+pub const ADHERENCE_EARLY_SECS: i64 = 60;
+pub const ADHERENCE_LATE_SECS: i64 = 5 * 60;
+pub const ADHERENCE_MATCH_BEFORE_SECS: i64 = 10 * 60;
+pub const ADHERENCE_MATCH_AFTER_SECS: i64 = 30 * 60;
+
+// Service day: listed times before this (minutes after midnight) belong to the previous
+// service day (e.g., "00:20" after "23:50" is 24:20), and no trip runs past the maximum.
+pub const SERVICE_DAY_START_MINUTES: u32 = 3 * 60;
+pub const SERVICE_DAY_MAX_MINUTES: u32 = 30 * 60;
+
+// Schedule output: key timing stops listed per direction (including both terminals)
+pub const FEATURED_STOPS_PER_DIRECTION: usize = 6;
+
+// Headway summaries: time bands (name, from, to in service-day minutes), and regular
+// periods of at least this many departures whose headways differ by at most the tolerance.
+pub const HEADWAY_TIME_BANDS: &[(&str, u32, u32)] = &[
+    ("early", 3 * 60, 7 * 60),
+    ("am_peak", 7 * 60, 9 * 60),
+    ("midday", 9 * 60, 17 * 60),
+    ("pm_peak", 17 * 60, 19 * 60),
+    ("evening", 19 * 60, 30 * 60),
+];
+pub const HEADWAY_MIN_REGULAR_TRIPS: usize = 4;
+pub const HEADWAY_REGULAR_TOLERANCE_MINS: u32 = 1;
+
+// Stop-level timetables: segments without observed travel times are estimated from the
+// distance along the route at this average speed, plus a dwell time per intermediate stop.
+pub const TIMETABLE_DEFAULT_SPEED_KMH: f64 = 20.0;
+pub const TIMETABLE_DWELL_SECS: f64 = 15.0;
+
+// Isochrones: walking times are evaluated on a grid of at most this many cells.
+pub const ISOCHRONE_MAX_CELLS: usize = 4_000_000;
+
+// Walking transfers: stations with the same name at most this far apart are taken to be
+// the two sides of the street (an "opposite" transfer).
+pub const OPPOSITE_STOP_MAX_M: f64 = 150.0;
+
+// ============================================================================
+// Loading (polly.toml)
+// ============================================================================
+
+/// Configuration file read from the working directory when `--config` is not given
+pub const CONFIG_FILE: &str = "polly.toml";
+
+// Environment variables predating `polly.toml`, mapped to configuration keys
+const LEGACY_ENV_KEYS: &[(&str, &str)] = &[
+    ("DATA_GO_KR_SERVICE_KEY", "service_key"),
+    ("TAGO_API_URL", "endpoints.tago_route"),
+    ("TAGO_BUS_LOCATION_API_URL", "endpoints.tago_bus_location"),
+    ("TAGO_ARRIVAL_API_URL", "endpoints.tago_arrival"),
+    ("OSRM_API_URL", "endpoints.osrm"),
+];
+
+// Keys whose values are masked by `config show`
+const SECRET_KEYS: &[&str] = &["service_key"];
+
+/// The effective configuration and the layers it was built from
+pub struct LoadedConfig {
+    pub config: PollyConfig,
+    pub sources: Vec<String>,
+}
+
+/// Builds the effective configuration from layered sources, later layers winning:
+///
+/// 1. Built-in defaults.
+/// 2. The configuration file (`config_path`, or `polly.toml` if present).
+/// 3. The file's `[cities.<city_code>]` section for the selected city.
+/// 4. Environment variables: `POLLY_<KEY>` with `__` between sections
+///    (e.g., `POLLY_SCHEDULE__REQUEST_INTERVAL_MS=500`) and the legacy names
+///    such as `DATA_GO_KR_SERVICE_KEY` and `OSRM_API_URL`.
+/// 5. Command line overrides (`--set key=value` and processor flags).
+///
+/// The city is chosen before the layers are merged, by the last layer that sets
+/// `city_code`.
+pub fn load(config_path: Option<&Path>, cli: &[(String, String)]) -> Result<LoadedConfig> {
+    // Variables that are not valid Unicode cannot name a key or hold a value.
+    let env: BTreeMap<String, String> = env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .collect();
+    load_with_env(config_path, cli, &env)
+}
+
+/// `load` over the environment variables in `env`
+fn load_with_env(
+    config_path: Option<&Path>,
+    cli: &[(String, String)],
+    env: &BTreeMap<String, String>,
+) -> Result<LoadedConfig> {
+    let defaults = Table::try_from(PollyConfig::default())?;
+    let mut merged = defaults.clone();
+    let mut sources = vec!["defaults".to_string()];
+
+    // File layer
+    let file_path = match config_path {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(PathBuf::from(CONFIG_FILE)).filter(|p| p.exists()),
+    };
+    let mut cities = Table::new();
+    if let Some(path) = file_path {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {:?}", path))?;
+        let mut file: Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file {:?}", path))?;
+
+        if let Some(value) = file.remove("cities") {
+            cities = match value {
+                Value::Table(t) => t,
+                _ => bail!("'cities' in {:?} must be a table", path),
+            };
+        }
+        merge(&mut merged, file);
+        sources.push(path.display().to_string());
+    }
+
+    let env_values = env_overrides(&defaults, env);
+
+    // City section layer
+    let city_code = env_values
+        .iter()
+        .chain(cli)
+        .rev()
+        .find(|(k, _)| k == "city_code")
+        .map(|(_, v)| v.clone())
+        .or_else(|| {
+            merged
+                .get("city_code")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    if let Some(section) = cities.remove(&city_code) {
+        let Value::Table(section) = section else {
+            bail!("[cities.{}] must be a table", city_code);
+        };
+        merge(&mut merged, section);
+        sources.push(format!("[cities.{}]", city_code));
+    }
+
+    // Environment and command line layers
+    for (layer, values) in [
+        ("environment", &env_values),
+        ("command line", &cli.to_vec()),
+    ] {
+        if values.is_empty() {
+            continue;
+        }
+        for (key, raw) in values {
+            let value = typed_value(&defaults, key, raw)?;
+            set_path(&mut merged, key, value);
+        }
+        sources.push(layer.to_string());
+    }
+
+    let config: PollyConfig = Value::Table(merged)
+        .try_into()
+        .context("Invalid configuration")?;
+
+    Ok(LoadedConfig { config, sources })
+}
+
+/// Parses a `key=value` command line override.
+pub fn parse_override(text: &str) -> Result<(String, String)> {
+    let (key, value) = text
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected KEY=VALUE, got '{}'", text))?;
+    Ok((key.trim().to_string(), value.trim().to_string()))
+}
+
+/// Prints the effective configuration as TOML with secrets masked.
+pub fn show(loaded: &LoadedConfig) -> Result<()> {
+    let mut table = Table::try_from(&loaded.config)?;
+    for key in SECRET_KEYS {
+        if let Some(Value::String(secret)) = table.get(*key) {
+            let masked = mask_secret(secret);
+            table.insert(key.to_string(), Value::String(masked));
+        }
+    }
+
+    println!("# Effective configuration");
+    println!("# Sources (later wins): {}", loaded.sources.join(", "));
+    println!();
+    print!("{}", toml::to_string_pretty(&table)?);
+
+    Ok(())
+}
+
+/// Keeps the first 4 characters of a secret ("abcd****"); short secrets are fully masked.
+fn mask_secret(secret: &str) -> String {
+    match secret.chars().count() {
+        0 => String::new(),
+        n if n <= 8 => "*".repeat(n),
+        _ => format!("{}****", secret.chars().take(4).collect::<String>()),
+    }
+}
+
+/// Configuration values from environment variables, as (key, raw value).
+///
+/// `POLLY_*` variables that name no configuration key (e.g., `POLLY_HOME`) are skipped
+/// with a warning, so unrelated variables with the prefix cannot break a run.
+fn env_overrides(defaults: &Table, env: &BTreeMap<String, String>) -> Vec<(String, String)> {
+    let mut values = Vec::new();
+
+    for (name, key) in LEGACY_ENV_KEYS {
+        if let Some(v) = env.get(*name)
+            && !v.is_empty()
+        {
+            values.push((key.to_string(), v.clone()));
+        }
+    }
+
+    let mut prefixed: Vec<(String, String)> = env
+        .iter()
+        .filter(|(_, v)| !v.is_empty())
+        .filter_map(|(name, v)| {
+            let key = name.strip_prefix("POLLY_")?.to_lowercase().replace("__", ".");
+            if !is_known_key(defaults, &key) {
+                warn!(variable = %name, "Ignoring environment variable: no configuration key '{}'", key);
+                return None;
+            }
+            Some((key, v.clone()))
+        })
+        .collect();
+    prefixed.sort();
+    values.extend(prefixed);
+
+    values
+}
+
+/// Whether `key` (e.g., "schedule.concurrency") names a value of the defaults
+fn is_known_key(defaults: &Table, key: &str) -> bool {
+    let mut current = defaults;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        match (current.get(part), parts.peek().is_none()) {
+            (Some(Value::Table(_)), true) | (None, _) => return false,
+            (Some(_), true) => return true,
+            (Some(Value::Table(t)), false) => current = t,
+            (Some(_), false) => return false,
+        }
+    }
+    false
+}
+
+/// Converts a raw override to the type of the default value at `key`.
+fn typed_value(defaults: &Table, key: &str, raw: &str) -> Result<Value> {
+    let mut current = defaults;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let value = current
+            .get(part)
+            .ok_or_else(|| anyhow!("Unknown configuration key '{}'", key))?;
+
+        if parts.peek().is_none() {
+            let parsed = match value {
+                Value::Integer(_) => raw.parse().map(Value::Integer).ok(),
+                Value::Float(_) => raw.parse().map(Value::Float).ok(),
+                Value::Boolean(_) => raw.parse().map(Value::Boolean).ok(),
+                Value::String(_) => Some(Value::String(raw.to_string())),
+                _ => None,
+            };
+            return parsed.ok_or_else(|| anyhow!("Invalid value '{}' for '{}'", raw, key));
+        }
+
+        current = value
+            .as_table()
+            .ok_or_else(|| anyhow!("Unknown configuration key '{}'", key))?;
+    }
+
+    bail!("Unknown configuration key '{}'", key)
+}
+
+/// Deep-merges `overlay` into `base` (tables are merged, other values replaced).
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(incoming)) => merge(existing, incoming),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn set_path(table: &mut Table, key: &str, value: Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let entry = table
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(inner) = entry {
+                set_path(inner, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    const FILE: &str = r#"
+city_code = "32020"
+
+[schedule]
+concurrency = 2
+
+[route]
+fetch_concurrency = 7
+
+[cities.32020.schedule]
+concurrency = 3
+max_retries = 9
+
+[cities.25050]
+service_key = "daejeon-key"
+"#;
+
+    /// Writes `content` to a config file unique to this test
+    fn config_file(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("polly-config-{}-{}.toml", process::id(), name));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn pairs(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn env_map(values: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs(values).into_iter().collect()
+    }
+
+    #[test]
+    fn later_layers_win() {
+        let path = config_file("layers", FILE);
+        let env = env_map(&[
+            ("POLLY_SCHEDULE__CONCURRENCY", "4"),
+            ("POLLY_SCHEDULE__REQUEST_INTERVAL_MS", "700"),
+        ]);
+        let cli = pairs(&[("schedule.concurrency", "5")]);
+
+        let concurrency = |cli: &[(String, String)], env: &BTreeMap<String, String>| {
+            let loaded = load_with_env(Some(&path), cli, env).unwrap();
+            loaded.config.schedule.concurrency
+        };
+        assert_eq!(concurrency(&cli, &env), 5);
+        assert_eq!(concurrency(&[], &env), 4);
+        assert_eq!(concurrency(&[], &BTreeMap::new()), 3);
+
+        let loaded = load_with_env(Some(&path), &cli, &env).unwrap();
+        let config = &loaded.config;
+        // Each value comes from the last layer that sets it
+        assert_eq!(config.schedule.request_interval_ms, 700);
+        assert_eq!(config.schedule.max_retries, 9);
+        assert_eq!(config.route.fetch_concurrency, 7);
+        assert_eq!(
+            config.route.snap_concurrency,
+            PollyConfig::default().route.snap_concurrency
+        );
+        assert_eq!(
+            loaded.sources,
+            [
+                "defaults".to_string(),
+                path.display().to_string(),
+                "[cities.32020]".to_string(),
+                "environment".to_string(),
+                "command line".to_string(),
+            ]
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn city_section_follows_the_last_city_code() {
+        let path = config_file("city", FILE);
+
+        let env = env_map(&[("POLLY_CITY_CODE", "25050")]);
+        let loaded = load_with_env(Some(&path), &[], &env).unwrap();
+        assert_eq!(loaded.config.city_code, "25050");
+        assert_eq!(loaded.config.service_key, "daejeon-key");
+        // The 32020 section no longer applies
+        assert_eq!(loaded.config.schedule.concurrency, 2);
+
+        let cli = pairs(&[("city_code", "32020")]);
+        let loaded = load_with_env(Some(&path), &cli, &env).unwrap();
+        assert_eq!(loaded.config.schedule.concurrency, 3);
+        assert!(loaded.config.service_key.is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn legacy_environment_names_map_to_keys() {
+        let path = config_file("legacy", "");
+        let env = env_map(&[
+            ("DATA_GO_KR_SERVICE_KEY", "legacy-key"),
+            ("OSRM_API_URL", "http://osrm.local/route/v1/driving"),
+            ("TAGO_ARRIVAL_API_URL", ""),
+        ]);
+
+        let config = load_with_env(Some(&path), &[], &env).unwrap().config;
+        assert_eq!(config.service_key, "legacy-key");
+        assert_eq!(config.endpoints.osrm, "http://osrm.local/route/v1/driving");
+        // Empty variables are unset
+        assert_eq!(
+            config.endpoints.tago_arrival,
+            PollyConfig::default().endpoints.tago_arrival
+        );
+
+        // The prefixed name wins over the legacy one
+        let mut env = env;
+        env.insert("POLLY_SERVICE_KEY".to_string(), "polly-key".to_string());
+        let config = load_with_env(Some(&path), &[], &env).unwrap().config;
+        assert_eq!(config.service_key, "polly-key");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unknown_polly_variables_are_skipped() {
+        let path = config_file("unknown", "");
+        let env = env_map(&[
+            ("POLLY_HOME", "/opt/polly"),
+            ("POLLY_SCHEDULE", "fast"),
+            ("POLLY_SCHEDULE__NO_SUCH_KEY", "1"),
+            ("POLLY_SCHEDULE__MAX_RETRIES", "6"),
+        ]);
+
+        let loaded = load_with_env(Some(&path), &[], &env).unwrap();
+        assert_eq!(loaded.config.schedule.max_retries, 6);
+
+        // Unknown keys and mistyped values are errors on the command line
+        let cli = pairs(&[("schedule.no_such_key", "1")]);
+        assert!(load_with_env(Some(&path), &cli, &env).is_err());
+        let env = env_map(&[("POLLY_SCHEDULE__MAX_RETRIES", "many")]);
+        assert!(load_with_env(Some(&path), &[], &env).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+// src/config/model.rs
+
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Configuration (polly.toml)
+// ============================================================================
+
+/// Effective configuration of all processors.
+///
+/// Built from the defaults below, `polly.toml`, its `[cities.<code>]` section,
+/// environment variables and command line flags, in that order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PollyConfig {
+    // TAGO city code (default: Wonju -> 32020)
+    pub city_code: String,
+    // Public Data Portal service key (secret)
+    pub service_key: String,
+    pub endpoints: Endpoints,
+    pub route: RouteSettings,
+    pub schedule: ScheduleSettings,
+    pub realtime: RealtimeSettings,
+    pub planner: PlannerSettings,
+}
+
+/// Upstream API and website endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Endpoints {
+    pub tago_route: String,
+    pub tago_bus_location: String,
+    pub tago_arrival: String,
+    pub osrm: String,
+    // Foot-profile OSRM route service for walking transfers (empty: straight-line estimates)
+    pub osrm_foot: String,
+    // Wonju Bus Information System schedule pages
+    pub schedule_main: String,
+    pub schedule_detail: String,
+}
+
+/// Route processor settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RouteSettings {
+    pub fetch_concurrency: usize,
+    pub snap_concurrency: usize,
+    // Number of stops per OSRM request
+    pub osrm_chunk_size: usize,
+    // Stops are moved onto the road corridor when they are at most this far from it (meters)
+    pub corridor_max_drift_m: f64,
+}
+
+/// Schedule crawler settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScheduleSettings {
+    // Detail pages fetched concurrently over one session
+    pub concurrency: usize,
+    // Minimum interval between two requests (politeness)
+    pub request_interval_ms: u64,
+    // Retries per route on transient failures, with exponential backoff from the base
+    pub max_retries: u32,
+    pub retry_base_ms: u64,
+    pub user_agent: String,
+}
+
+/// Real-time feed settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RealtimeSettings {
+    pub fetch_concurrency: usize,
+    // Arrival predictions are polled for this many stops ahead of each vehicle
+    pub arrival_lookahead: usize,
+}
+
+/// Journey planner settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlannerSettings {
+    pub walk_speed_kmh: f64,
+    // Walking distance is the straight-line distance times this factor
+    pub detour_factor: f64,
+    // Coordinates are walked to and from stations at most this far away (meters)
+    pub access_radius_m: f64,
+    // Walking transfers between stations at most this far apart (meters)
+    pub transfer_radius_m: f64,
+    pub max_transfers: usize,
+    // Walking transfers routed concurrently with the foot-profile OSRM service
+    pub foot_routing_concurrency: usize,
+}
+
+impl Default for PollyConfig {
+    fn default() -> Self {
+        Self {
+            city_code: "32020".to_string(),
+            service_key: String::new(),
+            endpoints: Endpoints::default(),
+            route: RouteSettings::default(),
+            schedule: ScheduleSettings::default(),
+            realtime: RealtimeSettings::default(),
+            planner: PlannerSettings::default(),
+        }
+    }
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            tago_route: "http://apis.data.go.kr/1613000/BusRouteInfoInqireService".to_string(),
+            tago_bus_location: "http://apis.data.go.kr/1613000/BusLcInfoInqireService".to_string(),
+            tago_arrival: "http://apis.data.go.kr/1613000/ArvlInfoInqireService".to_string(),
+            osrm: "http://router.project-osrm.org/route/v1/driving".to_string(),
+            osrm_foot: String::new(),
+            schedule_main: "http://its.wonju.go.kr/bus/bus04.do".to_string(),
+            schedule_detail: "http://its.wonju.go.kr/bus/bus04Detail.do".to_string(),
+        }
+    }
+}
+
+impl Default for RouteSettings {
+    fn default() -> Self {
+        Self {
+            fetch_concurrency: 10,
+            snap_concurrency: 4,
+            osrm_chunk_size: 120,
+            corridor_max_drift_m: 90.0,
+        }
+    }
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            request_interval_ms: 300,
+            max_retries: 3,
+            retry_base_ms: 1000,
+            user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string(),
+        }
+    }
+}
+
+impl Default for RealtimeSettings {
+    fn default() -> Self {
+        Self {
+            fetch_concurrency: 10,
+            arrival_lookahead: 5,
+        }
+    }
+}
+
+impl Default for PlannerSettings {
+    fn default() -> Self {
+        Self {
+            walk_speed_kmh: 4.5,
+            detour_factor: 1.3,
+            access_radius_m: 800.0,
+            transfer_radius_m: 300.0,
+            max_transfers: 3,
+            foot_routing_concurrency: 8,
+        }
+    }
+}
@@ -0,0 +1,326 @@
+// src/error/mod.rs
+
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+/// A request to an upstream service (TAGO, OSRM, the schedule website) that failed
+#[derive(Debug, Error)]
+pub enum UpstreamError {
+    #[error("{service} request failed: {source}")]
+    Network {
+        service: &'static str,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("{service} answered HTTP {status}")]
+    Status { service: &'static str, status: u16 },
+}
+
+/// A TAGO (data.go.kr) response that is not a successful result envelope
+#[derive(Debug, Error)]
+pub enum ApiError {
+    // The portal answers key and quota errors with XML, even when JSON was requested.
+    #[error("{service} returned a non-JSON response: {snippet}")]
+    NotJson {
+        service: &'static str,
+        snippet: String,
+    },
+    #[error("{service} returned result code {code}: {message}")]
+    ResultCode {
+        service: &'static str,
+        code: String,
+        message: String,
+    },
+}
+
+/// Input that could not be understood
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("Invalid {what}: {source}")]
+    Json {
+        what: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{0}")]
+    Content(String),
+}
+
+/// A route whose path could not be built
+#[derive(Debug, Error)]
+pub enum GeometryError {
+    #[error("OSRM found no route ({code})")]
+    NoRoute { code: String },
+    #[error("OSRM returned an empty geometry")]
+    Empty,
+}
+
+/// A file that could not be read or written
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Failed to read {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Failed to write {path:?}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Why a route produced no output
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error(transparent)]
+    Upstream(#[from] UpstreamError),
+    #[error(transparent)]
+    Api(#[from] ApiError),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Geometry(#[from] GeometryError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// The run was stopped by `--fail-fast` or `--max-failures`.
+#[derive(Debug, Error)]
+#[error("Stopped after {failures} failed routes (at most {limit} allowed)")]
+pub struct FailureLimitReached {
+    pub failures: usize,
+    pub limit: usize,
+}
+
+/// A journey was planned on a day type no timetable covers.
+#[derive(Debug, Error)]
+#[error("No timetable runs on day type '{day_type}'")]
+pub struct NoServiceOnDay {
+    pub day_type: String,
+}
+
+/// The configuration (polly.toml, environment, flags) could not be loaded.
+#[derive(Debug, Error)]
+#[error("Configuration error")]
+pub struct ConfigError;
+
+/// An error and its causes on one line, skipping causes its message already includes
+pub fn describe(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut text = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        let message = e.to_string();
+        if !text.contains(&message) {
+            text = format!("{}: {}", text, message);
+        }
+        source = e.source();
+    }
+    text
+}
+
+// ============================================================================
+// Error Kinds and Exit Codes
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Upstream,
+    Api,
+    Parse,
+    Geometry,
+    Io,
+    Config,
+    FailureLimit,
+    Other,
+}
+
+impl ErrorKind {
+    /// Classifies an error by the first typed error in its chain.
+    pub fn of(error: &anyhow::Error) -> Self {
+        if error.downcast_ref::<ConfigError>().is_some() {
+            return Self::Config;
+        }
+
+        error
+            .chain()
+            .find_map(|e| {
+                if let Some(e) = e.downcast_ref::<PipelineError>() {
+                    Some(e.kind())
+                } else if e.is::<UpstreamError>() || e.is::<reqwest::Error>() {
+                    Some(Self::Upstream)
+                } else if e.is::<ApiError>() {
+                    Some(Self::Api)
+                } else if e.is::<ParseError>() || e.is::<serde_json::Error>() {
+                    Some(Self::Parse)
+                } else if e.is::<GeometryError>() {
+                    Some(Self::Geometry)
+                } else if e.is::<StorageError>() || e.is::<io::Error>() {
+                    Some(Self::Io)
+                } else if e.is::<FailureLimitReached>() {
+                    Some(Self::FailureLimit)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(Self::Other)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Upstream => "upstream",
+            Self::Api => "api",
+            Self::Parse => "parse",
+            Self::Geometry => "geometry",
+            Self::Io => "io",
+            Self::Config => "config",
+            Self::FailureLimit => "failureLimit",
+            Self::Other => "other",
+        }
+    }
+
+    /// Process exit code of a run aborted by an error of this kind
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Other => exit_code::ERROR,
+            Self::FailureLimit => exit_code::FAILURE_LIMIT,
+            Self::Upstream => exit_code::UPSTREAM,
+            Self::Api => exit_code::API,
+            Self::Parse => exit_code::PARSE,
+            Self::Geometry => exit_code::GEOMETRY,
+            Self::Io => exit_code::IO,
+            Self::Config => exit_code::CONFIG,
+        }
+    }
+}
+
+impl PipelineError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Upstream(_) => ErrorKind::Upstream,
+            Self::Api(_) => ErrorKind::Api,
+            Self::Parse(_) => ErrorKind::Parse,
+            Self::Geometry(_) => ErrorKind::Geometry,
+            Self::Storage(_) => ErrorKind::Io,
+        }
+    }
+}
+
+/// Process exit codes (2 is used by the argument parser for usage errors)
+pub mod exit_code {
+    pub const SUCCESS: u8 = 0;
+    pub const ERROR: u8 = 1;
+    // Finished, but some routes failed (within --max-failures)
+    pub const PARTIAL: u8 = 3;
+    // Stopped by --fail-fast or --max-failures
+    pub const FAILURE_LIMIT: u8 = 4;
+    pub const CONFIG: u8 = 5;
+    pub const UPSTREAM: u8 = 10;
+    pub const API: u8 = 11;
+    pub const PARSE: u8 = 12;
+    pub const GEOMETRY: u8 = 13;
+    pub const IO: u8 = 14;
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn io_error(message: &str) -> io::Error {
+        io::Error::other(message.to_string())
+    }
+
+    #[test]
+    fn kind_is_the_first_typed_error_in_the_chain() {
+        let parse = anyhow::Error::new(ParseError::Content("no table".to_string()))
+            .context("Failed to parse 34-1");
+        assert_eq!(ErrorKind::of(&parse), ErrorKind::Parse);
+
+        let geometry = anyhow::Error::new(GeometryError::NoRoute {
+            code: "NoSegment".to_string(),
+        })
+        .context("Snapping 34-1")
+        .context("Route processor");
+        assert_eq!(ErrorKind::of(&geometry), ErrorKind::Geometry);
+
+        let pipeline = anyhow::Error::new(PipelineError::from(ApiError::ResultCode {
+            service: "TAGO",
+            code: "30".to_string(),
+            message: "SERVICE_KEY_IS_NOT_REGISTERED_ERROR".to_string(),
+        }));
+        assert_eq!(ErrorKind::of(&pipeline), ErrorKind::Api);
+
+        let storage = anyhow::Error::new(StorageError::Write {
+            path: PathBuf::from("out.json"),
+            source: io_error("read-only"),
+        });
+        assert_eq!(ErrorKind::of(&storage), ErrorKind::Io);
+        assert_eq!(ErrorKind::of(&io_error("gone").into()), ErrorKind::Io);
+
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(ErrorKind::of(&json.into()), ErrorKind::Parse);
+
+        let limit = anyhow::Error::new(FailureLimitReached {
+            failures: 2,
+            limit: 1,
+        });
+        assert_eq!(ErrorKind::of(&limit), ErrorKind::FailureLimit);
+
+        assert_eq!(ErrorKind::of(&anyhow!("something else")), ErrorKind::Other);
+    }
+
+    #[test]
+    fn config_errors_are_classified_by_their_context() {
+        // Over any typed cause, e.g., an unreadable or invalid polly.toml
+        let error = anyhow::Error::new(io_error("missing")).context(ConfigError);
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Config);
+        let error = anyhow::Error::new(ParseError::Content("bad".to_string())).context(ConfigError);
+        assert_eq!(ErrorKind::of(&error), ErrorKind::Config);
+    }
+
+    #[test]
+    fn exit_codes_are_stable() {
+        let codes = [
+            (ErrorKind::Other, 1),
+            (ErrorKind::FailureLimit, 4),
+            (ErrorKind::Config, 5),
+            (ErrorKind::Upstream, 10),
+            (ErrorKind::Api, 11),
+            (ErrorKind::Parse, 12),
+            (ErrorKind::Geometry, 13),
+            (ErrorKind::Io, 14),
+        ];
+        for (kind, code) in codes {
+            assert_eq!(kind.exit_code(), code, "{}", kind.as_str());
+        }
+        assert_eq!(exit_code::SUCCESS, 0);
+        assert_eq!(exit_code::PARTIAL, 3);
+    }
+
+    #[test]
+    fn describe_skips_causes_already_in_the_message() {
+        let error = anyhow::Error::new(io_error("disk full")).context("Failed to save 34-1");
+        assert_eq!(describe(error.as_ref()), "Failed to save 34-1: disk full");
+
+        // StorageError already names its source
+        let error = anyhow::Error::new(StorageError::Read {
+            path: PathBuf::from("a.json"),
+            source: io_error("denied"),
+        })
+        .context("Loading routes");
+        assert_eq!(
+            describe(error.as_ref()),
+            "Loading routes: Failed to read \"a.json\": denied"
+        );
+    }
+}
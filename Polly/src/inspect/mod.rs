@@ -0,0 +1,466 @@
+// src/inspect/mod.rs
+
+pub mod model;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::analysis::load_schedule;
+use crate::inspect::model::{
+    DepartureSummary, InspectedStop, RouteInspection, ServiceSummary, StopInspection, StopRoute,
+    TimetableSummary, TurnPoint, VariantInspection,
+};
+use crate::network::model::{NearbyStation, Network};
+use crate::registry::model::RouteRegistry;
+use crate::registry::normalize_route_no;
+use crate::route::RAW_DIR;
+use crate::route::model::{DerivedFeature, RawRouteFile};
+use crate::storage::{FsStorage, Storage, read_json};
+use crate::utils::geo::{closest_point_on_polyline, meters_between, round_m};
+
+// ============================================================================
+// Main Execution
+// ============================================================================
+
+/// Entry point of `polly inspect route`.
+///
+/// Joins everything known about one route: the variants linked to its number in the
+/// route registry, the stops of each variant (from `derived_routes/`, else `raw_routes/`),
+/// how each stop sits on the snapped path, and the service summary of its schedule.
+/// `query` is a route number ("34-1") or a TAGO route ID, which limits the output to
+/// that variant.
+pub async fn run_route(
+    routes_dir: PathBuf,
+    schedules_dir: PathBuf,
+    query: String,
+    json: bool,
+) -> Result<()> {
+    let inspection = inspect_route(&routes_dir, &schedules_dir, &query)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+    } else {
+        print_route(&inspection);
+    }
+    Ok(())
+}
+
+/// Entry point of `polly inspect stop`.
+///
+/// Lists the snapped routes serving a station, the other stations within `radius_m`
+/// meters, and its scheduled departures per day type (when `departures/` was built by
+/// the timetable processor).
+pub async fn run_stop(
+    routes_dir: PathBuf,
+    departures_dir: PathBuf,
+    node_id: String,
+    radius_m: f64,
+    json: bool,
+) -> Result<()> {
+    let inspection = inspect_stop(&routes_dir, &departures_dir, &node_id, radius_m)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+    } else {
+        print_stop(&inspection);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+pub fn inspect_route(
+    routes_dir: &Path,
+    schedules_dir: &Path,
+    query: &str,
+) -> Result<RouteInspection> {
+    let registry = RouteRegistry::build(routes_dir, schedules_dir)?;
+
+    let normalized = normalize_route_no(query);
+    let is_route_id = |route_id: &str| route_id == query;
+    let (route_no, entry) = registry
+        .routes
+        .get_key_value(&normalized)
+        .or_else(|| {
+            registry
+                .routes
+                .iter()
+                .find(|(_, e)| e.tago_routes.iter().any(|t| is_route_id(&t.route_id)))
+        })
+        .ok_or_else(|| anyhow!("No route number or ID matches '{}'", query))?;
+    let by_id = entry.tago_routes.iter().any(|t| is_route_id(&t.route_id));
+
+    let storage = FsStorage::new(routes_dir);
+    let network = Network::load(&storage)?;
+    let raw_keys = storage.list(RAW_DIR)?;
+
+    let variants = entry
+        .tago_routes
+        .iter()
+        .filter(|t| !by_id || is_route_id(&t.route_id))
+        .map(|t| {
+            let suffix = format!("_{}.json", t.route_id);
+            let raw = raw_keys
+                .iter()
+                .find(|k| k.ends_with(&suffix))
+                .and_then(|k| read_json::<RawRouteFile>(&storage, k).ok());
+            let derived = network
+                .routes
+                .iter()
+                .find(|r| r.properties.route_id == t.route_id);
+
+            inspect_variant(&network, &t.route_id, raw, derived)
+        })
+        .collect();
+
+    let timetable = entry
+        .schedule_file
+        .as_ref()
+        .and_then(|file| summarize_schedule(&schedules_dir.join(file), file));
+
+    Ok(RouteInspection {
+        route_no: route_no.clone(),
+        variant_ids: entry
+            .tago_routes
+            .iter()
+            .map(|t| t.route_id.clone())
+            .collect(),
+        variants,
+        timetable,
+    })
+}
+
+fn inspect_variant(
+    network: &Network,
+    route_id: &str,
+    raw: Option<RawRouteFile>,
+    derived: Option<&DerivedFeature>,
+) -> VariantInspection {
+    // Derived stops are aligned with `stop_to_coord`; raw stops have no path.
+    let stops: Vec<InspectedStop> = match (derived, &raw) {
+        (Some(route), _) => {
+            let coords = &route.geometry.coordinates;
+            route
+                .properties
+                .stops
+                .iter()
+                .enumerate()
+                .map(|(i, s)| {
+                    let position = network.stations.get(&s.id).map(|st| (st.lon, st.lat));
+                    let coord_idx = route.properties.indices.stop_to_coord.get(i).copied();
+                    let mapped = coord_idx.and_then(|idx| coords.get(idx));
+
+                    InspectedStop {
+                        ord: s.ord,
+                        node_id: s.id.clone(),
+                        name: s.name.clone(),
+                        up_down: s.up_down,
+                        lat: position.map(|p| p.1),
+                        lon: position.map(|p| p.0),
+                        coord_idx,
+                        path_distance_m: position
+                            .and_then(|p| closest_point_on_polyline(p, coords))
+                            .map(|(_, d)| round_m(d)),
+                        coord_distance_m: position
+                            .zip(mapped)
+                            .map(|(p, c)| round_m(meters_between(p.0, p.1, c[0], c[1]))),
+                    }
+                })
+                .collect()
+        }
+        (None, Some(raw)) => raw
+            .stops
+            .iter()
+            .map(|s| InspectedStop {
+                ord: s.node_ord,
+                node_id: s.node_id.clone(),
+                name: s.node_nm.clone(),
+                up_down: s.up_down_cd,
+                lat: Some(s.gps_lat),
+                lon: Some(s.gps_long),
+                coord_idx: None,
+                path_distance_m: None,
+                coord_distance_m: None,
+            })
+            .collect(),
+        (None, None) => Vec::new(),
+    };
+
+    // Same rule as the route processor: the last stop before the direction changes
+    let turn = stops
+        .windows(2)
+        .find(|w| w[0].up_down != w[1].up_down)
+        .map(|w| &w[0])
+        .or(stops.last())
+        .map(|s| TurnPoint {
+            node_id: s.node_id.clone(),
+            name: s.name.clone(),
+            ord: s.ord,
+            coord_idx: derived.map(|r| r.properties.indices.turn_idx),
+        });
+
+    VariantInspection {
+        route_id: route_id.to_string(),
+        snapped: derived.is_some(),
+        fetched_at: raw
+            .map(|r| r.fetched_at)
+            .or_else(|| derived.map(|r| r.properties.meta.source_ver.clone())),
+        length_m: derived.map(|r| r.properties.meta.total_dist),
+        turn,
+        stops,
+    }
+}
+
+/// Trips, first and last departures per day type and direction (the `headways` of a
+/// schedule file)
+fn summarize_schedule(path: &Path, file: &str) -> Option<TimetableSummary> {
+    let schedule = load_schedule(path).ok()?;
+
+    let day_types = schedule["headways"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(day_type, directions)| {
+            let by_direction = directions
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(direction, h)| {
+                    let summary = ServiceSummary {
+                        trips: h["trips"].as_u64().unwrap_or(0) as usize,
+                        first_departure: h["firstDeparture"].as_str().unwrap_or("").to_string(),
+                        last_departure: h["lastDeparture"].as_str().unwrap_or("").to_string(),
+                    };
+                    (direction.clone(), summary)
+                })
+                .collect();
+            (day_type.clone(), by_direction)
+        })
+        .collect();
+
+    Some(TimetableSummary {
+        schedule_file: file.to_string(),
+        effective_from: schedule["effectiveFrom"].as_str().map(str::to_string),
+        directions: schedule["directions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.as_str().map(str::to_string))
+            .collect(),
+        day_types,
+    })
+}
+
+fn print_route(inspection: &RouteInspection) {
+    println!("Route {}", inspection.route_no);
+    println!(" Variants: {}", inspection.variant_ids.join(", "));
+
+    for v in &inspection.variants {
+        println!();
+        let length = v
+            .length_m
+            .map_or("not snapped".to_string(), |l| format!("{:.1} m", l));
+        println!(
+            "Variant {}: {} stops, {}",
+            v.route_id,
+            v.stops.len(),
+            length
+        );
+        if let Some(fetched_at) = &v.fetched_at {
+            println!(" Fetched: {}", fetched_at);
+        }
+        if let Some(turn) = &v.turn {
+            let coord = turn
+                .coord_idx
+                .map_or(String::new(), |i| format!(", coordinate {}", i));
+            println!(
+                " Turn: {} ({}, ord {}{})",
+                turn.name, turn.node_id, turn.ord, coord
+            );
+        }
+
+        println!(
+            "  {:>4} {:>3}  {:<16} {:>10} {:>11} {:>6} {:>9} {:>9}",
+            "ord", "dir", "node", "lat", "lon", "coord", "to path", "to coord"
+        );
+        for s in &v.stops {
+            println!(
+                "  {:>4} {:>3}  {:<16} {:>10} {:>11} {:>6} {:>9} {:>9}  {}",
+                s.ord,
+                s.up_down,
+                s.node_id,
+                s.lat.map_or("-".to_string(), |v| format!("{:.6}", v)),
+                s.lon.map_or("-".to_string(), |v| format!("{:.6}", v)),
+                s.coord_idx.map_or("-".to_string(), |v| v.to_string()),
+                s.path_distance_m
+                    .map_or("-".to_string(), |v| format!("{:.1} m", v)),
+                s.coord_distance_m
+                    .map_or("-".to_string(), |v| format!("{:.1} m", v)),
+                s.name
+            );
+        }
+    }
+
+    println!();
+    match &inspection.timetable {
+        Some(t) => {
+            let effective = t
+                .effective_from
+                .as_ref()
+                .map_or(String::new(), |d| format!(", effective {}", d));
+            println!("Timetable ({}{})", t.schedule_file, effective);
+            for (day_type, directions) in &t.day_types {
+                for (direction, s) in directions {
+                    println!(
+                        "  {:<18} {:<12} {:>4} trips, {}-{}",
+                        day_type, direction, s.trips, s.first_departure, s.last_departure
+                    );
+                }
+            }
+        }
+        None => println!("Timetable: no schedule linked"),
+    }
+}
+
+// ============================================================================
+// Stops
+// ============================================================================
+
+pub fn inspect_stop(
+    routes_dir: &Path,
+    departures_dir: &Path,
+    node_id: &str,
+    radius_m: f64,
+) -> Result<StopInspection> {
+    let network = Network::load(&FsStorage::new(routes_dir))?;
+    let station = network
+        .stations
+        .get(node_id)
+        .ok_or_else(|| anyhow!("No station '{}' in routeMap.json", node_id))?;
+    let position = (station.lon, station.lat);
+
+    let mut routes = Vec::new();
+    for route in &network.routes {
+        for (i, s) in route.properties.stops.iter().enumerate() {
+            if s.id != node_id {
+                continue;
+            }
+            routes.push(StopRoute {
+                route_id: route.properties.route_id.clone(),
+                route_no: route.properties.route_no.clone(),
+                ord: s.ord,
+                up_down: s.up_down,
+                coord_idx: route.properties.indices.stop_to_coord.get(i).copied(),
+                path_distance_m: closest_point_on_polyline(position, &route.geometry.coordinates)
+                    .map(|(_, d)| round_m(d)),
+            });
+        }
+    }
+    routes.sort_by(|a, b| (&a.route_no, &a.route_id).cmp(&(&b.route_no, &b.route_id)));
+
+    let nearby_stations = network
+        .nearest_stations(position, Some(radius_m), usize::MAX)
+        .into_iter()
+        .filter(|(s, _)| s.node_id != node_id)
+        .map(|(s, d)| NearbyStation::new(s, d))
+        .collect();
+
+    Ok(StopInspection {
+        node_id: station.node_id.clone(),
+        name: station.name.clone(),
+        node_no: station.node_no.clone(),
+        lat: station.lat,
+        lon: station.lon,
+        routes,
+        nearby_stations,
+        departures: summarize_departures(&departures_dir.join(format!("{}.json", node_id))),
+    })
+}
+
+/// Departures per day type of a departure board (empty when there is none)
+fn summarize_departures(path: &Path) -> BTreeMap<String, DepartureSummary> {
+    let Some(board) = fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+    else {
+        return BTreeMap::new();
+    };
+
+    board["dayTypes"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(day_type, departures)| {
+            let departures = departures.as_array()?;
+            // Boards are sorted by time
+            let time =
+                |d: Option<&Value>| d.and_then(|d| d["time"].as_str()).unwrap_or("").to_string();
+            let route_nos: BTreeSet<String> = departures
+                .iter()
+                .filter_map(|d| d["routeNo"].as_str().map(str::to_string))
+                .collect();
+
+            let summary = DepartureSummary {
+                departures: departures.len(),
+                first_departure: time(departures.first()),
+                last_departure: time(departures.last()),
+                route_nos: route_nos.into_iter().collect(),
+            };
+            Some((day_type.clone(), summary))
+        })
+        .collect()
+}
+
+fn print_stop(inspection: &StopInspection) {
+    println!(
+        "Stop {} ({}, {})",
+        inspection.name, inspection.node_id, inspection.node_no
+    );
+    println!(" Position: {:.6}, {:.6}", inspection.lat, inspection.lon);
+
+    println!(" Routes:");
+    if inspection.routes.is_empty() {
+        println!("  (no snapped route serves this station)");
+    }
+    for r in &inspection.routes {
+        println!(
+            "  {:<8} {:<16} ord {:>3}, dir {}, coordinate {}, {} from path",
+            r.route_no,
+            r.route_id,
+            r.ord,
+            r.up_down,
+            r.coord_idx.map_or("-".to_string(), |v| v.to_string()),
+            r.path_distance_m
+                .map_or("-".to_string(), |v| format!("{:.1} m", v)),
+        );
+    }
+
+    println!(" Nearby stations:");
+    for s in &inspection.nearby_stations {
+        println!(
+            "  {:>8.1} m  {} ({}, {})",
+            s.distance_m, s.name, s.node_id, s.node_no
+        );
+    }
+
+    println!(" Departures:");
+    if inspection.departures.is_empty() {
+        println!("  (no departure board; run the timetable processor)");
+    }
+    for (day_type, d) in &inspection.departures {
+        println!(
+            "  {:<18} {:>4} departures, {}-{} ({})",
+            day_type,
+            d.departures,
+            d.first_departure,
+            d.last_departure,
+            d.route_nos.join(", ")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+// src/inspect/model.rs
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::network::model::NearbyStation;
+
+// ============================================================================
+// Output Models (Printed by `polly inspect route`)
+// ============================================================================
+
+/// One route number across raw, derived, routeMap.json and schedule data
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteInspection {
+    pub route_no: String,
+    // TAGO route IDs of every variant with this route number
+    pub variant_ids: Vec<String>,
+    // Inspected variants (only the requested one when inspecting by route ID)
+    pub variants: Vec<VariantInspection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timetable: Option<TimetableSummary>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantInspection {
+    pub route_id: String,
+    // Whether derived_routes/ has the snapped path (path fields are missing otherwise)
+    pub snapped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length_m: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn: Option<TurnPoint>,
+    pub stops: Vec<InspectedStop>,
+}
+
+/// The last stop before the direction (`up_down`) changes
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnPoint {
+    pub node_id: String,
+    pub name: String,
+    pub ord: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coord_idx: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectedStop {
+    pub ord: i64,
+    pub node_id: String,
+    pub name: String,
+    pub up_down: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
+    // Path coordinate the stop is mapped to (stop_to_coord)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coord_idx: Option<usize>,
+    // Distance to the closest point of the path, and to the mapped coordinate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_distance_m: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coord_distance_m: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimetableSummary {
+    pub schedule_file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_from: Option<String>,
+    pub directions: Vec<String>,
+    // Day type -> Direction -> Summary
+    pub day_types: BTreeMap<String, BTreeMap<String, ServiceSummary>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceSummary {
+    pub trips: usize,
+    pub first_departure: String,
+    pub last_departure: String,
+}
+
+// ============================================================================
+// Output Models (Printed by `polly inspect stop`)
+// ============================================================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopInspection {
+    pub node_id: String,
+    pub name: String,
+    pub node_no: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub routes: Vec<StopRoute>,
+    // Other stations within the inspection radius, nearest first
+    pub nearby_stations: Vec<NearbyStation>,
+    // Day type -> Scheduled departures (from departures/, when built)
+    pub departures: BTreeMap<String, DepartureSummary>,
+}
+
+/// A snapped route serving the station
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopRoute {
+    pub route_id: String,
+    pub route_no: String,
+    pub ord: i64,
+    pub up_down: i64,
+    pub coord_idx: Option<usize>,
+    pub path_distance_m: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepartureSummary {
+    pub departures: usize,
+    pub first_departure: String,
+    pub last_departure: String,
+    pub route_nos: Vec<String>,
+}
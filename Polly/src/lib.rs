@@ -0,0 +1,69 @@
+// src/lib.rs
+
+//! Data pipelines of wBus: bus routes, stations and timetables of Wonju.
+//!
+//! The `Polly` command line tool is a thin layer over this crate; everything it runs
+//! can be driven programmatically:
+//!
+//! - [`route`]: fetches routes and stops from TAGO ([`route::model::BusRouteProcessor`]),
+//!   snaps them to the road network with OSRM, and builds `routeMap.json`.
+//! - [`schedule`]: crawls and parses the timetable website
+//!   ([`schedule::parse_main_page`], [`schedule::parse_detail_page`],
+//!   [`schedule::model::ScheduleCrawler`]).
+//! - [`network`]: stations and snapped routes with spatial indexes
+//!   ([`network::model::Network`]), for nearest-stop and nearest-route queries, and
+//!   walking transfers between nearby stations ([`network::transfer`]).
+//! - [`planner`]: journeys between stations or coordinates over the stop-level
+//!   timetables ([`planner::model::Planner`]), and isochrones of the areas reachable
+//!   within a time budget.
+//! - [`storage`]: where processors save their files ([`storage::FsStorage`] on disk,
+//!   [`storage::MemoryStorage`] in memory, or any [`storage::Storage`] implementation).
+//! - [`config`]: the layered `polly.toml` configuration.
+//! - [`error`]: typed pipeline errors and the exit codes of the CLI.
+//!
+//! The output models (`*::model`) serialize to the JSON files the frontend reads.
+//!
+//! # Example
+//!
+//! Fetching and snapping one route into memory with a custom HTTP client:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use polly::config::model::PollyConfig;
+//! use polly::route::model::BusRouteProcessor;
+//! use polly::storage::MemoryStorage;
+//!
+//! # async fn example(config: PollyConfig) -> Result<(), polly::error::PipelineError> {
+//! let storage = Arc::new(MemoryStorage::new());
+//! let processor = BusRouteProcessor {
+//!     client: reqwest::Client::builder().build().unwrap(),
+//!     ..BusRouteProcessor::new(&config, storage.clone())
+//! };
+//!
+//! if let Some(raw) = processor.fetch_raw_route("WJB251000068", "30").await? {
+//!     processor.save_raw_route(&raw)?;
+//!     if let Some(derived) = processor.snap_route(raw).await? {
+//!         processor.save_derived_route("WJB251000068", &derived)?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod analysis;
+pub mod calendar;
+pub mod config;
+pub mod error;
+pub mod inspect;
+pub mod network;
+pub mod planner;
+pub mod realtime;
+pub mod registry;
+pub mod report;
+pub mod route;
+pub mod schedule;
+pub mod serve;
+pub mod storage;
+pub mod timetable;
+pub mod utils;
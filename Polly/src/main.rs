@@ -1,30 +1,80 @@
 // src/main.rs
 
-mod config;
-mod route;
-mod schedule;
-mod utils;
-
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+
+use polly::config::parse_override;
+use polly::error::{ConfigError, ErrorKind, exit_code};
+use polly::report::logging::{self, LogFormat};
+use polly::report::{FailurePolicy, RunStatus};
+use polly::{
+    analysis, calendar, config, inspect, network, planner, registry, route, schedule, serve,
+    timetable,
+};
 
 /// Polly CLI Tool
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Configuration file (default: ./polly.toml if present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Override a configuration value (e.g., --set schedule.request_interval_ms=500)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    set: Vec<String>,
+
+    /// Log output format (json: one object per event, for schedulers)
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    log_format: LogFormat,
+
+    /// Log level filter (e.g., debug or polly=debug,reqwest=warn; default: RUST_LOG or info)
+    #[arg(long, global = true)]
+    log_level: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// What to do when routes fail (route and schedule processors)
+#[derive(Args)]
+struct FailureArgs {
+    /// Stop at the first failed route
+    #[arg(long, conflicts_with_all = ["keep_going", "max_failures"])]
+    fail_fast: bool,
+
+    /// Process every route whatever the number of failures (default)
+    #[arg(long, conflicts_with = "max_failures")]
+    keep_going: bool,
+
+    /// Stop once more than N routes failed
+    #[arg(long, value_name = "N")]
+    max_failures: Option<usize>,
+}
+
+impl FailureArgs {
+    fn policy(&self) -> FailurePolicy {
+        FailurePolicy {
+            max_failures: if self.fail_fast {
+                Some(0)
+            } else {
+                self.max_failures
+            },
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Bus Route Information Collection and Snapping (Route Processor)
     Route {
-        /// City code to process (default: Wonju -> 32020)
-        #[arg(long, default_value = "32020")]
-        city_code: String,
+        /// City code to process (default: city_code from the configuration)
+        #[arg(long)]
+        city_code: Option<String>,
 
         /// Specific route number (if not specified, all)
         #[arg(short, long)]
@@ -41,6 +91,9 @@ enum Commands {
         /// Snap route paths using OSRM only (skip Tago API)
         #[arg(long)]
         osrm_only: bool,
+
+        #[command(flatten)]
+        failures: FailureArgs,
     },
     /// Bus Schedule Crawling (Schedule Processor)
     Schedule {
@@ -51,30 +104,557 @@ enum Commands {
         /// Output directory
         #[arg(short, long, default_value = "./storage")]
         output_dir: PathBuf,
+
+        /// JSON mapping of raw day type labels to day types (e.g., {"토·공휴일": "holiday"})
+        #[arg(long)]
+        day_types: Option<PathBuf>,
+
+        /// Re-parse the pages archived by a previous run (schedule_archive/<run>) offline
+        #[arg(long)]
+        from_archive: Option<PathBuf>,
+
+        /// Directory containing processed routes (raw_routes/), for featured stops
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Number of detail pages fetched concurrently (overrides schedule.concurrency)
+        #[arg(long)]
+        concurrency: Option<usize>,
+
+        /// Minimum interval between two requests, in milliseconds (overrides schedule.request_interval_ms)
+        #[arg(long)]
+        interval_ms: Option<u64>,
+
+        /// Retries per route on transient failures (overrides schedule.max_retries)
+        #[arg(long)]
+        retries: Option<u32>,
+
+        #[command(flatten)]
+        failures: FailureArgs,
+    },
+    /// Route Identity Registry Linking Schedules to TAGO Routes (Registry Processor)
+    Registry {
+        /// Directory containing processed routes (routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing scraped schedules
+        #[arg(long, default_value = "./storage/schedules")]
+        schedules_dir: PathBuf,
+    },
+    /// Stop-Level Timetable Interpolation (Timetable Processor)
+    Timetable {
+        /// Directory containing processed routes (derived_routes/, routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing scraped schedules
+        #[arg(long, default_value = "./storage/schedules")]
+        schedules_dir: PathBuf,
+
+        /// Directory containing observed travel times (optional input)
+        #[arg(long, default_value = "./storage/travel_times")]
+        travel_times_dir: PathBuf,
+
+        /// Specific route number or ID (if not specified, all)
+        #[arg(short, long)]
+        route: Option<String>,
+
+        /// Output directory
+        #[arg(short, long, default_value = "./storage")]
+        output_dir: PathBuf,
+    },
+    /// Service Calendar Generation with Korean Holidays (Calendar Processor)
+    Calendar {
+        /// First date of the horizon, YYYY-MM-DD (default: today)
+        #[arg(long)]
+        from: Option<NaiveDate>,
+
+        /// Number of days to cover
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+
+        /// Vacation periods and extra holidays (JSON)
+        #[arg(long, value_name = "JSON")]
+        vacations: Option<PathBuf>,
+
+        /// Output directory
+        #[arg(short, long, default_value = "./storage")]
+        output_dir: PathBuf,
+    },
+    /// GTFS-Realtime Feed Server for Live TAGO Data (Realtime Processor)
+    Serve {
+        /// City code to query (default: city_code from the configuration)
+        #[arg(long)]
+        city_code: Option<String>,
+
+        /// Directory containing processed routes (routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: String,
+
+        /// Polling interval in seconds
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+
+        /// Also write the feeds to this directory on every update
+        #[arg(long)]
+        write_dir: Option<PathBuf>,
+
+        /// Directory containing stop-level timetables, for journey planning at /plan
+        #[arg(long, default_value = "./storage/stop_times")]
+        stop_times_dir: PathBuf,
+    },
+    /// Nearest Stations and Routes to a Coordinate (Network Query)
+    Nearest {
+        /// Latitude of the query point
+        #[arg(long, allow_hyphen_values = true)]
+        lat: f64,
+
+        /// Longitude of the query point
+        #[arg(long, allow_hyphen_values = true)]
+        lon: f64,
+
+        /// Only stations and routes within this distance, in meters (default: any distance)
+        #[arg(long)]
+        radius: Option<f64>,
+
+        /// Maximum number of stations and of routes
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// Directory containing processed routes (derived_routes/, routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Walking Transfers Between Nearby Stations (Network Query)
+    Transfers {
+        /// Pair stations at most this far apart, in meters (default: planner.transfer_radius_m)
+        #[arg(long)]
+        radius: Option<f64>,
+
+        /// Directory containing processed routes (routeMap.json); transfers.json is saved here
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+    },
+    /// Journeys Between Stations or Coordinates over Timetables (Journey Planner)
+    Plan {
+        /// Origin: station ID (nodeid) or "lat,lon"
+        #[arg(long, allow_hyphen_values = true)]
+        from: String,
+
+        /// Destination: station ID (nodeid) or "lat,lon"
+        #[arg(long, allow_hyphen_values = true)]
+        to: String,
+
+        /// Departure time, HH:MM
+        #[arg(long)]
+        at: String,
+
+        /// Day type (weekday, saturday, sunday, holiday, ...)
+        #[arg(long, default_value = "weekday")]
+        day: String,
+
+        /// Directory containing processed routes (derived_routes/, routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing stop-level timetables (from the timetable processor)
+        #[arg(long, default_value = "./storage/stop_times")]
+        stop_times_dir: PathBuf,
+
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Areas Reachable Within N Minutes as GeoJSON (Journey Planner)
+    Isochrone {
+        /// Origin: station ID (nodeid) or "lat,lon"
+        #[arg(long, allow_hyphen_values = true)]
+        from: String,
+
+        /// Departure time, HH:MM
+        #[arg(long)]
+        at: String,
+
+        /// Day type (weekday, saturday, sunday, holiday, ...)
+        #[arg(long, default_value = "weekday")]
+        day: String,
+
+        /// Travel time budgets in minutes, one polygon each (e.g., 10,20,30)
+        #[arg(long, value_delimiter = ',', default_value = "10,20,30")]
+        minutes: Vec<u32>,
+
+        /// Grid cell size in meters
+        #[arg(long, default_value_t = 50.0)]
+        cell_size: f64,
+
+        /// Directory containing processed routes (derived_routes/, routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing stop-level timetables (from the timetable processor)
+        #[arg(long, default_value = "./storage/stop_times")]
+        stop_times_dir: PathBuf,
+
+        /// Output GeoJSON file (default: print to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// One Route or Station Across All Datasets (Inspector)
+    Inspect {
+        #[command(subcommand)]
+        command: InspectCommands,
+    },
+    /// Analysis of Archived Vehicle Observations (Analysis Processor)
+    Analyze {
+        #[command(subcommand)]
+        command: AnalyzeCommands,
+    },
+    /// Layered Configuration from polly.toml, Environment and Flags (Config)
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the effective configuration with secrets masked
+    Show,
+}
+
+#[derive(Subcommand)]
+enum InspectCommands {
+    /// Stops, path fit, variants and timetable summary of a route
+    Route {
+        /// Route number (e.g., 34-1) or TAGO route ID
+        route: String,
+
+        /// Directory containing processed routes (raw_routes/, derived_routes/, routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing scraped schedules
+        #[arg(long, default_value = "./storage/schedules")]
+        schedules_dir: PathBuf,
+
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Routes, nearby stations and departures of a station
+    Stop {
+        /// Station ID (nodeid)
+        node_id: String,
+
+        /// Directory containing processed routes (derived_routes/, routeMap.json)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing departure boards (from the timetable processor)
+        #[arg(long, default_value = "./storage/departures")]
+        departures_dir: PathBuf,
+
+        /// Radius for nearby stations, in meters
+        #[arg(long, default_value_t = 200.0)]
+        radius: f64,
+
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AnalyzeCommands {
+    /// Stop-to-stop travel time estimation from collected vehicle positions
+    TravelTimes {
+        /// Directory containing archived observations (*.jsonl)
+        #[arg(short, long, default_value = "./storage/observations")]
+        input_dir: PathBuf,
+
+        /// Directory containing processed routes (derived_routes/)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Specific route number or ID (if not specified, all)
+        #[arg(short, long)]
+        route: Option<String>,
+
+        /// Output directory
+        #[arg(short, long, default_value = "./storage")]
+        output_dir: PathBuf,
+    },
+    /// Schedule adherence analysis against scraped timetables
+    Adherence {
+        /// Directory containing archived observations (*.jsonl)
+        #[arg(short, long, default_value = "./storage/observations")]
+        input_dir: PathBuf,
+
+        /// Directory containing processed routes (derived_routes/)
+        #[arg(long, default_value = "./storage/processed_routes")]
+        routes_dir: PathBuf,
+
+        /// Directory containing scraped schedules
+        #[arg(long, default_value = "./storage/schedules")]
+        schedules_dir: PathBuf,
+
+        /// Specific route number (if not specified, all)
+        #[arg(short, long)]
+        route: Option<String>,
+
+        /// Output directory
+        #[arg(short, long, default_value = "./storage")]
+        output_dir: PathBuf,
     },
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
 
     let cli = Cli::parse();
 
+    // Exit codes tell wrappers whether the output is complete (see `error::exit_code`).
+    match run(cli).await {
+        Ok(RunStatus::Succeeded) => ExitCode::from(exit_code::SUCCESS),
+        Ok(RunStatus::Partial) => ExitCode::from(exit_code::PARTIAL),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(ErrorKind::of(&e).exit_code())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<RunStatus> {
+    logging::init(cli.log_format, cli.log_level.as_deref())?;
+
+    // Command line layer: --set overrides, then processor flags
+    let mut overrides = cli
+        .set
+        .iter()
+        .map(|s| parse_override(s))
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| e.context(ConfigError))?;
+    match &cli.command {
+        Commands::Route { city_code, .. } | Commands::Serve { city_code, .. } => {
+            if let Some(code) = city_code {
+                overrides.push(("city_code".to_string(), code.clone()));
+            }
+        }
+        Commands::Schedule {
+            concurrency,
+            interval_ms,
+            retries,
+            ..
+        } => {
+            let flags = [
+                ("schedule.concurrency", concurrency.map(|v| v.to_string())),
+                (
+                    "schedule.request_interval_ms",
+                    interval_ms.map(|v| v.to_string()),
+                ),
+                ("schedule.max_retries", retries.map(|v| v.to_string())),
+            ];
+            for (key, value) in flags {
+                if let Some(v) = value {
+                    overrides.push((key.to_string(), v));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let loaded =
+        config::load(cli.config.as_deref(), &overrides).map_err(|e| e.context(ConfigError))?;
+    let config = loaded.config.clone();
+
     match cli.command {
         Commands::Route {
-            city_code,
             route,
             output_dir,
             station_map_only,
             osrm_only,
+            failures,
+            ..
+        } => {
+            return route::run(
+                &config,
+                route,
+                output_dir,
+                station_map_only,
+                osrm_only,
+                failures.policy(),
+            )
+            .await;
+        }
+        Commands::Schedule {
+            route,
+            output_dir,
+            day_types,
+            from_archive,
+            routes_dir,
+            failures,
+            ..
+        } => {
+            return schedule::run(
+                &config,
+                route,
+                output_dir,
+                day_types,
+                from_archive,
+                routes_dir,
+                failures.policy(),
+            )
+            .await;
+        }
+        Commands::Registry {
+            routes_dir,
+            schedules_dir,
+        } => {
+            registry::run(routes_dir, schedules_dir).await?;
+        }
+        Commands::Timetable {
+            routes_dir,
+            schedules_dir,
+            travel_times_dir,
+            route,
+            output_dir,
+        } => {
+            timetable::run(
+                routes_dir,
+                schedules_dir,
+                travel_times_dir,
+                route,
+                output_dir,
+            )
+            .await?;
+        }
+        Commands::Calendar {
+            from,
+            days,
+            vacations,
+            output_dir,
+        } => {
+            calendar::run(from, days, vacations, output_dir).await?;
+        }
+        Commands::Serve {
+            routes_dir,
+            bind,
+            interval,
+            write_dir,
+            stop_times_dir,
+            ..
+        } => {
+            serve::run(
+                &config,
+                routes_dir,
+                stop_times_dir,
+                bind,
+                interval,
+                write_dir,
+            )
+            .await?;
+        }
+        Commands::Nearest {
+            lat,
+            lon,
+            radius,
+            limit,
+            routes_dir,
+            json,
         } => {
-            route::run(city_code, route, output_dir, station_map_only, osrm_only).await?;
+            network::run_nearest(routes_dir, lat, lon, radius, limit, json).await?;
+        }
+        Commands::Transfers { radius, routes_dir } => {
+            network::transfer::run(&config, routes_dir, radius).await?;
         }
-        Commands::Schedule { route, output_dir } => {
-            schedule::run(route, output_dir).await?;
+        Commands::Plan {
+            from,
+            to,
+            at,
+            day,
+            routes_dir,
+            stop_times_dir,
+            json,
+        } => {
+            planner::run(&config, routes_dir, stop_times_dir, from, to, at, day, json).await?;
+        }
+        Commands::Isochrone {
+            from,
+            at,
+            day,
+            minutes,
+            cell_size,
+            routes_dir,
+            stop_times_dir,
+            output,
+        } => {
+            planner::isochrone::run(
+                &config,
+                routes_dir,
+                stop_times_dir,
+                from,
+                at,
+                day,
+                minutes,
+                cell_size,
+                output,
+            )
+            .await?;
         }
+        Commands::Inspect { command } => match command {
+            InspectCommands::Route {
+                route,
+                routes_dir,
+                schedules_dir,
+                json,
+            } => {
+                inspect::run_route(routes_dir, schedules_dir, route, json).await?;
+            }
+            InspectCommands::Stop {
+                node_id,
+                routes_dir,
+                departures_dir,
+                radius,
+                json,
+            } => {
+                inspect::run_stop(routes_dir, departures_dir, node_id, radius, json).await?;
+            }
+        },
+        Commands::Analyze { command } => match command {
+            AnalyzeCommands::TravelTimes {
+                input_dir,
+                routes_dir,
+                route,
+                output_dir,
+            } => {
+                analysis::run_travel_times(input_dir, routes_dir, route, output_dir).await?;
+            }
+            AnalyzeCommands::Adherence {
+                input_dir,
+                routes_dir,
+                schedules_dir,
+                route,
+                output_dir,
+            } => {
+                analysis::run_adherence(input_dir, routes_dir, schedules_dir, route, output_dir)
+                    .await?;
+            }
+        },
+        Commands::Config { command } => match command {
+            ConfigCommands::Show => config::show(&loaded)?,
+        },
     }
 
-    Ok(())
+    Ok(RunStatus::Succeeded)
 }
@@ -0,0 +1,191 @@
+// src/network/mod.rs
+
+pub mod model;
+pub mod transfer;
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::network::model::{NearbyRoute, NearbyStation, NearestResult, Network, Station};
+use crate::route::model::{DerivedFeature, DerivedFeatureCollection};
+use crate::route::{DERIVED_DIR, ROUTE_MAP_FILE};
+use crate::storage::{FsStorage, Storage, read_json};
+use crate::utils::geo::{PointIndex, SegmentIndex, SegmentMatch, round_coord, round_m};
+use crate::utils::parse_flexible_string;
+
+/// Entry point of `polly nearest`.
+///
+/// Prints the stations and routes closest to (`lat`, `lon`), at most `limit` of each,
+/// optionally only those within `radius_m` meters. Routes are measured to their snapped
+/// path, so `derived_routes/` must exist in `routes_dir`.
+pub async fn run_nearest(
+    routes_dir: PathBuf,
+    lat: f64,
+    lon: f64,
+    radius_m: Option<f64>,
+    limit: usize,
+    json: bool,
+) -> Result<()> {
+    let network = Network::load(&FsStorage::new(routes_dir))?;
+    let point = (lon, lat);
+
+    let result = NearestResult {
+        lat,
+        lon,
+        radius_m,
+        stations: network
+            .nearest_stations(point, radius_m, limit)
+            .into_iter()
+            .map(|(s, d)| NearbyStation::new(s, d))
+            .collect(),
+        routes: network
+            .nearest_routes(point, radius_m, limit)
+            .into_iter()
+            .map(|m| {
+                let route = &network.routes[*m.item];
+                NearbyRoute {
+                    route_id: route.properties.route_id.clone(),
+                    route_no: route.properties.route_no.clone(),
+                    lat: round_coord(m.point.1),
+                    lon: round_coord(m.point.0),
+                    coord_idx: m.segment,
+                    distance_m: round_m(m.distance_m),
+                }
+            })
+            .collect(),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    match radius_m {
+        Some(r) => println!("Nearest to ({}, {}) within {} m:", lat, lon, r),
+        None => println!("Nearest to ({}, {}):", lat, lon),
+    }
+    println!(" Stations:");
+    for s in &result.stations {
+        println!(
+            "  {:>8.1} m  {} ({}, {})",
+            s.distance_m, s.name, s.node_id, s.node_no
+        );
+    }
+    println!(" Routes:");
+    for r in &result.routes {
+        println!(
+            "  {:>8.1} m  {} ({}) at coordinate {}",
+            r.distance_m, r.route_no, r.route_id, r.coord_idx
+        );
+    }
+
+    Ok(())
+}
+
+impl NearbyStation {
+    pub fn new(station: &Station, distance_m: f64) -> Self {
+        Self {
+            node_id: station.node_id.clone(),
+            name: station.name.clone(),
+            node_no: station.node_no.clone(),
+            lat: station.lat,
+            lon: station.lon,
+            distance_m: round_m(distance_m),
+        }
+    }
+}
+
+impl Network {
+    /// Loads the stations of `routeMap.json` and the routes of `derived_routes/`.
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        let route_map: Value = read_json(storage, ROUTE_MAP_FILE)?;
+
+        let stations = route_map["stations"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(id, s)| {
+                let station = Station {
+                    node_id: id.clone(),
+                    name: s["nodenm"].as_str().unwrap_or_default().to_string(),
+                    node_no: parse_flexible_string(&s["nodeno"]),
+                    lat: s["gpslati"].as_f64()?,
+                    lon: s["gpslong"].as_f64()?,
+                };
+                // TAGO lists some stations without coordinates (0, 0).
+                (station.lat != 0.0 || station.lon != 0.0).then(|| (id.clone(), station))
+            })
+            .collect();
+
+        let mut routes = Vec::new();
+        for key in storage.list(DERIVED_DIR)? {
+            if key.ends_with(".geojson") {
+                let collection: DerivedFeatureCollection = read_json(storage, &key)?;
+                routes.extend(collection.features);
+            }
+        }
+
+        Ok(Self::new(stations, routes))
+    }
+
+    /// Indexes stations (node ID -> Station) and snapped routes.
+    pub fn new(stations: BTreeMap<String, Station>, routes: Vec<DerivedFeature>) -> Self {
+        let station_index = PointIndex::new(
+            stations
+                .values()
+                .map(|s| ((s.lon, s.lat), s.node_id.clone())),
+        );
+        let route_index = SegmentIndex::new(
+            routes
+                .iter()
+                .enumerate()
+                .map(|(i, r)| (i, r.geometry.coordinates.as_slice())),
+        );
+
+        Self {
+            stations,
+            routes,
+            station_index,
+            route_index,
+        }
+    }
+
+    /// Stations nearest to `point` (lon, lat) with their distance in meters, nearest first
+    pub fn nearest_stations(
+        &self,
+        point: (f64, f64),
+        radius_m: Option<f64>,
+        limit: usize,
+    ) -> Vec<(&Station, f64)> {
+        let found: Box<dyn Iterator<Item = (&String, f64)>> = match radius_m {
+            Some(r) => Box::new(self.station_index.within(point, r).into_iter()),
+            None => Box::new(self.station_index.nearest_iter(point)),
+        };
+
+        found
+            .filter_map(|(id, d)| Some((self.stations.get(id)?, d)))
+            .take(limit)
+            .collect()
+    }
+
+    /// The closest point of each route nearest to `point` (lon, lat), nearest first.
+    ///
+    /// Match items are indices into `routes`.
+    pub fn nearest_routes(
+        &self,
+        point: (f64, f64),
+        radius_m: Option<f64>,
+        limit: usize,
+    ) -> Vec<SegmentMatch<'_, usize>> {
+        let found: Box<dyn Iterator<Item = SegmentMatch<'_, usize>>> = match radius_m {
+            Some(r) => Box::new(self.route_index.within(point, r).into_iter()),
+            None => Box::new(self.route_index.nearest_iter(point)),
+        };
+
+        let mut seen = HashSet::new();
+        found.filter(|m| seen.insert(*m.item)).take(limit).collect()
+    }
+}
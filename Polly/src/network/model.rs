@@ -0,0 +1,101 @@
+// src/network/model.rs
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::route::model::DerivedFeature;
+use crate::utils::geo::{PointIndex, SegmentIndex};
+
+// ============================================================================
+// Static Network (Read from routeMap.json and derived_routes/)
+// ============================================================================
+
+/// A station of `routeMap.json`
+#[derive(Debug, Clone)]
+pub struct Station {
+    pub node_id: String,
+    pub name: String,
+    pub node_no: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Stations and snapped routes, with spatial indexes over both
+pub struct Network {
+    // Node ID -> Station
+    pub stations: BTreeMap<String, Station>,
+    pub routes: Vec<DerivedFeature>,
+    // Items are node IDs
+    pub station_index: PointIndex<String>,
+    // Items are indices into `routes`
+    pub route_index: SegmentIndex<usize>,
+}
+
+// ============================================================================
+// Output Models (Printed by `polly nearest`)
+// ============================================================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestResult {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius_m: Option<f64>,
+    // Nearest first
+    pub stations: Vec<NearbyStation>,
+    pub routes: Vec<NearbyRoute>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearbyStation {
+    pub node_id: String,
+    pub name: String,
+    pub node_no: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub distance_m: f64,
+}
+
+/// The closest point of a route's path
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearbyRoute {
+    pub route_id: String,
+    pub route_no: String,
+    pub lat: f64,
+    pub lon: f64,
+    // Index of the path coordinate starting the closest segment
+    pub coord_idx: usize,
+    pub distance_m: f64,
+}
+
+// ============================================================================
+// Output Models (Saved to transfers.json)
+// ============================================================================
+
+/// Walking transfers between nearby stations
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFile {
+    pub generated_at: String,
+    pub radius_m: f64,
+    pub walk_speed_kmh: f64,
+    // Both directions of every pair, sorted by origin and distance
+    pub transfers: Vec<Transfer>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    pub from: String,
+    pub to: String,
+    #[serde(rename = "type")]
+    pub type_: String, // "walk" or "opposite" (same name, other side of the street)
+    // Walking distance: routed, or the straight-line distance times the detour factor
+    pub distance_m: f64,
+    pub walk_secs: u32,
+    pub routed: bool,
+}
@@ -0,0 +1,190 @@
+// src/network/transfer.rs
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use reqwest::Client;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::config::OPPOSITE_STOP_MAX_M;
+use crate::config::model::PollyConfig;
+use crate::error::UpstreamError;
+use crate::network::model::{Network, Station, Transfer, TransferFile};
+use crate::storage::{FsStorage, write_json};
+use crate::utils::geo::round_m;
+
+/// Transfer file saved next to `routeMap.json`
+pub const TRANSFERS_FILE: &str = "transfers.json";
+
+pub const TRANSFER_WALK: &str = "walk";
+pub const TRANSFER_OPPOSITE: &str = "opposite";
+
+/// Entry point of `polly transfers`.
+///
+/// Pairs every station of `routes_dir` with the stations within `radius_m` meters
+/// (default: `planner.transfer_radius_m`) and its opposite-side stop, and saves the walks
+/// to `transfers.json`.
+pub async fn run(config: &PollyConfig, routes_dir: PathBuf, radius_m: Option<f64>) -> Result<()> {
+    let storage = FsStorage::new(routes_dir);
+    let network = Network::load(&storage)?;
+    let radius_m = radius_m.unwrap_or(config.planner.transfer_radius_m);
+    info!(
+        "Pairing {} stations within {} m...",
+        network.stations.len(),
+        radius_m
+    );
+
+    let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+    let file = build_transfers(config, &client, &network, radius_m).await?;
+    write_json(&storage, TRANSFERS_FILE, &file, false)?;
+
+    let opposite = file
+        .transfers
+        .iter()
+        .filter(|t| t.type_ == TRANSFER_OPPOSITE)
+        .count();
+    let routed = file.transfers.iter().filter(|t| t.routed).count();
+    info!(
+        "✓ Saved {} transfers ({} opposite-side, {} routed) to {:?}",
+        file.transfers.len(),
+        opposite,
+        routed,
+        storage.root().join(TRANSFERS_FILE)
+    );
+    Ok(())
+}
+
+/// Walking transfers between all stations within `radius_m` of each other, both ways.
+///
+/// Stations with the same name within `OPPOSITE_STOP_MAX_M` (the stops on both sides of
+/// a street) are always paired as `opposite`, even when `radius_m` is smaller.
+///
+/// With `endpoints.osrm_foot` set, every pair is routed with the foot profile; pairs it
+/// cannot route (and all pairs without it) use the straight-line distance times
+/// `planner.detour_factor`. Walk times are at `planner.walk_speed_kmh`.
+pub async fn build_transfers(
+    config: &PollyConfig,
+    client: &Client,
+    network: &Network,
+    radius_m: f64,
+) -> Result<TransferFile> {
+    if radius_m.is_nan() || radius_m <= 0.0 {
+        bail!("Transfer radius must be positive");
+    }
+    let settings = &config.planner;
+    let speed_mps = settings.walk_speed_kmh / 3.6;
+
+    // Unordered pairs with their straight-line distance and transfer type. Opposite-side
+    // stops are searched on their own distance, independent of the walk radius.
+    let search_m = radius_m.max(OPPOSITE_STOP_MAX_M);
+    let mut pairs: Vec<(&Station, &Station, f64, &str)> = Vec::new();
+    for a in network.stations.values() {
+        for (id, d) in network.station_index.within((a.lon, a.lat), search_m) {
+            if a.node_id < *id
+                && let Some(b) = network.stations.get(id)
+            {
+                let opposite = a.name.trim() == b.name.trim() && d <= OPPOSITE_STOP_MAX_M;
+                if opposite {
+                    pairs.push((a, b, d, TRANSFER_OPPOSITE));
+                } else if d <= radius_m {
+                    pairs.push((a, b, d, TRANSFER_WALK));
+                }
+            }
+        }
+    }
+
+    let foot_url = config.endpoints.osrm_foot.trim_end_matches('/');
+    let routed: Vec<Option<f64>> = if foot_url.is_empty() {
+        vec![None; pairs.len()]
+    } else {
+        let mut routed: Vec<(usize, Option<f64>)> = stream::iter(pairs.iter().enumerate())
+            .map(|(i, (a, b, _, _))| async move {
+                (i, fetch_foot_distance(client, foot_url, a, b).await.ok())
+            })
+            .buffer_unordered(settings.foot_routing_concurrency.max(1))
+            .collect()
+            .await;
+        routed.sort_by_key(|(i, _)| *i);
+
+        let failed = routed.iter().filter(|(_, d)| d.is_none()).count();
+        if failed > 0 {
+            warn!(
+                failed,
+                total = pairs.len(),
+                "{} of {} pairs could not be routed on foot; using straight-line estimates.",
+                failed,
+                pairs.len()
+            );
+        }
+        routed.into_iter().map(|(_, d)| d).collect()
+    };
+
+    let mut transfers = Vec::with_capacity(pairs.len() * 2);
+    for ((a, b, straight_m, type_), routed_m) in pairs.into_iter().zip(routed) {
+        let distance_m = routed_m.unwrap_or(straight_m * settings.detour_factor);
+
+        for (from, to) in [(a, b), (b, a)] {
+            transfers.push(Transfer {
+                from: from.node_id.clone(),
+                to: to.node_id.clone(),
+                type_: type_.to_string(),
+                distance_m: round_m(distance_m),
+                walk_secs: (distance_m / speed_mps).ceil() as u32,
+                routed: routed_m.is_some(),
+            });
+        }
+    }
+    transfers.sort_by(|x, y| {
+        x.from
+            .cmp(&y.from)
+            .then(x.distance_m.total_cmp(&y.distance_m))
+            .then(x.to.cmp(&y.to))
+    });
+
+    Ok(TransferFile {
+        generated_at: Local::now().to_rfc3339(),
+        radius_m,
+        walk_speed_kmh: settings.walk_speed_kmh,
+        transfers,
+    })
+}
+
+/// Walking distance in meters between two stations from a foot-profile OSRM route service
+async fn fetch_foot_distance(
+    client: &Client,
+    base_url: &str,
+    a: &Station,
+    b: &Station,
+) -> Result<f64> {
+    let url = format!(
+        "{}/{:.6},{:.6};{:.6},{:.6}?overview=false&steps=false",
+        base_url, a.lon, a.lat, b.lon, b.lat
+    );
+    let network = |e: reqwest::Error| UpstreamError::Network {
+        service: "OSRM",
+        source: e.without_url(),
+    };
+
+    let resp = client.get(&url).send().await.map_err(network)?;
+    // Proxies and overloaded servers answer with non-JSON error pages.
+    if !resp.status().is_success() {
+        return Err(UpstreamError::Status {
+            service: "OSRM",
+            status: resp.status().as_u16(),
+        }
+        .into());
+    }
+    let json: Value = resp.json().await.map_err(network)?;
+
+    match (
+        json["code"].as_str(),
+        json["routes"][0]["distance"].as_f64(),
+    ) {
+        (Some("Ok"), Some(distance)) => Ok(distance),
+        (code, _) => bail!("OSRM foot route failed ({})", code.unwrap_or("no code")),
+    }
+}
@@ -0,0 +1,489 @@
+// src/planner/isochrone.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+use tracing::info;
+
+use crate::config::ISOCHRONE_MAX_CELLS;
+use crate::config::model::PollyConfig;
+use crate::planner::model::{
+    IsochroneCollection, IsochroneFeature, IsochroneGeometry, IsochroneProperties, Place, Planner,
+};
+use crate::schedule::service_time::ServiceTime;
+use crate::utils::ensure_dir;
+use crate::utils::geo::{Enu, LocalProjection, round_coord};
+
+/// Entry point of `polly isochrone`.
+///
+/// Saves the areas reachable from `from` within each of `minutes`, leaving at `at` on
+/// `day_type`, to `output` as GeoJSON (or prints them when no output is given).
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    config: &PollyConfig,
+    routes_dir: PathBuf,
+    stop_times_dir: PathBuf,
+    from: String,
+    at: String,
+    day_type: String,
+    minutes: Vec<u32>,
+    cell_m: f64,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let planner = Planner::load(&routes_dir, &stop_times_dir, config.planner.clone())?;
+    let collection = planner.isochrones(
+        &Place::parse(&from)?,
+        ServiceTime::parse(&at)?,
+        &day_type,
+        &minutes,
+        cell_m,
+    )?;
+
+    let Some(path) = output else {
+        println!("{}", serde_json::to_string(&collection)?);
+        return Ok(());
+    };
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        ensure_dir(dir)?;
+    }
+    fs::write(&path, serde_json::to_string(&collection)?)?;
+    for f in collection.features.iter().rev() {
+        info!(
+            " {:>3} min: {} station(s), {:.2} km²",
+            f.properties.minutes, f.properties.stops, f.properties.area_km2
+        );
+    }
+    info!("✓ Saved isochrones to {:?}", path);
+    Ok(())
+}
+
+impl Planner {
+    /// Areas reachable from `from` within each of `minutes`, leaving at `at` on `day_type`.
+    ///
+    /// Earliest arrivals at every station come from the planner (bus rides along the
+    /// timetables, walking transfers); from the origin and from every station reached in
+    /// time, the rest of the budget is spent walking. Walking times are evaluated on a grid
+    /// of `cell_m` meter cells in a tangent plane at the origin, and the cells within each
+    /// budget are outlined into a MultiPolygon. Features are nested, largest first.
+    pub fn isochrones(
+        &self,
+        from: &Place,
+        at: ServiceTime,
+        day_type: &str,
+        minutes: &[u32],
+        cell_m: f64,
+    ) -> Result<IsochroneCollection> {
+        let mut minutes: Vec<u32> = minutes.iter().copied().filter(|&m| m > 0).collect();
+        minutes.sort_unstable_by(|a, b| b.cmp(a));
+        minutes.dedup();
+        let Some(&max_minutes) = minutes.first() else {
+            bail!("No isochrone duration given");
+        };
+        if cell_m.is_nan() || cell_m < 1.0 {
+            bail!("Cell size must be at least 1 meter");
+        }
+
+        let origin_stop = self.resolve(from)?;
+        let Some((lon, lat)) = self.place_coord(from, origin_stop) else {
+            bail!("The origin station has no coordinates");
+        };
+        let arrivals = self.earliest_arrivals(from, at, day_type)?;
+
+        let start = at.minutes() * 60;
+        let Some(limit) = max_minutes
+            .checked_mul(60)
+            .and_then(|budget| start.checked_add(budget))
+        else {
+            bail!("Isochrone duration of {} minutes is too long", max_minutes);
+        };
+        // Straight-line walking speed (m/s)
+        let speed = self.settings.walk_speed_kmh / 3.6 / self.settings.detour_factor;
+
+        // Places to walk on from: (east, north, arrival)
+        let projection = LocalProjection::new(lon, lat);
+        let mut seeds = vec![(0.0, 0.0, start)];
+        for (s, arrival) in arrivals.iter().enumerate() {
+            if let (Some(arrival), Some(coord)) = (arrival, self.stops[s].coord)
+                && *arrival < limit
+            {
+                let enu = projection.to_enu(coord.0, coord.1);
+                seeds.push((enu.east, enu.north, *arrival));
+            }
+        }
+
+        // Grid covering every walk
+        let reach = |arrival: u32| (limit - arrival) as f64 * speed;
+        let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+        let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+        for &(x, y, arrival) in &seeds {
+            let r = reach(arrival);
+            min_x = min_x.min(x - r);
+            min_y = min_y.min(y - r);
+            max_x = max_x.max(x + r);
+            max_y = max_y.max(y + r);
+        }
+        let x0 = (min_x / cell_m).floor() * cell_m;
+        let y0 = (min_y / cell_m).floor() * cell_m;
+        let nx = ((max_x - x0) / cell_m).ceil() as usize + 1;
+        let ny = ((max_y - y0) / cell_m).ceil() as usize + 1;
+        if nx * ny > ISOCHRONE_MAX_CELLS {
+            bail!(
+                "The isochrone grid would have {} cells (at most {}); use a larger cell size",
+                nx * ny,
+                ISOCHRONE_MAX_CELLS
+            );
+        }
+
+        // Earliest time at every cell center
+        let mut times = vec![u32::MAX; nx * ny];
+        for &(x, y, arrival) in &seeds {
+            let r = reach(arrival);
+            let i_range = cell_range(x - r, x + r, x0, cell_m, nx);
+            let j_range = cell_range(y - r, y + r, y0, cell_m, ny);
+            for j in j_range {
+                let cy = y0 + (j as f64 + 0.5) * cell_m;
+                for i in i_range.clone() {
+                    let cx = x0 + (i as f64 + 0.5) * cell_m;
+                    let d = (cx - x).hypot(cy - y);
+                    if d <= r {
+                        let t = arrival + (d / speed).ceil() as u32;
+                        let cell = &mut times[j * nx + i];
+                        *cell = (*cell).min(t);
+                    }
+                }
+            }
+        }
+
+        let origin = self.place_point(from);
+        let to_lonlat = |(i, j): (i64, i64)| {
+            let (lon, lat) = projection.to_lonlat(Enu {
+                east: x0 + i as f64 * cell_m,
+                north: y0 + j as f64 * cell_m,
+                up: 0.0,
+            });
+            vec![round_coord(lon), round_coord(lat)]
+        };
+
+        let features = minutes
+            .iter()
+            .map(|&m| {
+                let budget = start + m * 60;
+                let filled = |i: i64, j: i64| {
+                    i >= 0
+                        && j >= 0
+                        && (i as usize) < nx
+                        && (j as usize) < ny
+                        && times[j as usize * nx + i as usize] <= budget
+                };
+                let cells = times.iter().filter(|&&t| t <= budget).count();
+
+                let coordinates = polygons(trace_rings(nx, ny, &filled))
+                    .into_iter()
+                    .map(|rings| {
+                        rings
+                            .into_iter()
+                            .map(|ring| ring.into_iter().map(to_lonlat).collect())
+                            .collect()
+                    })
+                    .collect();
+
+                IsochroneFeature {
+                    type_: "Feature".to_string(),
+                    properties: IsochroneProperties {
+                        minutes: m,
+                        origin: origin.clone(),
+                        at: at.to_string(),
+                        day_type: day_type.to_string(),
+                        stops: arrivals
+                            .iter()
+                            .filter(|a| a.is_some_and(|a| a <= budget))
+                            .count(),
+                        area_km2: (cells as f64 * cell_m * cell_m / 1_000_000.0 * 100.0).round()
+                            / 100.0,
+                    },
+                    geometry: IsochroneGeometry {
+                        type_: "MultiPolygon".to_string(),
+                        coordinates,
+                    },
+                }
+            })
+            .collect();
+
+        Ok(IsochroneCollection {
+            type_: "FeatureCollection".to_string(),
+            features,
+        })
+    }
+}
+
+/// Indices of the cells overlapping [from, to] along one axis of the grid
+fn cell_range(from: f64, to: f64, origin: f64, cell_m: f64, n: usize) -> std::ops::Range<usize> {
+    let first = ((from - origin) / cell_m).floor().max(0.0) as usize;
+    let last = (((to - origin) / cell_m).ceil().max(0.0) as usize).min(n);
+    first.min(last)..last
+}
+
+// ============================================================================
+// Cell Outlines
+// ============================================================================
+
+/// Closed boundary rings of the filled cells, on the grid corners (cell (i, j) spans
+/// corners (i, j) to (i + 1, j + 1)).
+///
+/// Edges run with the filled cells on their left, so outer rings are counterclockwise
+/// and holes clockwise. Cells touching only at a corner get separate rings.
+fn trace_rings(nx: usize, ny: usize, filled: &dyn Fn(i64, i64) -> bool) -> Vec<Vec<(i64, i64)>> {
+    let mut edges: Vec<((i64, i64), (i64, i64))> = Vec::new();
+    for j in 0..ny as i64 {
+        for i in 0..nx as i64 {
+            if !filled(i, j) {
+                continue;
+            }
+            if !filled(i, j - 1) {
+                edges.push(((i, j), (i + 1, j)));
+            }
+            if !filled(i + 1, j) {
+                edges.push(((i + 1, j), (i + 1, j + 1)));
+            }
+            if !filled(i, j + 1) {
+                edges.push(((i + 1, j + 1), (i, j + 1)));
+            }
+            if !filled(i - 1, j) {
+                edges.push(((i, j + 1), (i, j)));
+            }
+        }
+    }
+
+    let mut outgoing: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (e, (from, _)) in edges.iter().enumerate() {
+        outgoing.entry(*from).or_default().push(e);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut edge = first;
+        loop {
+            used[edge] = true;
+            let (from, to) = edges[edge];
+            ring.push(from);
+
+            // Where two rings meet at a corner, turn left to stay on this one
+            let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+            let left = (to.0 - dy, to.1 + dx);
+            let candidates: Vec<usize> = outgoing[&to]
+                .iter()
+                .copied()
+                .filter(|&e| !used[e] || e == first)
+                .collect();
+            let next = candidates
+                .iter()
+                .copied()
+                .find(|&e| edges[e].1 == left)
+                .or_else(|| candidates.first().copied());
+
+            match next {
+                Some(e) if e != first => edge = e,
+                _ => break,
+            }
+        }
+        rings.push(simplify(ring));
+    }
+    rings
+}
+
+/// Drops the corners between collinear edges and closes the ring
+fn simplify(ring: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    let n = ring.len();
+    let mut out: Vec<(i64, i64)> = (0..n)
+        .filter(|&k| {
+            let (a, b, c) = (ring[(k + n - 1) % n], ring[k], ring[(k + 1) % n]);
+            (b.0 - a.0) * (c.1 - b.1) != (b.1 - a.1) * (c.0 - b.0)
+        })
+        .map(|k| ring[k])
+        .collect();
+    if let Some(&first) = out.first() {
+        out.push(first);
+    }
+    out
+}
+
+/// Twice the signed area of a closed ring (positive when counterclockwise)
+fn signed_area2(ring: &[(i64, i64)]) -> i64 {
+    ring.windows(2)
+        .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+        .sum()
+}
+
+/// Groups rings into polygons: every counterclockwise ring with the holes it encloses
+fn polygons(rings: Vec<Vec<(i64, i64)>>) -> Vec<Vec<Vec<(i64, i64)>>> {
+    let (outers, holes): (Vec<_>, Vec<_>) = rings.into_iter().partition(|r| signed_area2(r) > 0);
+    let mut polygons: Vec<Vec<Vec<(i64, i64)>>> = outers.into_iter().map(|r| vec![r]).collect();
+
+    for hole in holes {
+        // Center of the empty cell right of the hole's first edge (never on a grid line)
+        let (a, b) = (hole[0], hole[1]);
+        let (dx, dy) = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+        let probe = (
+            a.0 as f64 + dx as f64 * 0.5 + dy as f64 * 0.5,
+            a.1 as f64 + dy as f64 * 0.5 - dx as f64 * 0.5,
+        );
+
+        let owner = polygons
+            .iter_mut()
+            .filter(|p| contains(&p[0], probe))
+            .min_by_key(|p| signed_area2(&p[0]));
+        if let Some(polygon) = owner {
+            polygon.push(hole);
+        }
+    }
+    polygons
+}
+
+/// Even-odd point-in-ring test
+fn contains(ring: &[(i64, i64)], (x, y): (f64, f64)) -> bool {
+    let mut inside = false;
+    for w in ring.windows(2) {
+        let ((x1, y1), (x2, y2)) = (
+            (w[0].0 as f64, w[0].1 as f64),
+            (w[1].0 as f64, w[1].1 as f64),
+        );
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rings of a grid drawn top row first, `#` for filled cells
+    fn rings(rows: &[&str]) -> Vec<Vec<(i64, i64)>> {
+        let ny = rows.len();
+        let nx = rows[0].len();
+        let filled = |i: i64, j: i64| {
+            i >= 0
+                && j >= 0
+                && (i as usize) < nx
+                && (j as usize) < ny
+                && rows[ny - 1 - j as usize].as_bytes()[i as usize] == b'#'
+        };
+        trace_rings(nx, ny, &filled)
+    }
+
+    /// A closed ring starting at its smallest corner, to compare rings traced from
+    /// any edge
+    fn normalized(ring: &[(i64, i64)]) -> Vec<(i64, i64)> {
+        assert_eq!(ring.first(), ring.last(), "ring is not closed");
+        let open = &ring[..ring.len() - 1];
+        let start = (0..open.len()).min_by_key(|&k| open[k]).unwrap();
+        let mut out: Vec<_> = open[start..]
+            .iter()
+            .chain(&open[..start])
+            .copied()
+            .collect();
+        out.push(out[0]);
+        out
+    }
+
+    #[test]
+    fn single_cell_is_a_counterclockwise_square() {
+        let rings = rings(&["#"]);
+
+        assert_eq!(rings.len(), 1);
+        assert_eq!(
+            normalized(&rings[0]),
+            [(0, 0), (1, 0), (1, 1), (0, 1), (0, 0)]
+        );
+        assert_eq!(signed_area2(&rings[0]), 2);
+    }
+
+    #[test]
+    fn l_shape_keeps_only_its_corners() {
+        let rings = rings(&[
+            "#.", //
+            "##",
+        ]);
+
+        assert_eq!(rings.len(), 1);
+        assert_eq!(
+            normalized(&rings[0]),
+            [(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2), (0, 0)]
+        );
+        assert_eq!(signed_area2(&rings[0]), 6);
+    }
+
+    #[test]
+    fn simplify_drops_collinear_corners_and_closes_the_ring() {
+        let ring = vec![(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)];
+        assert_eq!(simplify(ring), [(0, 0), (2, 0), (2, 1), (0, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn ring_with_a_hole_is_one_polygon() {
+        let polygons = polygons(rings(&[
+            "###", //
+            "#.#", "###",
+        ]));
+
+        assert_eq!(polygons.len(), 1);
+        let [outer, hole] = polygons[0].as_slice() else {
+            panic!("expected an outer ring and a hole: {:?}", polygons[0]);
+        };
+        assert_eq!(signed_area2(outer), 18);
+        assert_eq!(signed_area2(hole), -2);
+        assert_eq!(normalized(hole), [(1, 1), (1, 2), (2, 2), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn island_in_a_hole_is_its_own_polygon() {
+        let polygons = polygons(rings(&[
+            "#####", //
+            "#...#", "#.#.#", "#...#", "#####",
+        ]));
+
+        let mut areas: Vec<Vec<i64>> = polygons
+            .iter()
+            .map(|p| p.iter().map(|r| signed_area2(r)).collect())
+            .collect();
+        areas.sort();
+        // The hole belongs to the outer ring, not to the island inside it
+        assert_eq!(areas, [vec![2], vec![50, -18]]);
+    }
+
+    #[test]
+    fn cells_touching_at_a_corner_get_separate_rings() {
+        for rows in [[".#", "#."], ["#.", ".#"]] {
+            let rings = rings(&rows);
+
+            assert_eq!(rings.len(), 2, "{:?}", rows);
+            for ring in &rings {
+                assert_eq!(ring.len(), 5, "{:?}: {:?}", rows, ring);
+                assert_eq!(signed_area2(ring), 2);
+            }
+            assert_eq!(polygons(rings).len(), 2);
+        }
+    }
+
+    #[test]
+    fn contains_uses_the_even_odd_rule() {
+        let outer = [(0, 0), (3, 0), (3, 3), (0, 3), (0, 0)];
+        let hole = [(1, 1), (1, 2), (2, 2), (2, 1), (1, 1)];
+
+        assert!(contains(&outer, (0.5, 0.5)));
+        assert!(contains(&outer, (1.5, 1.5)));
+        assert!(!contains(&outer, (3.5, 1.5)));
+        assert!(!contains(&outer, (-0.5, 1.5)));
+        assert!(contains(&hole, (1.5, 1.5)));
+        assert!(!contains(&hole, (0.5, 1.5)));
+    }
+}
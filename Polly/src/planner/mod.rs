@@ -0,0 +1,1107 @@
+// src/planner/mod.rs
+
+pub mod isochrone;
+pub mod model;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use tracing::warn;
+
+use crate::config::model::{PlannerSettings, PollyConfig};
+use crate::error::NoServiceOnDay;
+use crate::network::model::{Network, TransferFile};
+use crate::network::transfer::TRANSFERS_FILE;
+use crate::planner::model::{
+    Footpath, Itinerary, Leg, Pattern, Place, PlacePoint, PlanResult, Planner, PlannerStop, Trip,
+};
+use crate::schedule::day_type::fallback_chain;
+use crate::schedule::service_time::ServiceTime;
+use crate::storage::{FsStorage, read_json};
+use crate::timetable::model::StopTimesFile;
+use crate::utils::geo::{meters_between, round_m};
+
+/// Entry point of `polly plan`.
+///
+/// Plans journeys from `from` to `to` (station IDs or "lat,lon") leaving at `at`
+/// ("HH:MM") on `day_type`, over the timetables in `stop_times_dir` and the stations
+/// of `routes_dir`.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    config: &PollyConfig,
+    routes_dir: PathBuf,
+    stop_times_dir: PathBuf,
+    from: String,
+    to: String,
+    at: String,
+    day_type: String,
+    json: bool,
+) -> Result<()> {
+    let planner = Planner::load(&routes_dir, &stop_times_dir, config.planner.clone())?;
+    let result = planner.plan(
+        &Place::parse(&from)?,
+        &Place::parse(&to)?,
+        ServiceTime::parse(&at)?,
+        &day_type,
+    )?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    print_plan(&result);
+    Ok(())
+}
+
+fn print_plan(result: &PlanResult) {
+    println!(
+        "Journeys from {} to {} at {} ({}):",
+        place_label(&result.from),
+        place_label(&result.to),
+        result.at,
+        result.day_type
+    );
+    if result.itineraries.is_empty() {
+        println!(" No journey found.");
+        return;
+    }
+
+    for (i, it) in result.itineraries.iter().enumerate() {
+        println!(
+            " {}. {} -> {} ({} min, {} transfer(s), walk {:.0} m)",
+            i + 1,
+            it.departure,
+            it.arrival,
+            it.duration_mins,
+            it.transfers,
+            it.walk_distance_m
+        );
+        for leg in &it.legs {
+            let what = match (&leg.route_no, &leg.headsign) {
+                (Some(no), Some(headsign)) => format!(
+                    "Bus {} to {}, {} stop(s)",
+                    no,
+                    headsign,
+                    leg.stops.unwrap_or_default()
+                ),
+                _ => format!("Walk {:.0} m", leg.distance_m.unwrap_or_default()),
+            };
+            println!(
+                "    {}-{}  {}: {} -> {}",
+                leg.departure,
+                leg.arrival,
+                what,
+                place_label(&leg.from),
+                place_label(&leg.to)
+            );
+        }
+    }
+}
+
+fn place_label(place: &PlacePoint) -> String {
+    match (&place.name, &place.node_id, place.lat, place.lon) {
+        (Some(name), Some(id), _, _) => format!("{} ({})", name, id),
+        (_, _, Some(lat), Some(lon)) => format!("({}, {})", lat, lon),
+        _ => "?".to_string(),
+    }
+}
+
+/// Reads every `*.json` file of `stop_times_dir`, skipping unreadable files.
+pub fn read_stop_times(stop_times_dir: &Path) -> Result<Vec<StopTimesFile>> {
+    let mut paths: Vec<_> = fs::read_dir(stop_times_dir)
+        .with_context(|| format!("Failed to read {:?}", stop_times_dir))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(serde_json::from_str(&content)?))
+        {
+            Ok(f) => files.push(f),
+            Err(e) => warn!(path = ?path, "Skipping {:?}: {}", path, e),
+        }
+    }
+    Ok(files)
+}
+
+impl Place {
+    /// Parses a station ID ("WJB251036041") or a coordinate ("37.3422,127.9202", lat first).
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        if let Some((lat, lon)) = text.split_once(',') {
+            let (Ok(lat), Ok(lon)) = (lat.trim().parse::<f64>(), lon.trim().parse::<f64>()) else {
+                bail!("Invalid coordinate '{}' (expected \"lat,lon\")", text);
+            };
+            return Ok(Self::Coordinate(lon, lat));
+        }
+        if text.is_empty() {
+            bail!("Empty place");
+        }
+        Ok(Self::Stop(text.to_string()))
+    }
+}
+
+// ============================================================================
+// RAPTOR
+// ============================================================================
+
+/// How a stop was reached in a round
+#[derive(Debug, Clone, Copy)]
+enum LabelKind {
+    // Walked from the query origin
+    Access {
+        distance_m: f64,
+    },
+    // Rode `trip` of `pattern` from position `board` to `alight`
+    Ride {
+        pattern: usize,
+        trip: usize,
+        board: usize,
+        alight: usize,
+    },
+    // Walked from another stop after arriving there in the same round
+    Walk {
+        from: usize,
+        distance_m: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Label {
+    // Seconds since the service-day midnight
+    arrival: u32,
+    kind: LabelKind,
+}
+
+/// A leg before its places are resolved (`None` is the query origin or destination)
+enum Step {
+    Walk {
+        from: Option<usize>,
+        to: Option<usize>,
+        departure: u32,
+        arrival: u32,
+        distance_m: f64,
+    },
+    Ride {
+        pattern: usize,
+        trip: usize,
+        board: usize,
+        alight: usize,
+    },
+}
+
+impl Planner {
+    /// Loads the network of `routes_dir` and the timetables of `stop_times_dir`, and the
+    /// walking transfers of `transfers.json` when `routes_dir` has one.
+    pub fn load(
+        routes_dir: &Path,
+        stop_times_dir: &Path,
+        settings: PlannerSettings,
+    ) -> Result<Self> {
+        let storage = FsStorage::new(routes_dir);
+        let network = Network::load(&storage)?;
+        let files = read_stop_times(stop_times_dir)?;
+        let mut planner = Self::new(network, &files, settings);
+
+        if routes_dir.join(TRANSFERS_FILE).exists() {
+            let transfers: TransferFile = read_json(&storage, TRANSFERS_FILE)?;
+            planner.use_transfers(&transfers);
+        }
+        Ok(planner)
+    }
+
+    /// Replaces the straight-line walking transfers with those of a transfer file
+    /// (`polly transfers`, possibly routed on foot). Walk times follow the settings' speed.
+    pub fn use_transfers(&mut self, file: &TransferFile) {
+        let speed_mps = self.settings.walk_speed_kmh / 3.6;
+        let mut footpaths = vec![Vec::new(); self.stops.len()];
+        for t in &file.transfers {
+            if let (Some(&from), Some(&to)) =
+                (self.stop_lookup.get(&t.from), self.stop_lookup.get(&t.to))
+            {
+                footpaths[from].push(Footpath {
+                    to,
+                    distance_m: t.distance_m,
+                    walk_secs: (t.distance_m / speed_mps).ceil() as u32,
+                });
+            }
+        }
+        self.footpaths = footpaths;
+    }
+
+    /// Builds patterns (one per route direction), the stops they serve and the walking
+    /// transfers between stops within `settings.transfer_radius_m`.
+    pub fn new(network: Network, files: &[StopTimesFile], settings: PlannerSettings) -> Self {
+        let mut stops: Vec<PlannerStop> = Vec::new();
+        let mut stop_lookup: HashMap<String, usize> = HashMap::new();
+        let mut patterns = Vec::new();
+
+        for file in files {
+            for direction in &file.directions {
+                let pattern_stops: Vec<usize> = direction
+                    .stops
+                    .iter()
+                    .map(|s| {
+                        *stop_lookup.entry(s.id.clone()).or_insert_with(|| {
+                            stops.push(PlannerStop {
+                                node_id: s.id.clone(),
+                                name: s.name.clone(),
+                                coord: network.stations.get(&s.id).map(|st| (st.lon, st.lat)),
+                            });
+                            stops.len() - 1
+                        })
+                    })
+                    .collect();
+
+                let trips = direction
+                    .day_types
+                    .iter()
+                    .map(|(day_type, trips)| {
+                        let mut parsed: Vec<Trip> = trips
+                            .iter()
+                            .filter_map(|t| {
+                                let times = t
+                                    .times
+                                    .iter()
+                                    .map(|s| ServiceTime::parse(s).map(|st| st.minutes() * 60))
+                                    .collect::<Result<Vec<_>>>()
+                                    .ok()?;
+                                (times.len() == pattern_stops.len()).then_some(Trip { times })
+                            })
+                            .collect();
+                        parsed.sort_by_key(|t| t.times.first().copied());
+                        (day_type.clone(), parsed)
+                    })
+                    .collect();
+
+                patterns.push(Pattern {
+                    route_id: file.route_id.clone(),
+                    route_no: file.route_no.clone(),
+                    direction: direction.direction.clone(),
+                    headsign: direction.headsign.clone(),
+                    stops: pattern_stops,
+                    trips,
+                });
+            }
+        }
+
+        let mut stop_patterns = vec![Vec::new(); stops.len()];
+        for (p, pattern) in patterns.iter().enumerate() {
+            for (pos, &s) in pattern.stops.iter().enumerate() {
+                stop_patterns[s].push((p, pos));
+            }
+        }
+
+        let mut planner = Self {
+            settings,
+            network,
+            stops,
+            stop_lookup,
+            patterns,
+            stop_patterns,
+            footpaths: Vec::new(),
+        };
+        planner.footpaths = (0..planner.stops.len())
+            .map(|s| match planner.stops[s].coord {
+                Some(coord) => planner
+                    .stops_within(coord, planner.settings.transfer_radius_m)
+                    .into_iter()
+                    .filter(|f| f.to != s)
+                    .collect(),
+                None => Vec::new(),
+            })
+            .collect();
+        planner
+    }
+
+    /// Walks from `point` (lon, lat) to every served stop within `radius_m`, nearest first
+    pub fn stops_within(&self, point: (f64, f64), radius_m: f64) -> Vec<Footpath> {
+        self.network
+            .station_index
+            .within(point, radius_m)
+            .into_iter()
+            .filter_map(|(id, d)| {
+                let (distance_m, walk_secs) = self.walk(d);
+                Some(Footpath {
+                    to: *self.stop_lookup.get(id)?,
+                    distance_m,
+                    walk_secs,
+                })
+            })
+            .collect()
+    }
+
+    /// Walking distance and time for a straight-line distance in meters
+    pub fn walk(&self, straight_m: f64) -> (f64, u32) {
+        let distance_m = straight_m * self.settings.detour_factor;
+        let speed_mps = self.settings.walk_speed_kmh / 3.6;
+        (distance_m, (distance_m / speed_mps).ceil() as u32)
+    }
+
+    /// Trips of every pattern running on `day_type`, following the fallback chain per pattern
+    pub fn trips_on(&self, day_type: &str) -> Vec<&[Trip]> {
+        let chain = fallback_chain(day_type);
+        self.patterns
+            .iter()
+            .map(|p| {
+                chain
+                    .iter()
+                    .find_map(|d| p.trips.get(*d))
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Journeys from `from` to `to` leaving at `at` on `day_type`.
+    ///
+    /// Runs RAPTOR rounds (one more bus ride per round, up to `max_transfers` transfers),
+    /// each followed by walking transfers, and returns the Pareto set of arrival time and
+    /// number of rides: fewer rides first, each later journey arriving strictly earlier.
+    /// A direct walk is offered when the places are within `access_radius_m`.
+    pub fn plan(
+        &self,
+        from: &Place,
+        to: &Place,
+        at: ServiceTime,
+        day_type: &str,
+    ) -> Result<PlanResult> {
+        let day_trips = self.trips_on(day_type);
+        if day_trips.iter().all(|t| t.is_empty()) {
+            return Err(NoServiceOnDay {
+                day_type: day_type.to_string(),
+            }
+            .into());
+        }
+
+        let origin = self.resolve(from)?;
+        let destination = self.resolve(to)?;
+        let start = at.minutes() * 60;
+        let access = self.endpoints(from, origin)?;
+        let egress = self.endpoints(to, destination)?;
+
+        let mut itineraries = Vec::new();
+        let mut best_arrival = u32::MAX;
+
+        // Direct walk
+        if let (Some(a), Some(b)) = (
+            self.place_coord(from, origin),
+            self.place_coord(to, destination),
+        ) {
+            let straight = meters_between(a.0, a.1, b.0, b.1);
+            if straight <= self.settings.access_radius_m {
+                let (distance_m, walk_secs) = self.walk(straight);
+                best_arrival = start + walk_secs;
+                let steps = vec![Step::Walk {
+                    from: None,
+                    to: None,
+                    departure: start,
+                    arrival: best_arrival,
+                    distance_m,
+                }];
+                itineraries.push(self.itinerary(steps, from, to, &day_trips));
+            }
+        }
+
+        // One journey per number of rides, if it arrives earlier than the ones before
+        let rounds = self.raptor(&access, matches!(from, Place::Stop(_)), start, &day_trips);
+        for k in 1..rounds.len() {
+            let arrival = egress
+                .iter()
+                .filter_map(|f| Some((rounds[k][f.to]?.arrival + f.walk_secs, f)))
+                .min_by_key(|(t, _)| *t);
+            if let Some((arrival, f)) = arrival
+                && arrival < best_arrival
+            {
+                best_arrival = arrival;
+                let steps = self.reconstruct(&rounds, k, f, start, &day_trips);
+                itineraries.push(self.itinerary(steps, from, to, &day_trips));
+            }
+        }
+
+        Ok(PlanResult {
+            from: self.place_point(from),
+            to: self.place_point(to),
+            at: at.to_string(),
+            day_type: day_type.to_string(),
+            itineraries,
+        })
+    }
+
+    /// Earliest arrival (seconds since the service-day midnight) at every stop from
+    /// `from` leaving at `at` on `day_type`, `None` for unreachable stops
+    pub fn earliest_arrivals(
+        &self,
+        from: &Place,
+        at: ServiceTime,
+        day_type: &str,
+    ) -> Result<Vec<Option<u32>>> {
+        let day_trips = self.trips_on(day_type);
+        if day_trips.iter().all(|t| t.is_empty()) {
+            return Err(NoServiceOnDay {
+                day_type: day_type.to_string(),
+            }
+            .into());
+        }
+
+        let origin = self.resolve(from)?;
+        let access = self.endpoints(from, origin)?;
+        let rounds = self.raptor(
+            &access,
+            matches!(from, Place::Stop(_)),
+            at.minutes() * 60,
+            &day_trips,
+        );
+
+        // Labels carry over between rounds, so the last round holds the best of each stop
+        Ok(rounds
+            .last()
+            .map(|r| r.iter().map(|l| l.map(|l| l.arrival)).collect())
+            .unwrap_or_default())
+    }
+
+    /// RAPTOR rounds from the `access` stops: round 0 holds the walks from the origin
+    /// (and the walking transfers from the origin stop when `walk_from_origin`), and
+    /// every later round one more bus ride, until a round improves no stop.
+    fn raptor(
+        &self,
+        access: &[Footpath],
+        walk_from_origin: bool,
+        start: u32,
+        day_trips: &[&[Trip]],
+    ) -> Vec<Vec<Option<Label>>> {
+        let n = self.stops.len();
+        let mut best = vec![u32::MAX; n];
+        let mut round: Vec<Option<Label>> = vec![None; n];
+        let mut marked = vec![false; n];
+
+        // Round 0: the origin stop, or the stops within walking distance of a coordinate
+        for f in access {
+            let arrival = start + f.walk_secs;
+            if arrival < best[f.to] {
+                best[f.to] = arrival;
+                round[f.to] = Some(Label {
+                    arrival,
+                    kind: LabelKind::Access {
+                        distance_m: f.distance_m,
+                    },
+                });
+                marked[f.to] = true;
+            }
+        }
+        if walk_from_origin {
+            self.relax_footpaths(&mut round, &mut best, &mut marked);
+        }
+
+        let mut rounds = vec![round];
+        for _ in 0..=self.settings.max_transfers {
+            let prev = rounds.last().unwrap();
+            let mut round = prev.clone();
+            let mut improved = vec![false; n];
+
+            // Patterns to scan, from the earliest marked stop of each
+            let mut queue: HashMap<usize, usize> = HashMap::new();
+            for s in (0..n).filter(|&s| marked[s]) {
+                for &(p, pos) in &self.stop_patterns[s] {
+                    queue
+                        .entry(p)
+                        .and_modify(|e| *e = (*e).min(pos))
+                        .or_insert(pos);
+                }
+            }
+            let mut queue: Vec<_> = queue.into_iter().collect();
+            queue.sort_unstable();
+
+            for (p, first) in queue {
+                let pattern = &self.patterns[p];
+                let trips = day_trips[p];
+                // (trip, boarding position)
+                let mut current: Option<(usize, usize)> = None;
+
+                for pos in first..pattern.stops.len() {
+                    let s = pattern.stops[pos];
+
+                    if let Some((trip, board)) = current {
+                        let arrival = trips[trip].times[pos];
+                        if arrival < best[s] {
+                            best[s] = arrival;
+                            round[s] = Some(Label {
+                                arrival,
+                                kind: LabelKind::Ride {
+                                    pattern: p,
+                                    trip,
+                                    board,
+                                    alight: pos,
+                                },
+                            });
+                            improved[s] = true;
+                        }
+                    }
+
+                    // Catch an earlier trip here if the stop was reached in the previous round
+                    if let Some(label) = prev[s] {
+                        let catchable = trips
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, t)| t.times[pos] >= label.arrival)
+                            .min_by_key(|(_, t)| t.times[pos])
+                            .map(|(i, _)| i);
+                        if let Some(trip) = catchable
+                            && current
+                                .is_none_or(|(c, _)| trips[trip].times[pos] < trips[c].times[pos])
+                        {
+                            current = Some((trip, pos));
+                        }
+                    }
+                }
+            }
+
+            if !improved.contains(&true) {
+                break;
+            }
+            self.relax_footpaths(&mut round, &mut best, &mut improved);
+            marked = improved;
+            rounds.push(round);
+        }
+
+        rounds
+    }
+
+    /// Walks from every stop marked in this round, marking the stops it improves.
+    ///
+    /// Stops walked from keep their label, so every walk departs from the arrival its
+    /// `from` label holds (walks are not chained within a round).
+    fn relax_footpaths(&self, round: &mut [Option<Label>], best: &mut [u32], marked: &mut [bool]) {
+        let reached: Vec<(usize, u32)> = (0..round.len())
+            .filter(|&s| marked[s])
+            .filter_map(|s| Some((s, round[s]?.arrival)))
+            .collect();
+        let mut sources = vec![false; round.len()];
+        for &(s, _) in &reached {
+            sources[s] = true;
+        }
+
+        for &(from, departure) in &reached {
+            for f in &self.footpaths[from] {
+                let arrival = departure + f.walk_secs;
+                if !sources[f.to] && arrival < best[f.to] {
+                    best[f.to] = arrival;
+                    round[f.to] = Some(Label {
+                        arrival,
+                        kind: LabelKind::Walk {
+                            from,
+                            distance_m: f.distance_m,
+                        },
+                    });
+                    marked[f.to] = true;
+                }
+            }
+        }
+    }
+
+    /// Steps of the journey reaching `egress.to` in round `k`, in travel order
+    fn reconstruct(
+        &self,
+        rounds: &[Vec<Option<Label>>],
+        mut k: usize,
+        egress: &Footpath,
+        start: u32,
+        day_trips: &[&[Trip]],
+    ) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let mut s = egress.to;
+        let reached = rounds[k][s].map(|l| l.arrival).unwrap_or(start);
+        if egress.walk_secs > 0 {
+            steps.push(Step::Walk {
+                from: Some(s),
+                to: None,
+                departure: reached,
+                arrival: reached + egress.walk_secs,
+                distance_m: egress.distance_m,
+            });
+        }
+
+        while let Some(label) = rounds[k][s] {
+            match label.kind {
+                LabelKind::Access { distance_m } => {
+                    if distance_m > 0.0 {
+                        steps.push(Step::Walk {
+                            from: None,
+                            to: Some(s),
+                            departure: start,
+                            arrival: label.arrival,
+                            distance_m,
+                        });
+                    }
+                    break;
+                }
+                LabelKind::Walk { from, distance_m } => {
+                    let departure = rounds[k][from].map(|l| l.arrival).unwrap_or(start);
+                    steps.push(Step::Walk {
+                        from: Some(from),
+                        to: Some(s),
+                        departure,
+                        arrival: label.arrival,
+                        distance_m,
+                    });
+                    s = from;
+                }
+                LabelKind::Ride {
+                    pattern,
+                    trip,
+                    board,
+                    alight,
+                } => {
+                    steps.push(Step::Ride {
+                        pattern,
+                        trip,
+                        board,
+                        alight,
+                    });
+                    s = self.patterns[pattern].stops[board];
+                    k -= 1;
+                }
+            }
+        }
+        steps.reverse();
+
+        // Leave the origin just in time for the first bus instead of waiting at the stop
+        if let [
+            Step::Walk {
+                departure, arrival, ..
+            },
+            Step::Ride {
+                pattern,
+                trip,
+                board,
+                ..
+            },
+            ..,
+        ] = steps.as_mut_slice()
+        {
+            let boarding = day_trips[*pattern][*trip].times[*board];
+            *departure = boarding - (*arrival - *departure);
+            *arrival = boarding;
+        }
+        steps
+    }
+
+    fn itinerary(
+        &self,
+        steps: Vec<Step>,
+        from: &Place,
+        to: &Place,
+        day_trips: &[&[Trip]],
+    ) -> Itinerary {
+        let point = |stop: Option<usize>, place: &Place| match stop {
+            Some(s) => self.stop_point(s),
+            None => self.place_point(place),
+        };
+
+        let mut legs = Vec::new();
+        let mut rides = 0;
+        let mut walk_distance_m = 0.0;
+        let (mut departure, mut arrival) = (None, 0);
+
+        for step in steps {
+            let (leg, dep, arr) = match step {
+                Step::Walk {
+                    from: a,
+                    to: b,
+                    departure,
+                    arrival,
+                    distance_m,
+                } => {
+                    walk_distance_m += distance_m;
+                    let leg = Leg {
+                        mode: "walk".to_string(),
+                        from: point(a, from),
+                        to: point(b, to),
+                        departure: clock(departure, false),
+                        arrival: clock(arrival, true),
+                        distance_m: Some(round_m(distance_m)),
+                        route_id: None,
+                        route_no: None,
+                        headsign: None,
+                        stops: None,
+                    };
+                    (leg, departure, arrival)
+                }
+                Step::Ride {
+                    pattern,
+                    trip,
+                    board,
+                    alight,
+                } => {
+                    rides += 1;
+                    let p = &self.patterns[pattern];
+                    let times = &day_trips[pattern][trip].times;
+                    let leg = Leg {
+                        mode: "bus".to_string(),
+                        from: self.stop_point(p.stops[board]),
+                        to: self.stop_point(p.stops[alight]),
+                        departure: clock(times[board], false),
+                        arrival: clock(times[alight], true),
+                        distance_m: None,
+                        route_id: Some(p.route_id.clone()),
+                        route_no: Some(p.route_no.clone()),
+                        headsign: Some(p.headsign.clone()),
+                        stops: Some(alight - board),
+                    };
+                    (leg, times[board], times[alight])
+                }
+            };
+            departure.get_or_insert(dep);
+            arrival = arr;
+            legs.push(leg);
+        }
+
+        let departure = departure.unwrap_or(arrival);
+        Itinerary {
+            departure: clock(departure, false),
+            arrival: clock(arrival, true),
+            duration_mins: arrival.div_ceil(60) - departure / 60,
+            transfers: rides.max(1) - 1,
+            walk_distance_m: round_m(walk_distance_m),
+            legs,
+        }
+    }
+
+    fn resolve(&self, place: &Place) -> Result<Option<usize>> {
+        match place {
+            Place::Stop(id) => match self.stop_lookup.get(id) {
+                Some(&s) => Ok(Some(s)),
+                None => bail!("Station '{}' is not served by any timetable", id),
+            },
+            Place::Coordinate(..) => Ok(None),
+        }
+    }
+
+    /// The stops a journey starts or ends at, with the walk between them and the place
+    fn endpoints(&self, place: &Place, stop: Option<usize>) -> Result<Vec<Footpath>> {
+        match (place, stop) {
+            (Place::Coordinate(lon, lat), _) => {
+                Ok(self.stops_within((*lon, *lat), self.settings.access_radius_m))
+            }
+            (_, Some(s)) => Ok(vec![Footpath {
+                to: s,
+                distance_m: 0.0,
+                walk_secs: 0,
+            }]),
+            _ => bail!("Unresolved place {:?}", place),
+        }
+    }
+
+    fn place_coord(&self, place: &Place, stop: Option<usize>) -> Option<(f64, f64)> {
+        match place {
+            Place::Coordinate(lon, lat) => Some((*lon, *lat)),
+            Place::Stop(_) => self.stops[stop?].coord,
+        }
+    }
+
+    fn place_point(&self, place: &Place) -> PlacePoint {
+        match place {
+            Place::Stop(id) => match self.stop_lookup.get(id) {
+                Some(&s) => self.stop_point(s),
+                None => PlacePoint {
+                    node_id: Some(id.clone()),
+                    name: None,
+                    lat: None,
+                    lon: None,
+                },
+            },
+            Place::Coordinate(lon, lat) => PlacePoint {
+                node_id: None,
+                name: None,
+                lat: Some(*lat),
+                lon: Some(*lon),
+            },
+        }
+    }
+
+    fn stop_point(&self, s: usize) -> PlacePoint {
+        let stop = &self.stops[s];
+        PlacePoint {
+            node_id: Some(stop.node_id.clone()),
+            name: Some(stop.name.clone()),
+            lat: stop.coord.map(|c| c.1),
+            lon: stop.coord.map(|c| c.0),
+        }
+    }
+}
+
+/// "HH:MM" of a time in seconds, rounding arrivals up and departures down
+fn clock(secs: u32, round_up: bool) -> String {
+    let minutes = if round_up {
+        secs.div_ceil(60)
+    } else {
+        secs / 60
+    };
+    ServiceTime::from_minutes(minutes).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::network::model::Station;
+
+    const LAT: f64 = 37.3;
+    const WEEKDAY: &str = "weekday";
+
+    /// Longitude `m` meters east of 127.9 at `LAT`
+    fn east(m: f64) -> f64 {
+        127.9 + m / (111_320.0 * LAT.to_radians().cos())
+    }
+
+    fn network(stations: &[(&str, f64)]) -> Network {
+        let stations: BTreeMap<String, Station> = stations
+            .iter()
+            .map(|&(id, m)| {
+                let station = Station {
+                    node_id: id.to_string(),
+                    name: format!("Stop {}", id),
+                    node_no: String::new(),
+                    lat: LAT,
+                    lon: east(m),
+                };
+                (id.to_string(), station)
+            })
+            .collect();
+        Network::new(stations, Vec::new())
+    }
+
+    /// A route with one direction over `stops` and its weekday trips
+    fn route(route_no: &str, stops: &[&str], trips: &[&[&str]]) -> StopTimesFile {
+        let stops: Vec<_> = stops
+            .iter()
+            .enumerate()
+            .map(|(i, id)| json!({ "id": id, "name": format!("Stop {}", id), "ord": i + 1 }))
+            .collect();
+        let trips: Vec<_> = trips
+            .iter()
+            .map(|times| json!({ "departure": times[0], "times": times }))
+            .collect();
+
+        serde_json::from_value(json!({
+            "routeId": format!("R{}", route_no),
+            "routeNo": route_no,
+            "generatedAt": "",
+            "directions": [{
+                "direction": "out",
+                "headsign": stops.last().unwrap()["name"],
+                "stops": stops,
+                "observedSegments": 0,
+                "dayTypes": { WEEKDAY: trips },
+            }],
+        }))
+        .unwrap()
+    }
+
+    /// A0 - A1 - A2 - B1 - B2 one kilometer apart, and C0 50 m past A1
+    fn line_planner() -> Planner {
+        let network = network(&[
+            ("A0", 0.0),
+            ("A1", 1000.0),
+            ("A2", 2000.0),
+            ("B1", 3000.0),
+            ("B2", 4000.0),
+            ("C0", 1050.0),
+            ("C1", 5000.0),
+        ]);
+        let files = [
+            route(
+                "1",
+                &["A0", "A1", "A2"],
+                &[&["08:00", "08:05", "08:10"], &["08:30", "08:35", "08:40"]],
+            ),
+            route("2", &["A2", "B1", "B2"], &[&["08:15", "08:20", "08:25"]]),
+            route("3", &["C0", "C1"], &[&["08:10", "08:20"]]),
+            route("9", &["A0", "B2"], &[&["08:00", "09:00"]]),
+        ];
+        Planner::new(network, &files, PlannerSettings::default())
+    }
+
+    fn plan(planner: &Planner, from: &str, to: &str, at: &str) -> PlanResult {
+        planner
+            .plan(
+                &Place::parse(from).unwrap(),
+                &Place::parse(to).unwrap(),
+                ServiceTime::parse(at).unwrap(),
+                WEEKDAY,
+            )
+            .unwrap()
+    }
+
+    /// (mode, from, to, departure, arrival) of every leg
+    fn legs(it: &Itinerary) -> Vec<(&str, String, String, &str, &str)> {
+        it.legs
+            .iter()
+            .map(|l| {
+                (
+                    l.mode.as_str(),
+                    l.from.node_id.clone().unwrap_or_default(),
+                    l.to.node_id.clone().unwrap_or_default(),
+                    l.departure.as_str(),
+                    l.arrival.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn direct_ride() {
+        let result = plan(&line_planner(), "A0", "A2", "07:55");
+
+        assert_eq!(result.itineraries.len(), 1);
+        let it = &result.itineraries[0];
+        assert_eq!(
+            (it.departure.as_str(), it.arrival.as_str()),
+            ("08:00", "08:10")
+        );
+        assert_eq!(it.transfers, 0);
+        assert_eq!(
+            legs(it),
+            [("bus", "A0".into(), "A2".into(), "08:00", "08:10")]
+        );
+        assert_eq!(it.legs[0].route_no.as_deref(), Some("1"));
+        assert_eq!(it.legs[0].stops, Some(2));
+    }
+
+    #[test]
+    fn later_journeys_have_more_rides_and_arrive_strictly_earlier() {
+        let result = plan(&line_planner(), "A0", "B2", "07:55");
+
+        let summary: Vec<_> = result
+            .itineraries
+            .iter()
+            .map(|it| (it.transfers, it.arrival.as_str()))
+            .collect();
+        assert_eq!(summary, [(0, "09:00"), (1, "08:25")]);
+        assert_eq!(
+            legs(&result.itineraries[1]),
+            [
+                ("bus", "A0".into(), "A2".into(), "08:00", "08:10"),
+                ("bus", "A2".into(), "B2".into(), "08:15", "08:25"),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_journey_when_the_last_trip_has_left() {
+        let result = plan(&line_planner(), "A0", "B2", "08:31");
+        assert!(result.itineraries.is_empty());
+    }
+
+    #[test]
+    fn walking_transfer_between_nearby_stops() {
+        let result = plan(&line_planner(), "A0", "C1", "07:55");
+
+        assert_eq!(result.itineraries.len(), 1);
+        let it = &result.itineraries[0];
+        assert_eq!(
+            legs(it),
+            [
+                ("bus", "A0".into(), "A1".into(), "08:00", "08:05"),
+                ("walk", "A1".into(), "C0".into(), "08:05", "08:06"),
+                ("bus", "C0".into(), "C1".into(), "08:10", "08:20"),
+            ]
+        );
+        assert_eq!(it.transfers, 1);
+        // 50 m times the detour factor
+        assert!((it.walk_distance_m - 65.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn coordinate_origin_walks_to_the_stop_just_in_time() {
+        let origin = format!("{},{}", LAT, east(-100.0));
+        let result = plan(&line_planner(), &origin, "A2", "07:30");
+
+        assert_eq!(result.itineraries.len(), 1);
+        let it = &result.itineraries[0];
+        // 130 m at 4.5 km/h takes 104 s, so leave at 07:58:16
+        assert_eq!(
+            legs(it),
+            [
+                ("walk", String::new(), "A0".into(), "07:58", "08:00"),
+                ("bus", "A0".into(), "A2".into(), "08:00", "08:10"),
+            ]
+        );
+        assert_eq!(it.departure, "07:58");
+        assert_eq!(it.transfers, 0);
+        assert_eq!(result.from.lat, Some(LAT));
+    }
+
+    #[test]
+    fn walks_depart_from_the_arrival_they_were_relaxed_from() {
+        // X is reached by bus at 08:10 and Z at 08:00. Z is 50 m from X, and Y 250 m
+        // from X but 300 m from Z, beyond the transfer radius.
+        let network = network(&[
+            ("O", 0.0),
+            ("X", 2000.0),
+            ("Z", 2050.0),
+            ("Y", 1750.0),
+            ("W", 5000.0),
+        ]);
+        let files = [
+            route("1", &["O", "X"], &[&["08:00", "08:10"]]),
+            route("2", &["O", "Z"], &[&["07:50", "08:00"]]),
+            route("3", &["Y", "W"], &[&["08:30", "08:40"]]),
+        ];
+        let settings = PlannerSettings {
+            transfer_radius_m: 280.0,
+            ..PlannerSettings::default()
+        };
+        let planner = Planner::new(network, &files, settings);
+
+        let result = plan(&planner, "O", "Y", "07:45");
+
+        assert_eq!(result.itineraries.len(), 1);
+        // 325 m take 260 s from X's bus arrival at 08:10
+        assert_eq!(
+            legs(&result.itineraries[0]),
+            [
+                ("bus", "O".into(), "X".into(), "08:00", "08:10"),
+                ("walk", "X".into(), "Y".into(), "08:10", "08:15"),
+            ]
+        );
+    }
+
+    #[test]
+    fn vacation_weekdays_fall_back_to_weekday_trips() {
+        let planner = line_planner();
+        let result = planner
+            .plan(
+                &Place::parse("A0").unwrap(),
+                &Place::parse("A2").unwrap(),
+                ServiceTime::parse("07:55").unwrap(),
+                "vacation_weekday",
+            )
+            .unwrap();
+        assert_eq!(result.itineraries.len(), 1);
+    }
+
+    #[test]
+    fn day_type_without_trips_is_an_error() {
+        let planner = line_planner();
+        let Err(err) = planner.plan(
+            &Place::parse("A0").unwrap(),
+            &Place::parse("A2").unwrap(),
+            ServiceTime::parse("08:00").unwrap(),
+            "sunday",
+        ) else {
+            panic!("planned on a day type without timetables");
+        };
+        assert!(err.is::<NoServiceOnDay>());
+    }
+}
@@ -0,0 +1,172 @@
+// src/planner/model.rs
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::config::model::PlannerSettings;
+use crate::network::model::Network;
+
+// ============================================================================
+// Planner (Read from stop_times/, routeMap.json and derived_routes/)
+// ============================================================================
+
+/// Journey planner over the stop-level timetables of every route.
+///
+/// Loaded once and queried with `plan`; all day types are kept, so one planner
+/// answers queries for any day.
+pub struct Planner {
+    pub settings: PlannerSettings,
+    pub network: Network,
+    pub stops: Vec<PlannerStop>,
+    // Node ID -> Index into `stops`
+    pub stop_lookup: HashMap<String, usize>,
+    pub patterns: Vec<Pattern>,
+    // Per stop: patterns serving it, with the position of the stop in the pattern
+    pub stop_patterns: Vec<Vec<(usize, usize)>>,
+    // Per stop: walking transfers to nearby stops
+    pub footpaths: Vec<Vec<Footpath>>,
+}
+
+/// A stop served by at least one timetable
+pub struct PlannerStop {
+    pub node_id: String,
+    pub name: String,
+    // (lon, lat), if the station has coordinates in routeMap.json
+    pub coord: Option<(f64, f64)>,
+}
+
+/// One direction of a route: a fixed stop sequence and its trips per day type
+pub struct Pattern {
+    pub route_id: String,
+    pub route_no: String,
+    pub direction: String,
+    pub headsign: String,
+    // Indices into `stops`
+    pub stops: Vec<usize>,
+    // Day type -> Trips
+    pub trips: BTreeMap<String, Vec<Trip>>,
+}
+
+/// A scheduled trip with its time at every stop of the pattern
+pub struct Trip {
+    // Seconds since the service-day midnight, in the order of the pattern's stops
+    pub times: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Footpath {
+    pub to: usize,
+    pub distance_m: f64,
+    pub walk_secs: u32,
+}
+
+/// Origin or destination of a query
+#[derive(Debug, Clone)]
+pub enum Place {
+    Stop(String),
+    // (lon, lat)
+    Coordinate(f64, f64),
+}
+
+// ============================================================================
+// Output Models (Printed by `polly plan` and served at /plan)
+// ============================================================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanResult {
+    pub from: PlacePoint,
+    pub to: PlacePoint,
+    pub at: String, // "HH:MM"
+    pub day_type: String,
+    // Fewer transfers first; every later itinerary arrives earlier
+    pub itineraries: Vec<Itinerary>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacePoint {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Itinerary {
+    pub departure: String, // "HH:MM"
+    pub arrival: String,   // "HH:MM"
+    pub duration_mins: u32,
+    pub transfers: usize,
+    pub walk_distance_m: f64,
+    pub legs: Vec<Leg>,
+}
+
+/// A walk or a bus ride
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leg {
+    pub mode: String, // "walk" or "bus"
+    pub from: PlacePoint,
+    pub to: PlacePoint,
+    pub departure: String, // "HH:MM"
+    pub arrival: String,   // "HH:MM"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_m: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_no: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headsign: Option<String>,
+    // Number of stops ridden
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stops: Option<usize>,
+}
+
+// ============================================================================
+// Output Models (Saved by `polly isochrone`)
+// ============================================================================
+
+/// Nested isochrones, largest first (GeoJSON)
+#[derive(Serialize)]
+pub struct IsochroneCollection {
+    #[serde(rename = "type")]
+    pub type_: String, // "FeatureCollection"
+    pub features: Vec<IsochroneFeature>,
+}
+
+#[derive(Serialize)]
+pub struct IsochroneFeature {
+    #[serde(rename = "type")]
+    pub type_: String, // "Feature"
+    pub properties: IsochroneProperties,
+    pub geometry: IsochroneGeometry,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsochroneProperties {
+    // Everything inside is reachable within this many minutes
+    pub minutes: u32,
+    pub origin: PlacePoint,
+    pub at: String, // "HH:MM"
+    pub day_type: String,
+    // Stations reachable within `minutes`
+    pub stops: usize,
+    pub area_km2: f64,
+}
+
+#[derive(Serialize)]
+pub struct IsochroneGeometry {
+    #[serde(rename = "type")]
+    pub type_: String, // "MultiPolygon"
+    // Polygons -> Rings (exterior first) -> [lon, lat]
+    pub coordinates: Vec<Vec<Vec<Vec<f64>>>>,
+}
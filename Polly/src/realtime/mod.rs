@@ -0,0 +1,350 @@
+// src/realtime/mod.rs
+
+pub mod gtfs_rt;
+pub mod model;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use prost::Message;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::config::model::PollyConfig;
+use crate::realtime::gtfs_rt::{
+    FeedEntity, FeedHeader, FeedMessage, GTFS_REALTIME_VERSION, Incrementality, Position,
+    StopTimeEvent, StopTimeUpdate, TripDescriptor, TripUpdate, VehicleDescriptor, VehiclePosition,
+    VehicleStopStatus,
+};
+use crate::realtime::model::{
+    ArrivalPrediction, FeedSnapshot, LiveVehicle, RealtimeProcessor, RouteSequence, SequenceStop,
+};
+use crate::utils::{extract_items, parse_flexible_string, tago_json};
+
+impl RealtimeProcessor {
+    /// Creates a processor for the routes listed in `routeMap.json`.
+    ///
+    /// Route IDs (`routeid`) and stop IDs (`nodeid`) in the generated feeds are the same
+    /// identifiers Polly publishes in `routeMap.json` and the derived route files.
+    pub fn load(route_map_path: &Path, config: &PollyConfig) -> Result<Self> {
+        if config.service_key.is_empty() {
+            anyhow::bail!("DATA_GO_KR_SERVICE_KEY (service_key) is missing!");
+        }
+
+        let content = fs::read_to_string(route_map_path)?;
+        let route_map: Value = serde_json::from_str(&content)?;
+
+        let mut routes = BTreeMap::new();
+        if let Some(details) = route_map["route_details"].as_object() {
+            for (route_id, detail) in details {
+                let mut stops: Vec<SequenceStop> = detail["sequence"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|s| SequenceStop {
+                        node_id: s["nodeid"].as_str().unwrap_or("").to_string(),
+                        node_ord: s["nodeord"].as_i64().unwrap_or(0),
+                        up_down: s["updowncd"].as_i64().unwrap_or(0),
+                    })
+                    .collect();
+                stops.sort_by_key(|s| s.node_ord);
+
+                routes.insert(
+                    route_id.clone(),
+                    RouteSequence {
+                        route_no: parse_flexible_string(&detail["routeno"]),
+                        stops,
+                    },
+                );
+            }
+        }
+
+        if routes.is_empty() {
+            anyhow::bail!("No routes found in {:?}", route_map_path);
+        }
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            service_key: config.service_key.clone(),
+            city_code: config.city_code.clone(),
+            bus_location_base_url: config.endpoints.tago_bus_location.clone(),
+            arrival_base_url: config.endpoints.tago_arrival.clone(),
+            settings: config.realtime.clone(),
+            routes,
+        })
+    }
+
+    /// Polls TAGO once and encodes the VehiclePositions and TripUpdates feeds.
+    ///
+    /// 1. Fetches vehicle positions for every route.
+    /// 2. Fetches arrival predictions for the next stops ahead of each vehicle.
+    /// 3. Attributes each prediction to the vehicle that is `arrprevstationcnt` stops
+    ///    before the predicted stop on the same route.
+    ///
+    pub async fn build_snapshot(&self) -> Result<FeedSnapshot> {
+        let now = Local::now();
+        let timestamp = now.timestamp() as u64;
+        let start_date = now.format("%Y%m%d").to_string();
+
+        let vehicles = self.fetch_all_vehicles().await;
+
+        // Stops ahead of each vehicle, polled once even when shared by several routes.
+        let mut target_stops = BTreeSet::new();
+        for v in &vehicles {
+            if let Some((seq, idx)) = self.locate(v) {
+                for stop in seq
+                    .stops
+                    .iter()
+                    .skip(idx + 1)
+                    .take(self.settings.arrival_lookahead)
+                {
+                    target_stops.insert(stop.node_id.clone());
+                }
+            }
+        }
+
+        let predictions = self.fetch_all_arrivals(target_stops).await;
+
+        // (Route ID, Node ID) -> Predictions
+        let mut by_stop: HashMap<(&str, &str), Vec<&ArrivalPrediction>> = HashMap::new();
+        for p in &predictions {
+            by_stop
+                .entry((p.route_id.as_str(), p.node_id.as_str()))
+                .or_default()
+                .push(p);
+        }
+
+        let mut position_entities = Vec::new();
+        let mut update_entities = Vec::new();
+
+        for v in &vehicles {
+            // A bus running on two route variants is listed under both route IDs.
+            let entity_id = format!("{}:{}", v.route_id, v.vehicle_no);
+            let located = self.locate(v);
+            let trip = TripDescriptor {
+                trip_id: None,
+                start_date: Some(start_date.clone()),
+                route_id: Some(v.route_id.clone()),
+                direction_id: located.map(|(seq, idx)| seq.stops[idx].up_down as u32),
+            };
+            let descriptor = VehicleDescriptor {
+                id: Some(v.vehicle_no.clone()),
+                label: located.map(|(seq, _)| seq.route_no.clone()),
+                license_plate: Some(v.vehicle_no.clone()),
+            };
+
+            // TAGO reports the last stop passed; GTFS-Realtime expects the next one.
+            let (status, stop) = match located {
+                Some((seq, idx)) => match seq.stops.get(idx + 1) {
+                    Some(next) => (VehicleStopStatus::InTransitTo, Some(next)),
+                    None => (VehicleStopStatus::StoppedAt, seq.stops.get(idx)),
+                },
+                None => (VehicleStopStatus::InTransitTo, None),
+            };
+
+            position_entities.push(FeedEntity {
+                id: entity_id.clone(),
+                is_deleted: None,
+                trip_update: None,
+                vehicle: Some(VehiclePosition {
+                    trip: Some(trip.clone()),
+                    position: Some(Position {
+                        latitude: v.gps_lat as f32,
+                        longitude: v.gps_long as f32,
+                    }),
+                    current_stop_sequence: stop.map(|s| s.node_ord as u32),
+                    current_status: stop.map(|_| status as i32),
+                    timestamp: Some(timestamp),
+                    stop_id: stop.map(|s| s.node_id.clone()),
+                    vehicle: Some(descriptor.clone()),
+                }),
+            });
+
+            let Some((seq, idx)) = located else {
+                continue;
+            };
+
+            let stop_time_update: Vec<StopTimeUpdate> = seq
+                .stops
+                .iter()
+                .enumerate()
+                .skip(idx + 1)
+                .take(self.settings.arrival_lookahead)
+                .filter_map(|(k, stop)| {
+                    let stops_away = (k - idx) as i64;
+                    by_stop
+                        .get(&(v.route_id.as_str(), stop.node_id.as_str()))?
+                        .iter()
+                        .find(|p| p.stops_away == stops_away)
+                        .map(|p| StopTimeUpdate {
+                            stop_sequence: Some(stop.node_ord as u32),
+                            arrival: Some(StopTimeEvent {
+                                delay: None,
+                                time: Some(timestamp as i64 + p.arrival_secs),
+                            }),
+                            stop_id: Some(stop.node_id.clone()),
+                        })
+                })
+                .collect();
+
+            if stop_time_update.is_empty() {
+                continue;
+            }
+
+            update_entities.push(FeedEntity {
+                id: entity_id,
+                is_deleted: None,
+                trip_update: Some(TripUpdate {
+                    trip,
+                    stop_time_update,
+                    vehicle: Some(descriptor),
+                    timestamp: Some(timestamp),
+                }),
+                vehicle: None,
+            });
+        }
+
+        Ok(FeedSnapshot {
+            generated_at: timestamp,
+            vehicle_positions: encode_feed(position_entities, timestamp),
+            trip_updates: encode_feed(update_entities, timestamp),
+        })
+    }
+
+    /// Finds a vehicle's route sequence and the index of its last passed stop.
+    fn locate(&self, v: &LiveVehicle) -> Option<(&RouteSequence, usize)> {
+        let seq = self.routes.get(&v.route_id)?;
+        let idx = seq
+            .stops
+            .iter()
+            .position(|s| s.node_ord == v.node_ord)
+            .or_else(|| seq.stops.iter().position(|s| s.node_id == v.node_id))?;
+        Some((seq, idx))
+    }
+
+    async fn fetch_all_vehicles(&self) -> Vec<LiveVehicle> {
+        let route_ids: Vec<String> = self.routes.keys().cloned().collect();
+        let mut stream = stream::iter(route_ids)
+            .map(|route_id| async move {
+                let result = self.fetch_vehicles(&route_id).await;
+                (route_id, result)
+            })
+            .buffer_unordered(self.settings.fetch_concurrency);
+
+        let mut vehicles = Vec::new();
+        while let Some((route_id, result)) = stream.next().await {
+            match result {
+                Ok(mut list) => vehicles.append(&mut list),
+                Err(e) => warn!(route = %route_id, "Bus location fetch failed: {:?}", e),
+            }
+        }
+        vehicles
+    }
+
+    async fn fetch_vehicles(&self, route_id: &str) -> Result<Vec<LiveVehicle>> {
+        let params = [
+            ("cityCode", self.city_code.as_str()),
+            ("routeId", route_id),
+            ("numOfRows", "1024"),
+            ("pageNo", "1"),
+            ("serviceKey", self.service_key.as_str()),
+            ("_type", "json"),
+        ];
+
+        let url = format!("{}/getRouteAcctoBusLcList", self.bus_location_base_url);
+        let json = tago_json(self.client.get(&url).query(&params).send().await).await?;
+
+        let as_f64 = |v: &Value| v.as_f64().or_else(|| v.as_str()?.parse().ok());
+
+        Ok(extract_items(&json)?
+            .iter()
+            .filter_map(|item| {
+                Some(LiveVehicle {
+                    route_id: route_id.to_string(),
+                    vehicle_no: item["vehicleno"].as_str()?.to_string(),
+                    node_id: item["nodeid"].as_str().unwrap_or("").to_string(),
+                    node_ord: parse_flexible_string(&item["nodeord"]).parse().ok()?,
+                    gps_lat: as_f64(&item["gpslati"])?,
+                    gps_long: as_f64(&item["gpslong"])?,
+                })
+            })
+            .collect())
+    }
+
+    async fn fetch_all_arrivals(&self, node_ids: BTreeSet<String>) -> Vec<ArrivalPrediction> {
+        let mut stream = stream::iter(node_ids)
+            .map(|node_id| async move {
+                let result = self.fetch_arrivals(&node_id).await;
+                (node_id, result)
+            })
+            .buffer_unordered(self.settings.fetch_concurrency);
+
+        let mut predictions = Vec::new();
+        while let Some((node_id, result)) = stream.next().await {
+            match result {
+                Ok(mut list) => predictions.append(&mut list),
+                Err(e) => warn!(node = %node_id, "Arrival fetch failed: {:?}", e),
+            }
+        }
+        predictions
+    }
+
+    async fn fetch_arrivals(&self, node_id: &str) -> Result<Vec<ArrivalPrediction>> {
+        let params = [
+            ("cityCode", self.city_code.as_str()),
+            ("nodeId", node_id),
+            ("numOfRows", "1024"),
+            ("pageNo", "1"),
+            ("serviceKey", self.service_key.as_str()),
+            ("_type", "json"),
+        ];
+
+        let url = format!("{}/getSttnAcctoArvlPrearngeInfoList", self.arrival_base_url);
+        let json = tago_json(self.client.get(&url).query(&params).send().await).await?;
+
+        Ok(extract_items(&json)?
+            .iter()
+            .filter_map(|item| {
+                Some(ArrivalPrediction {
+                    route_id: item["routeid"].as_str()?.to_string(),
+                    node_id: node_id.to_string(),
+                    stops_away: parse_flexible_string(&item["arrprevstationcnt"])
+                        .parse()
+                        .ok()?,
+                    arrival_secs: parse_flexible_string(&item["arrtime"]).parse().ok()?,
+                })
+            })
+            .collect())
+    }
+}
+
+fn encode_feed(entity: Vec<FeedEntity>, timestamp: u64) -> Vec<u8> {
+    FeedMessage {
+        header: FeedHeader {
+            gtfs_realtime_version: GTFS_REALTIME_VERSION.to_string(),
+            incrementality: Some(Incrementality::FullDataset as i32),
+            timestamp: Some(timestamp),
+        },
+        entity,
+    }
+    .encode_to_vec()
+}
+
+/// Writes the feeds of a snapshot to `vehicle_positions.pb` and `trip_updates.pb`.
+///
+/// Files are replaced atomically so readers never see a partially written feed.
+pub fn write_snapshot(dir: &Path, snapshot: &FeedSnapshot) -> Result<()> {
+    for (name, bytes) in [
+        ("vehicle_positions.pb", &snapshot.vehicle_positions),
+        ("trip_updates.pb", &snapshot.trip_updates),
+    ] {
+        let tmp = dir.join(format!("{}.tmp", name));
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, dir.join(name))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// src/realtime/model.rs
+
+use std::collections::BTreeMap;
+
+use crate::config::model::RealtimeSettings;
+
+// ============================================================================
+// Static Reference Data (Read from routeMap.json)
+// ============================================================================
+
+/// A stop in a route's sequence, as listed in `routeMap.json`
+#[derive(Debug, Clone)]
+pub struct SequenceStop {
+    pub node_id: String,
+    pub node_ord: i64,
+    pub up_down: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteSequence {
+    pub route_no: String,
+    pub stops: Vec<SequenceStop>,
+}
+
+// ============================================================================
+// Live Data Models (Fetched from TAGO)
+// ============================================================================
+
+/// A vehicle position from `getRouteAcctoBusLcList`
+#[derive(Debug, Clone)]
+pub struct LiveVehicle {
+    pub route_id: String,
+    pub vehicle_no: String,
+    pub node_id: String,
+    pub node_ord: i64,
+    pub gps_lat: f64,
+    pub gps_long: f64,
+}
+
+/// An arrival prediction from `getSttnAcctoArvlPrearngeInfoList`
+#[derive(Debug, Clone)]
+pub struct ArrivalPrediction {
+    pub route_id: String,
+    pub node_id: String,
+    // Number of stops between the vehicle and this stop (`arrprevstationcnt`)
+    pub stops_away: i64,
+    // Seconds until arrival (`arrtime`)
+    pub arrival_secs: i64,
+}
+
+/// Encoded GTFS-Realtime feeds from one polling cycle
+#[derive(Debug, Clone)]
+pub struct FeedSnapshot {
+    pub generated_at: u64,
+    pub vehicle_positions: Vec<u8>,
+    pub trip_updates: Vec<u8>,
+}
+
+/// Main processor structure
+pub struct RealtimeProcessor {
+    pub client: reqwest::Client,
+    pub service_key: String,
+    pub city_code: String,
+    pub bus_location_base_url: String,
+    pub arrival_base_url: String,
+    pub settings: RealtimeSettings,
+    // Route ID -> Sequence
+    pub routes: BTreeMap<String, RouteSequence>,
+}
@@ -0,0 +1,195 @@
+// src/registry/mod.rs
+
+pub mod model;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::registry::model::{RegistryEntry, RouteRegistry, TagoRouteRef};
+
+pub const REGISTRY_FILE: &str = "routeRegistry.json";
+
+/// Main entry point for the route identity registry.
+///
+/// 1. Reads the TAGO route numbers and IDs from `routeMap.json` in `routes_dir`.
+/// 2. Reads the route number of every schedule file in `schedules_dir`.
+/// 3. Joins both sources on the normalized route number.
+/// 4. Reports routes found in only one source and saves `routeRegistry.json` to `routes_dir`.
+///
+pub async fn run(routes_dir: PathBuf, schedules_dir: PathBuf) -> Result<()> {
+    info!("[Building route registry]");
+
+    let registry = RouteRegistry::build(&routes_dir, &schedules_dir)?;
+
+    let linked = registry
+        .routes
+        .values()
+        .filter(|e| e.schedule_file.is_some() && !e.tago_routes.is_empty())
+        .count();
+    info!("Linked {} routes across sources.", linked);
+
+    if !registry.schedule_only.is_empty() {
+        info!(
+            "Schedule only ({}): {}",
+            registry.schedule_only.len(),
+            registry.schedule_only.join(", ")
+        );
+    }
+    if !registry.tago_only.is_empty() {
+        info!(
+            "TAGO only ({}): {}",
+            registry.tago_only.len(),
+            registry.tago_only.join(", ")
+        );
+    }
+
+    let path = routes_dir.join(REGISTRY_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&registry)?)?;
+
+    info!("✓ Saved route registry to {:?}", path);
+
+    Ok(())
+}
+
+/// Normalizes a route number so that the same route matches across sources.
+///
+/// Full-width characters and dash variants are folded, whitespace and a trailing "번"
+/// are removed, and parenthesized suffixes are dropped: "３４－１번 (평일)" -> "34-1".
+pub fn normalize_route_no(raw: &str) -> String {
+    let base = raw.split(['(', '（']).next().unwrap_or(raw);
+
+    let folded: String = base
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            // Full-width ASCII (e.g., '３', 'Ａ')
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => '-',
+            _ => c,
+        })
+        .collect();
+
+    folded.trim_end_matches('번').to_uppercase()
+}
+
+impl RouteRegistry {
+    /// Builds the registry from `routeMap.json` and the schedule files.
+    pub fn build(routes_dir: &Path, schedules_dir: &Path) -> Result<Self> {
+        let mut registry = RouteRegistry {
+            last_updated: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ..Default::default()
+        };
+
+        let map_path = routes_dir.join("routeMap.json");
+        let content = fs::read_to_string(&map_path)
+            .with_context(|| format!("Failed to read {:?}", map_path))?;
+        let route_map: Value = serde_json::from_str(&content)?;
+
+        if let Some(numbers) = route_map["route_numbers"].as_object() {
+            for (route_no, ids) in numbers {
+                let entry = registry
+                    .routes
+                    .entry(normalize_route_no(route_no))
+                    .or_default();
+                for id in ids
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str())
+                {
+                    entry.tago_routes.push(TagoRouteRef {
+                        route_id: id.to_string(),
+                        route_no: route_no.clone(),
+                    });
+                }
+            }
+        }
+
+        if schedules_dir.is_dir() {
+            let mut files: Vec<_> = fs::read_dir(schedules_dir)?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            files.sort();
+
+            for path in files {
+                let Some(route_no) = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+                    .and_then(|v| v["routeId"].as_str().map(str::to_string))
+                else {
+                    warn!(path = ?path, "Skipping {:?}: not a schedule file", path);
+                    continue;
+                };
+
+                let entry = registry
+                    .routes
+                    .entry(normalize_route_no(&route_no))
+                    .or_default();
+                if let Some(existing) = &entry.schedule_file {
+                    warn!(
+                        route = %route_no,
+                        "{:?} and {} both hold route {}; keeping {}.",
+                        path.file_name().unwrap(),
+                        existing,
+                        route_no,
+                        existing
+                    );
+                    continue;
+                }
+                entry.schedule_route_no = Some(route_no);
+                entry.schedule_file = Some(path.file_name().unwrap().to_string_lossy().to_string());
+            }
+        }
+
+        for (key, entry) in &registry.routes {
+            match (entry.schedule_file.is_some(), entry.tago_routes.is_empty()) {
+                (true, true) => registry.schedule_only.push(key.clone()),
+                (false, false) => registry.tago_only.push(key.clone()),
+                _ => {}
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// The registry entry of a route number from any source.
+    pub fn entry(&self, route_no: &str) -> Option<&RegistryEntry> {
+        self.routes.get(&normalize_route_no(route_no))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_route_no_folds_variants_of_the_same_number() {
+        for (raw, expected) in [
+            ("34-1", "34-1"),
+            ("３４－１번 (평일)", "34-1"),
+            ("34-1(평일)", "34-1"),
+            ("34-1（휴일）", "34-1"),
+            ("34–1", "34-1"),
+            ("34 − 1", "34-1"),
+            (" 100번 ", "100"),
+            ("ａ1", "A1"),
+            ("마을1", "마을1"),
+        ] {
+            assert_eq!(normalize_route_no(raw), expected, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn normalize_route_no_keeps_distinct_numbers_apart() {
+        assert_ne!(normalize_route_no("34-1"), normalize_route_no("34"));
+        assert_ne!(normalize_route_no("34-1"), normalize_route_no("341"));
+        assert_eq!(normalize_route_no("(평일)"), "");
+    }
+}
@@ -0,0 +1,41 @@
+// src/registry/model.rs
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Output Models (Saved to routeRegistry.json)
+// ============================================================================
+
+/// Links the routes of every source under one normalized route number
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteRegistry {
+    pub last_updated: String,
+    // Normalized route number (e.g., "34-1") -> Entry
+    pub routes: BTreeMap<String, RegistryEntry>,
+    // Normalized route numbers found in only one source
+    pub schedule_only: Vec<String>,
+    pub tago_only: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    // Route number as written on the schedule site (e.g., "34-1")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_route_no: Option<String>,
+    // Schedule filename in the schedules directory (e.g., "34-1.json")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule_file: Option<String>,
+    // TAGO routes (variants) with this route number
+    pub tago_routes: Vec<TagoRouteRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagoRouteRef {
+    pub route_id: String,
+    pub route_no: String,
+}
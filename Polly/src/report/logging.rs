@@ -0,0 +1,100 @@
+// src/report/logging.rs
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+// Progress bars of the run; log lines are written around them.
+static PROGRESS: OnceLock<MultiProgress> = OnceLock::new();
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines with live progress bars
+    Text,
+    /// One JSON object per event (with its spans), without progress bars
+    Json,
+}
+
+/// Installs the global logger writing to stderr.
+///
+/// The level filter is `level`, else `RUST_LOG`, else `info`
+/// (e.g., `debug` or `polly=debug,reqwest=warn`).
+pub fn init(format: LogFormat, level: Option<&str>) -> Result<()> {
+    let filter = match level {
+        Some(directives) => EnvFilter::try_new(directives)
+            .with_context(|| format!("Invalid log level '{}'", directives))?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    let terminal = io::stderr().is_terminal();
+
+    let progress = match format {
+        LogFormat::Text if terminal => MultiProgress::new(),
+        _ => MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+    };
+
+    match format {
+        LogFormat::Text => tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_target(false)
+            .with_ansi(terminal)
+            .with_writer(ProgressWriter(progress.clone()))
+            .init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(io::stderr)
+            .init(),
+    }
+
+    let _ = PROGRESS.set(progress);
+    Ok(())
+}
+
+/// Creates a progress bar of `len` steps; hidden for JSON logs and non-terminals.
+pub fn progress_bar(len: usize, message: &str) -> ProgressBar {
+    let Some(progress) = PROGRESS.get() else {
+        return ProgressBar::hidden();
+    };
+
+    let style =
+        ProgressStyle::with_template(" {msg} [{bar:30}] {pos}/{len} ({elapsed}, ETA {eta})")
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars("=> ");
+
+    progress.add(
+        ProgressBar::new(len as u64)
+            .with_style(style)
+            .with_message(message.to_string()),
+    )
+}
+
+/// Writes log lines to stderr with the progress bars cleared, then redraws them.
+#[derive(Clone)]
+struct ProgressWriter(MultiProgress);
+
+struct SuspendedStderr<'a>(&'a MultiProgress);
+
+impl<'a> MakeWriter<'a> for ProgressWriter {
+    type Writer = SuspendedStderr<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        SuspendedStderr(&self.0)
+    }
+}
+
+impl Write for SuspendedStderr<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.suspend(|| io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
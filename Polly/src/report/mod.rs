@@ -0,0 +1,285 @@
+// src/report/mod.rs
+
+pub mod logging;
+pub mod model;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use tracing::{info, warn};
+
+use crate::error::{ErrorKind, FailureLimitReached, describe};
+use crate::report::model::{PhaseTiming, RouteFailure, RunReport, UpstreamStats};
+use crate::utils::ensure_dir;
+
+/// File name of the report written to the output directory of a run
+pub const RUN_REPORT_FILE: &str = "run_report.json";
+
+/// What to do when routes fail
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FailurePolicy {
+    // Failed routes tolerated before the run stops (None: keep going)
+    pub max_failures: Option<usize>,
+}
+
+/// Outcome of a run that was not aborted by an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Succeeded,
+    // Some routes failed; their output is missing
+    Partial,
+}
+
+/// Collects counts, phase durations, upstream requests and route failures of one run.
+///
+/// Shared by all tasks of a processor (behind an `Arc`); `finish` writes the report.
+pub struct RunRecorder {
+    command: String,
+    policy: FailurePolicy,
+    started_at: DateTime<Local>,
+    started: Instant,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    counts: BTreeMap<String, usize>,
+    phases: Vec<PhaseTiming>,
+    requests: BTreeMap<String, UpstreamStats>,
+    failures: Vec<RouteFailure>,
+}
+
+/// A running phase; its duration is recorded when dropped.
+pub struct Phase<'a> {
+    recorder: &'a RunRecorder,
+    name: String,
+    started: Instant,
+}
+
+impl Drop for Phase<'_> {
+    fn drop(&mut self) {
+        let duration_secs = self.started.elapsed().as_secs_f64();
+        self.recorder.state().phases.push(PhaseTiming {
+            name: std::mem::take(&mut self.name),
+            duration_secs,
+        });
+    }
+}
+
+impl RunRecorder {
+    pub fn new(command: &str, policy: FailurePolicy) -> Self {
+        Self {
+            command: command.to_string(),
+            policy,
+            started_at: Local::now(),
+            started: Instant::now(),
+            state: Mutex::new(RecorderState::default()),
+        }
+    }
+
+    /// Starts timing a phase that lasts until the returned guard is dropped.
+    pub fn phase(&self, name: &str) -> Phase<'_> {
+        Phase {
+            recorder: self,
+            name: name.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn add(&self, counter: &str, n: usize) {
+        *self.state().counts.entry(counter.to_string()).or_default() += n;
+    }
+
+    /// Records a request to `upstream`; `status` is `None` when no response arrived.
+    pub fn request(&self, upstream: &str, status: Option<u16>, elapsed: Duration) {
+        let mut state = self.state();
+        let stats = state.requests.entry(upstream.to_string()).or_default();
+
+        stats.requests += 1;
+        match status {
+            Some(s) if (200..300).contains(&s) => stats.succeeded += 1,
+            _ => stats.failed += 1,
+        }
+        let status = status.map_or("network".to_string(), |s| s.to_string());
+        *stats.statuses.entry(status).or_default() += 1;
+        stats.total_secs += elapsed.as_secs_f64();
+    }
+
+    pub fn retry(&self, upstream: &str) {
+        self.state()
+            .requests
+            .entry(upstream.to_string())
+            .or_default()
+            .retries += 1;
+    }
+
+    /// Records (and logs) a route that produced no output.
+    ///
+    /// Returns an error when the failure exceeds the policy; the run should stop then.
+    pub fn fail(
+        &self,
+        route: &str,
+        phase: &str,
+        kind: ErrorKind,
+        cause: &str,
+        attempts: Option<u32>,
+    ) -> Result<(), FailureLimitReached> {
+        warn!(route, phase, kind = kind.as_str(), "✗ {}", cause);
+
+        let mut state = self.state();
+        state.failures.push(RouteFailure {
+            route: route.to_string(),
+            phase: phase.to_string(),
+            kind: kind.as_str().to_string(),
+            cause: cause.to_string(),
+            attempts,
+        });
+
+        match self.policy.max_failures {
+            Some(limit) if state.failures.len() > limit => Err(FailureLimitReached {
+                failures: state.failures.len(),
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the report of the run that ended with `result` to `path`.
+    pub fn finish(&self, path: &Path, result: &Result<()>) -> Result<RunStatus> {
+        let mut state = self.state();
+        let mut failures = std::mem::take(&mut state.failures);
+        failures.sort_by(|a, b| (&a.phase, &a.route).cmp(&(&b.phase, &b.route)));
+
+        let error_kind = result.as_ref().err().map(ErrorKind::of);
+        let run_status = match failures.is_empty() {
+            true => RunStatus::Succeeded,
+            false => RunStatus::Partial,
+        };
+        let status = match (error_kind, run_status) {
+            (Some(ErrorKind::FailureLimit), _) => "aborted",
+            (Some(_), _) => "failed",
+            (None, RunStatus::Partial) => "partial",
+            (None, RunStatus::Succeeded) => "succeeded",
+        };
+
+        let report = RunReport {
+            command: self.command.clone(),
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: Local::now().to_rfc3339(),
+            duration_secs: self.started.elapsed().as_secs_f64(),
+            status: status.to_string(),
+            error: result.as_ref().err().map(|e| describe(e.as_ref())),
+            error_kind: error_kind.map(|k| k.as_str().to_string()),
+            counts: std::mem::take(&mut state.counts),
+            phases: std::mem::take(&mut state.phases),
+            requests: std::mem::take(&mut state.requests),
+            failures,
+        };
+
+        if let Some(dir) = path.parent() {
+            ensure_dir(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&report)?)?;
+
+        match report.failures.len() {
+            0 => info!(status, "Run report saved to {:?}", path),
+            n => warn!(
+                status,
+                failures = n,
+                "✗ {} routes failed; see {:?}",
+                n,
+                path
+            ),
+        }
+
+        Ok(run_status)
+    }
+
+    fn state(&self) -> MutexGuard<'_, RecorderState> {
+        // A panicking task leaves the statistics usable.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use anyhow::anyhow;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// Finishes `recorder` with `result`, returning its status and the saved report
+    fn finish(name: &str, recorder: &RunRecorder, result: Result<()>) -> (RunStatus, Value) {
+        let path =
+            std::env::temp_dir().join(format!("polly-report-{}-{}.json", process::id(), name));
+        let status = recorder.finish(&path, &result).unwrap();
+        let report = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(path).unwrap();
+        (status, report)
+    }
+
+    fn keep_going() -> RunRecorder {
+        RunRecorder::new("test", FailurePolicy::default())
+    }
+
+    #[test]
+    fn run_without_failures_succeeded() {
+        let (status, report) = finish("succeeded", &keep_going(), Ok(()));
+        assert_eq!(status, RunStatus::Succeeded);
+        assert_eq!(report["status"], "succeeded");
+        assert!(report.get("errorKind").is_none());
+    }
+
+    #[test]
+    fn run_with_failed_routes_is_partial() {
+        let recorder = keep_going();
+        recorder
+            .fail("34-1", "fetch", ErrorKind::Upstream, "HTTP 500", Some(4))
+            .unwrap();
+
+        let (status, report) = finish("partial", &recorder, Ok(()));
+        assert_eq!(status, RunStatus::Partial);
+        assert_eq!(report["status"], "partial");
+        assert_eq!(report["failures"][0]["kind"], "upstream");
+        assert_eq!(report["failures"][0]["attempts"], 4);
+    }
+
+    #[test]
+    fn run_stopped_by_the_failure_limit_is_aborted() {
+        let recorder = RunRecorder::new(
+            "test",
+            FailurePolicy {
+                max_failures: Some(1),
+            },
+        );
+        recorder
+            .fail("34-1", "fetch", ErrorKind::Upstream, "HTTP 500", None)
+            .unwrap();
+        let limit = recorder
+            .fail("34-2", "parse", ErrorKind::Parse, "No table", None)
+            .unwrap_err();
+        assert_eq!((limit.failures, limit.limit), (2, 1));
+
+        let (status, report) = finish("aborted", &recorder, Err(limit.into()));
+        assert_eq!(status, RunStatus::Partial);
+        assert_eq!(report["status"], "aborted");
+        assert_eq!(report["errorKind"], "failureLimit");
+        assert_eq!(report["failures"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn run_ended_by_an_error_failed() {
+        let error = anyhow!("Invalid route map").context("Loading routes");
+        let (_, report) = finish("failed", &keep_going(), Err(error));
+        assert_eq!(report["status"], "failed");
+        assert_eq!(report["errorKind"], "other");
+        assert_eq!(report["error"], "Loading routes: Invalid route map");
+    }
+}
@@ -0,0 +1,69 @@
+// src/report/model.rs
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+// ============================================================================
+// Output Models (Saved as run_report.json)
+// ============================================================================
+
+/// Machine-readable summary of one processor run
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub command: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_secs: f64,
+    // "succeeded", "partial" (some routes failed), "aborted" (too many routes failed)
+    // or "failed"
+    pub status: String,
+    // Error that stopped the run, and its kind (see `RouteFailure::kind`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<String>,
+    // Counter -> Value (e.g., "routesFetched")
+    pub counts: BTreeMap<String, usize>,
+    // Phases in the order they finished
+    pub phases: Vec<PhaseTiming>,
+    // Upstream (e.g., "tago", "osrm", "schedule") -> Request statistics
+    pub requests: BTreeMap<String, UpstreamStats>,
+    pub failures: Vec<RouteFailure>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTiming {
+    pub name: String,
+    pub duration_secs: f64,
+}
+
+/// Requests made to one upstream service
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamStats {
+    pub requests: usize,
+    // Answered with a 2xx status
+    pub succeeded: usize,
+    pub failed: usize,
+    pub retries: usize,
+    // HTTP status (or "network" when there was no response) -> Requests
+    pub statuses: BTreeMap<String, usize>,
+    // Sum of the request durations
+    pub total_secs: f64,
+}
+
+/// A route that produced no output, and why
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteFailure {
+    pub route: String,
+    pub phase: String,
+    // "upstream", "api", "parse", "geometry", "io" or "other"
+    pub kind: String,
+    pub cause: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
+}
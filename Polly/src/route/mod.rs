@@ -1,9 +1,9 @@
 // src/route/mod.rs
 
-mod model;
+pub mod model;
 
 use std::collections::{BTreeMap, HashMap};
-use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -11,56 +11,88 @@ use anyhow::Result;
 use chrono::Local;
 use futures::stream::{self, StreamExt};
 use serde_json::{Value, json};
+use tokio::time::Instant;
+use tracing::{Instrument, debug, info, info_span};
 
-use crate::config::{CONCURRENCY_FETCH, CONCURRENCY_SNAP, OSRM_CHUNK_SIZE, OSRM_URL, TAGO_URL};
+use crate::config::model::PollyConfig;
+use crate::error::{
+    GeometryError, ParseError, PipelineError, StorageError, UpstreamError, describe,
+};
+use crate::report::{FailurePolicy, RUN_REPORT_FILE, RunRecorder, RunStatus, logging};
 use crate::route::model::{
     BusRouteProcessor, DerivedFeature, DerivedFeatureCollection, FrontendMeta, FrontendProperties,
     FrontendStop, RawRouteFile, RawStop, RouteGeometry, RouteIndices, RouteProcessData,
 };
+use crate::storage::{FsStorage, Storage, read_json, write_json};
 use crate::utils::{
-    ensure_dir, extract_items,
-    geo::{calculate_metrics, closest_point_on_polyline, find_nearest_coord_index},
-    get_env, parse_flexible_string, resolve_url,
+    extract_items,
+    geo::{PointIndex, calculate_metrics, closest_point_on_polyline},
+    parse_flexible_string, tago_json,
 };
 
+/// Storage directory of the fetched routes (`<route_no>_<route_id>.json`)
+pub const RAW_DIR: &str = "raw_routes";
+/// Storage directory of the snapped routes (`<route_id>.geojson`)
+pub const DERIVED_DIR: &str = "derived_routes";
+/// Storage key of the station and route map
+pub const ROUTE_MAP_FILE: &str = "routeMap.json";
+
 // ============================================================================
 // Main Execution
 // ============================================================================
 
 pub async fn run(
-    city_code: String,
+    config: &PollyConfig,
     specific_route: Option<String>,
     output_dir: PathBuf,
     station_map_only: bool,
     osrm_only: bool,
-) -> Result<()> {
-    // Setup Directories
-    let raw_dir = output_dir.join("raw_routes");
-    let derived_dir = output_dir.join("derived_routes");
-
-    ensure_dir(&raw_dir)?;
-    ensure_dir(&derived_dir)?;
+    policy: FailurePolicy,
+) -> Result<RunStatus> {
+    let report = Arc::new(RunRecorder::new("route", policy));
+
+    let result = process(
+        config,
+        specific_route,
+        &output_dir,
+        station_map_only,
+        osrm_only,
+        &report,
+    )
+    .await;
+
+    let status = report.finish(&output_dir.join(RUN_REPORT_FILE), &result)?;
+    result.map(|()| status)
+}
 
-    let service_key = get_env("DATA_GO_KR_SERVICE_KEY");
-    if service_key.is_empty() {
-        anyhow::bail!("DATA_GO_KR_SERVICE_KEY is missing!");
+async fn process(
+    config: &PollyConfig,
+    specific_route: Option<String>,
+    output_dir: &Path,
+    station_map_only: bool,
+    osrm_only: bool,
+    report: &Arc<RunRecorder>,
+) -> Result<()> {
+    if config.service_key.is_empty() {
+        anyhow::bail!("DATA_GO_KR_SERVICE_KEY (service_key) is missing!");
     }
 
+    let storage = Arc::new(FsStorage::new(output_dir));
     let processor = Arc::new(BusRouteProcessor {
-        service_key,
-        city_code: city_code.clone(),
-        raw_dir: raw_dir.clone(),
-        derived_dir: derived_dir.clone(),
-        mapping_file: output_dir.join("routeMap.json"),
-        tago_base_url: resolve_url("TAGO_API_URL", TAGO_URL),
-        osrm_base_url: resolve_url("OSRM_API_URL", OSRM_URL),
+        report: Arc::clone(report),
+        ..BusRouteProcessor::new(config, storage)
     });
 
     // [Phase 1] Data Collection (Raw Save)
     if !osrm_only {
-        println!("\n[Phase 1: Fetching Raw Data to {:?}]", raw_dir);
+        let _phase = report.phase("fetch");
+        info!(
+            "[Phase 1: Fetching Raw Data to {:?}]",
+            output_dir.join(RAW_DIR)
+        );
 
-        let routes = processor.get_all_routes().await?;
+        let routes = processor.fetch_route_list().await?;
+        report.add("routesListed", routes.len());
         let target_routes: Vec<Value> = if let Some(target_no) = specific_route.as_ref() {
             routes
                 .into_iter()
@@ -70,14 +102,26 @@ pub async fn run(
             routes
         };
 
-        println!(" Targeting {} routes...", target_routes.len());
+        info!(
+            routes = target_routes.len(),
+            "Targeting {} routes...",
+            target_routes.len()
+        );
+        report.add("routesTargeted", target_routes.len());
+        let progress = logging::progress_bar(target_routes.len(), "Fetching");
 
         let mut route_stream = stream::iter(target_routes)
             .map(|route| {
                 let proc = Arc::clone(&processor);
-                async move { proc.fetch_and_save_raw(route).await }
+                let label = format!(
+                    "{}_{}",
+                    parse_flexible_string(&route["routeno"]),
+                    route["routeid"].as_str().unwrap_or_default()
+                );
+                let span = info_span!("route", route = %label);
+                async move { (label, proc.fetch_and_save_raw(route).await) }.instrument(span)
             })
-            .buffer_unordered(CONCURRENCY_FETCH);
+            .buffer_unordered(config.route.fetch_concurrency);
 
         // Aggregation for routeMap.json
         let mut all_stops = BTreeMap::new();
@@ -85,7 +129,8 @@ pub async fn run(
         let mut route_mapping: BTreeMap<String, Vec<String>> = BTreeMap::new();
         let mut count = 0usize;
 
-        while let Some(result) = route_stream.next().await {
+        while let Some((label, result)) = route_stream.next().await {
+            progress.inc(1);
             match result {
                 Ok(Some(data)) => {
                     count += 1;
@@ -97,68 +142,78 @@ pub async fn run(
                     for (id, val) in data.stops_map {
                         all_stops.insert(id, val);
                     }
-                    if count % 10 == 0 {
-                        print!(".");
-                    }
                 }
-                Ok(None) => {}
-                Err(e) => eprintln!("\n Error: {:?}", e),
+                Ok(None) => report.add("routesWithoutStops", 1),
+                Err(e) => report.fail(&label, "fetch", e.kind(), &describe(&e), None)?,
             }
         }
-        println!("\n Processed {} raw routes.", count);
+        progress.finish_and_clear();
+        info!(routes = count, "Processed {} raw routes.", count);
+        report.add("routesFetched", count);
+        report.add("stations", all_stops.len());
 
-        processor.save_route_map_json(&route_mapping, &route_details_map, &all_stops)?;
+        processor.save_route_map(&route_mapping, &route_details_map, &all_stops)?;
 
         if station_map_only {
-            println!("✓ Station map generated.");
+            info!("✓ Station map generated.");
             return Ok(());
         }
     }
 
     // [Phase 2] Data Processing (Raw -> Derived)
-    println!(
-        "\n[Phase 2: Processing raw data to GeoJSON: {:?}]",
-        derived_dir
+    let _phase = report.phase("snap");
+    info!(
+        "[Phase 2: Processing raw data to GeoJSON: {:?}]",
+        output_dir.join(DERIVED_DIR)
     );
 
-    // Read all JSONs from `raw_routes/`
-    let raw_entries: Vec<_> = fs::read_dir(&raw_dir)?.filter_map(|e| e.ok()).collect();
+    // Read all JSONs from `raw_routes/` (filtered by route)
+    let raw_keys: Vec<String> = processor
+        .storage
+        .list(RAW_DIR)?
+        .into_iter()
+        .filter(|key| key.ends_with(".json"))
+        .filter(|key| {
+            let fname = key.rsplit('/').next().unwrap_or(key);
+            specific_route
+                .as_ref()
+                .is_none_or(|target| fname.starts_with(target) || fname.contains(target))
+        })
+        .collect();
+    let progress = logging::progress_bar(raw_keys.len(), "Snapping");
 
     // Process with concurrency
-    let mut snap_stream = stream::iter(raw_entries)
-        .map(|entry| {
+    let mut snap_stream = stream::iter(raw_keys)
+        .map(|key| {
             let proc = Arc::clone(&processor);
-            let specific = specific_route.clone();
+            let label = key
+                .rsplit('/')
+                .next()
+                .unwrap_or(&key)
+                .trim_end_matches(".json")
+                .to_string();
+            let span = info_span!("route", route = %label);
 
             async move {
-                let path = entry.path();
-                if path.extension().map_or(false, |ext| ext == "json") {
-                    let fname = path.file_name().unwrap().to_string_lossy();
-
-                    // Filter check
-                    if let Some(ref target) = specific {
-                        if !fname.starts_with(target) && !fname.contains(target) {
-                            return Ok(());
-                        }
-                    }
-
-                    println!(" Processing {}...", fname);
-
-                    proc.process_raw_to_derived(&path).await
-                } else {
-                    Ok(())
-                }
+                debug!("Processing {}...", label);
+                (label, proc.process_raw_to_derived(&key).await)
             }
+            .instrument(span)
         })
-        .buffer_unordered(CONCURRENCY_SNAP);
-
-    while let Some(res) = snap_stream.next().await {
-        if let Err(e) = res {
-            eprintln!(" Processing failed: {:?}", e);
+        .buffer_unordered(config.route.snap_concurrency);
+
+    let mut snapped = 0usize;
+    while let Some((label, res)) = snap_stream.next().await {
+        progress.inc(1);
+        match res {
+            Ok(()) => snapped += 1,
+            Err(e) => report.fail(&label, "snap", e.kind(), &describe(&e), None)?,
         }
     }
+    progress.finish_and_clear();
+    report.add("routesSnapped", snapped);
 
-    println!("✓ Pipeline Complete.");
+    info!(routes = snapped, "✓ Pipeline Complete.");
 
     Ok(())
 }
@@ -168,9 +223,26 @@ pub async fn run(
 // ============================================================================
 
 impl BusRouteProcessor {
+    /// A processor with the endpoints and settings of `config`, saving to `storage`.
+    ///
+    /// It uses a default HTTP client and a run report that tolerates any failure.
+    pub fn new(config: &PollyConfig, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            service_key: config.service_key.clone(),
+            city_code: config.city_code.clone(),
+            tago_base_url: config.endpoints.tago_route.clone(),
+            osrm_base_url: config.endpoints.osrm.clone(),
+            settings: config.route.clone(),
+            client: reqwest::Client::new(),
+            storage,
+            report: Arc::new(RunRecorder::new("route", FailurePolicy::default())),
+        }
+    }
+
     // Phase 1 Logic
 
-    async fn get_all_routes(&self) -> Result<Vec<Value>> {
+    /// Lists the routes of the city (TAGO items with `routeid` and `routeno`).
+    pub async fn fetch_route_list(&self) -> Result<Vec<Value>, PipelineError> {
         let params = [
             ("cityCode", self.city_code.as_str()),
             ("numOfRows", "2000"),
@@ -179,49 +251,30 @@ impl BusRouteProcessor {
             ("_type", "json"),
         ];
 
-        let url = format!("{}/getRouteNoList", self.tago_base_url);
-        let resp = reqwest::Client::new()
-            .get(&url)
-            .query(&params)
-            .send()
-            .await?;
-        let json: Value = resp.json().await?;
+        let json = self.call_tago("getRouteNoList", &params).await?;
 
-        extract_items(&json)
+        Ok(extract_items(&json)?)
     }
 
-    async fn fetch_and_save_raw(&self, route_info: Value) -> Result<Option<RouteProcessData>> {
-        let route_id = route_info["routeid"]
-            .as_str()
-            .unwrap_or_default()
-            .to_string();
-        let route_no = parse_flexible_string(&route_info["routeno"]);
-
-        if route_no == "UNKNOWN" || route_id.is_empty() {
-            return Ok(None);
-        }
-
+    /// Fetches the stops of a route in order; `None` when TAGO lists none.
+    pub async fn fetch_raw_route(
+        &self,
+        route_id: &str,
+        route_no: &str,
+    ) -> Result<Option<RawRouteFile>, PipelineError> {
         // Fetch Stops
         let params = [
             ("cityCode", self.city_code.as_str()),
-            ("routeId", route_id.as_str()),
+            ("routeId", route_id),
             ("numOfRows", "1024"),
             ("serviceKey", self.service_key.as_str()),
             ("_type", "json"),
         ];
 
-        let url = format!("{}/getRouteAcctoThrghSttnList", self.tago_base_url);
-        let resp = reqwest::Client::new()
-            .get(&url)
-            .query(&params)
-            .send()
+        let json = self
+            .call_tago("getRouteAcctoThrghSttnList", &params)
             .await?;
 
-        let json: Value = match resp.json().await {
-            Ok(v) => v,
-            Err(_) => return Ok(None),
-        };
-
         let items = extract_items(&json)?;
         if items.is_empty() {
             return Ok(None);
@@ -246,16 +299,39 @@ impl BusRouteProcessor {
 
         stops.sort_by_key(|s| s.node_ord);
 
-        // Save RAW file
-        let raw_file = RawRouteFile {
-            route_id: route_id.clone(),
-            route_no: route_no.clone(),
+        Ok(Some(RawRouteFile {
+            route_id: route_id.to_string(),
+            route_no: route_no.to_string(),
             fetched_at: Local::now().to_rfc3339(),
-            stops: stops.clone(),
-        };
+            stops,
+        }))
+    }
+
+    /// Saves a fetched route to `raw_routes/<route_no>_<route_id>.json`.
+    pub fn save_raw_route(&self, raw: &RawRouteFile) -> Result<(), StorageError> {
+        let key = format!("{}/{}_{}.json", RAW_DIR, raw.route_no, raw.route_id);
+        write_json(self.storage.as_ref(), &key, raw, false)
+    }
 
-        let file_path = self.raw_dir.join(format!("{}_{}.json", route_no, route_id));
-        fs::write(file_path, serde_json::to_string_pretty(&raw_file)?)?;
+    async fn fetch_and_save_raw(
+        &self,
+        route_info: Value,
+    ) -> Result<Option<RouteProcessData>, PipelineError> {
+        let route_id = route_info["routeid"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let route_no = parse_flexible_string(&route_info["routeno"]);
+
+        if route_no == "UNKNOWN" || route_id.is_empty() {
+            return Ok(None);
+        }
+
+        let Some(raw) = self.fetch_raw_route(&route_id, &route_no).await? else {
+            return Ok(None);
+        };
+        self.save_raw_route(&raw)?;
+        let stops = raw.stops;
 
         // Generate Metadata for routeMap.json
         let sequence_meta: Vec<Value> = stops
@@ -289,18 +365,21 @@ impl BusRouteProcessor {
     }
 
     // Phase 2 Logic
-    async fn process_raw_to_derived(&self, raw_path: &Path) -> Result<()> {
-        // Read Raw File
-        let content = fs::read_to_string(raw_path)?;
-        let raw_data: RawRouteFile = serde_json::from_str(&content)?;
 
+    /// Snaps a fetched route to the road network (OSRM), mapping each stop to the path.
+    ///
+    /// Returns `None` for routes with fewer than two stops.
+    pub async fn snap_route(
+        &self,
+        raw_data: RawRouteFile,
+    ) -> Result<Option<DerivedFeatureCollection>, PipelineError> {
         let mut stops = raw_data.stops;
 
         // Sanitize coordinates (drift correction)
         self.sanitize_stops_to_corridor(&mut stops).await;
 
         if stops.len() < 2 {
-            return Ok(());
+            return Ok(None);
         }
 
         let route_id = raw_data.route_id;
@@ -321,54 +400,47 @@ impl BusRouteProcessor {
         let mut stop_to_coord: Vec<usize> = Vec::with_capacity(stops.len());
         let mut start_idx = 0;
 
+        // Stops of each chunk, with the path coordinates OSRM returned for it
+        let mut chunks: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+
         while start_idx < stops.len() - 1 {
-            let end_idx = (start_idx + OSRM_CHUNK_SIZE).min(stops.len());
+            let end_idx = (start_idx + self.settings.osrm_chunk_size).min(stops.len());
             let chunk = &stops[start_idx..end_idx];
 
             if chunk.len() < 2 {
                 break;
             }
 
-            if let Some(coords) = self.fetch_osrm_route(chunk).await {
-                let current_total = full_coordinates.len();
-
-                // Merge Geometry
-                let (to_append, _offset) = if current_total > 0 {
-                    (&coords[1..], 0)
-                } else {
-                    (&coords[..], 0)
-                };
-
-                // Map Stops to Geometry
-                for (i, stop) in chunk.iter().enumerate() {
-                    let global_stop_idx = start_idx + i;
-                    if global_stop_idx < stop_to_coord.len() {
-                        continue;
-                    }
+            // A chunk without a path would leave a gap in the route.
+            let coords = self.fetch_osrm_route(chunk).await?;
 
-                    if let Some(local_idx) =
-                        find_nearest_coord_index((stop.gps_long, stop.gps_lat), &coords)
-                    {
-                        let global_coord_idx = if current_total > 0 {
-                            if local_idx == 0 {
-                                current_total - 1
-                            } else {
-                                current_total + local_idx - 1
-                            }
-                        } else {
-                            local_idx
-                        };
-                        stop_to_coord.push(global_coord_idx);
-                    } else {
-                        stop_to_coord.push(current_total);
-                    }
-                }
+            // Merge Geometry (consecutive chunks share a stop, and so a coordinate)
+            let first = full_coordinates.len().saturating_sub(1);
+            let to_append = if full_coordinates.is_empty() {
+                &coords[..]
+            } else {
+                &coords[1..]
+            };
+            full_coordinates.extend_from_slice(to_append);
+            chunks.push((start_idx..end_idx, first..full_coordinates.len()));
 
-                full_coordinates.extend_from_slice(to_append);
-            }
             start_idx = end_idx - 1;
         }
 
+        // Map Stops to Geometry, each within the path of its chunk
+        let coord_index = PointIndex::new(
+            full_coordinates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| ((c[0], c[1]), i)),
+        );
+        for (stop_range, coord_range) in chunks {
+            for stop in &stops[stop_range.start.max(stop_to_coord.len())..stop_range.end] {
+                let coord = nearest_coord_index(&coord_index, stop, coord_range.clone());
+                stop_to_coord.push(coord.unwrap_or(coord_range.start));
+            }
+        }
+
         while stop_to_coord.len() < stops.len() {
             stop_to_coord.push(full_coordinates.len().saturating_sub(1));
         }
@@ -431,14 +503,48 @@ impl BusRouteProcessor {
             }],
         };
 
-        // Save Derived File
-        let output_path = self.derived_dir.join(format!("{}.geojson", route_id));
-        fs::write(output_path, serde_json::to_string(&derived_data)?)?;
+        Ok(Some(derived_data))
+    }
 
+    /// Saves a snapped route to `derived_routes/<route_id>.geojson`.
+    pub fn save_derived_route(
+        &self,
+        route_id: &str,
+        derived: &DerivedFeatureCollection,
+    ) -> Result<(), StorageError> {
+        // Compact JSON: derived routes are served to the frontend as they are.
+        let key = format!("{}/{}.geojson", DERIVED_DIR, route_id);
+        write_json(self.storage.as_ref(), &key, derived, true)
+    }
+
+    async fn process_raw_to_derived(&self, raw_key: &str) -> Result<(), PipelineError> {
+        let raw_data: RawRouteFile = read_json(self.storage.as_ref(), raw_key)?;
+        let route_id = raw_data.route_id.clone();
+
+        if let Some(derived) = self.snap_route(raw_data).await? {
+            self.save_derived_route(&route_id, &derived)?;
+        }
         Ok(())
     }
 
-    // Helpers (Sanitize, OSRM Fetch, Save Map)
+    // Helpers (TAGO Fetch, Sanitize, OSRM Fetch, Save Map)
+
+    /// Calls a TAGO operation, recording the request in the run report.
+    async fn call_tago(
+        &self,
+        operation: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Value, PipelineError> {
+        let url = format!("{}/{}", self.tago_base_url, operation);
+
+        let started = Instant::now();
+        let resp = self.client.get(&url).query(params).send().await;
+        let status = resp.as_ref().ok().map(|r| r.status().as_u16());
+        self.report.request("tago", status, started.elapsed());
+
+        tago_json(resp).await
+    }
+
     async fn sanitize_stops_to_corridor(&self, stops: &mut [RawStop]) {
         if stops.len() < 3 {
             return;
@@ -448,19 +554,30 @@ impl BusRouteProcessor {
             let prev = stops[i - 1].clone();
             let next = stops[i + 1].clone();
 
-            if let Some(corr) = self.fetch_osrm_route_between(&prev, &next).await {
-                let p = (stops[i].gps_long, stops[i].gps_lat);
-                if let Some(((cx, cy), d)) = closest_point_on_polyline(p, &corr) {
-                    if d <= 90.0 {
-                        stops[i].gps_long = cx;
-                        stops[i].gps_lat = cy;
-                    }
+            // Best effort: a stop without a corridor keeps its coordinates.
+            let corr = match self.fetch_osrm_route_between(&prev, &next).await {
+                Ok(corr) => corr,
+                Err(e) => {
+                    debug!("No corridor for {}: {}", stops[i].node_id, describe(&e));
+                    continue;
                 }
+            };
+
+            let p = (stops[i].gps_long, stops[i].gps_lat);
+            if let Some(((cx, cy), d)) = closest_point_on_polyline(p, &corr)
+                && d <= self.settings.corridor_max_drift_m
+            {
+                stops[i].gps_long = cx;
+                stops[i].gps_lat = cy;
             }
         }
     }
 
-    async fn fetch_osrm_route_between(&self, a: &RawStop, b: &RawStop) -> Option<Vec<Vec<f64>>> {
+    async fn fetch_osrm_route_between(
+        &self,
+        a: &RawStop,
+        b: &RawStop,
+    ) -> Result<Vec<Vec<f64>>, PipelineError> {
         let coords = format!(
             "{:.6},{:.6};{:.6},{:.6}",
             a.gps_long, a.gps_lat, b.gps_long, b.gps_lat
@@ -469,7 +586,7 @@ impl BusRouteProcessor {
         self.call_osrm(&coords).await
     }
 
-    async fn fetch_osrm_route(&self, stops: &[RawStop]) -> Option<Vec<Vec<f64>>> {
+    async fn fetch_osrm_route(&self, stops: &[RawStop]) -> Result<Vec<Vec<f64>>, PipelineError> {
         let coords = stops
             .iter()
             .map(|s| format!("{:.6},{:.6}", s.gps_long, s.gps_lat))
@@ -479,35 +596,84 @@ impl BusRouteProcessor {
         self.call_osrm(&coords).await
     }
 
-    async fn call_osrm(&self, coords_param: &str) -> Option<Vec<Vec<f64>>> {
+    async fn call_osrm(&self, coords_param: &str) -> Result<Vec<Vec<f64>>, PipelineError> {
         let url = format!(
             "{}/{coords}?overview=full&geometries=geojson&steps=false&continue_straight=true",
             self.osrm_base_url,
             coords = coords_param
         );
 
-        let resp = reqwest::get(&url).await.ok()?;
-        if !resp.status().is_success() {
-            return None;
+        let started = Instant::now();
+        let resp = self.client.get(&url).send().await;
+        let status = resp.as_ref().ok().map(|r| r.status().as_u16());
+        self.report.request("osrm", status, started.elapsed());
+
+        let network = |e: reqwest::Error| UpstreamError::Network {
+            service: "OSRM",
+            source: e.without_url(),
+        };
+        let resp = resp.map_err(network)?;
+        let status = resp.status();
+        let body = resp.text().await.map_err(network)?;
+
+        // OSRM explains unroutable requests with a 400 and a JSON code (e.g., "NoRoute").
+        let json: Value = match serde_json::from_str(&body) {
+            Ok(json) => json,
+            Err(_) if !status.is_success() => {
+                return Err(UpstreamError::Status {
+                    service: "OSRM",
+                    status: status.as_u16(),
+                }
+                .into());
+            }
+            Err(source) => {
+                return Err(ParseError::Json {
+                    what: "OSRM response".to_string(),
+                    source,
+                }
+                .into());
+            }
+        };
+        match json["code"].as_str() {
+            Some("Ok") => {}
+            Some(code) => {
+                return Err(GeometryError::NoRoute {
+                    code: code.to_string(),
+                }
+                .into());
+            }
+            None if !status.is_success() => {
+                return Err(UpstreamError::Status {
+                    service: "OSRM",
+                    status: status.as_u16(),
+                }
+                .into());
+            }
+            None => {}
         }
 
-        let json: Value = resp.json().await.ok()?;
-        let coords: Vec<Vec<f64>> =
-            serde_json::from_value(json["routes"][0]["geometry"]["coordinates"].clone()).ok()?;
+        let coords: Vec<Vec<f64>> = serde_json::from_value(
+            json["routes"][0]["geometry"]["coordinates"].clone(),
+        )
+        .map_err(|source| ParseError::Json {
+            what: "OSRM geometry".to_string(),
+            source,
+        })?;
 
         if coords.is_empty() {
-            None
+            Err(GeometryError::Empty.into())
         } else {
-            Some(coords)
+            Ok(coords)
         }
     }
 
-    fn save_route_map_json(
+    /// Saves the station and route map to `routeMap.json`.
+    pub fn save_route_map(
         &self,
         map: &BTreeMap<String, Vec<String>>,
         details: &HashMap<String, Value>,
         stops: &BTreeMap<String, Value>,
-    ) -> Result<()> {
+    ) -> Result<(), StorageError> {
         let final_data = json!({
             "lastUpdated": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
             "route_numbers": map,
@@ -515,11 +681,75 @@ impl BusRouteProcessor {
             "stations": stops
         });
 
-        fs::write(
-            &self.mapping_file,
-            serde_json::to_string_pretty(&final_data)?,
-        )?;
+        write_json(self.storage.as_ref(), ROUTE_MAP_FILE, &final_data, false)
+    }
+}
+
+/// Index of the path coordinate in `range` closest to a stop (the first one when the
+/// path revisits it)
+fn nearest_coord_index(
+    index: &PointIndex<usize>,
+    stop: &RawStop,
+    range: Range<usize>,
+) -> Option<usize> {
+    let point = (stop.gps_long, stop.gps_lat);
+    let (_, distance) = index.nearest_iter(point).find(|(i, _)| range.contains(i))?;
+
+    // Neighbors come in chord order, which may differ slightly from surface distance,
+    // so compare every coordinate within that distance.
+    index
+        .within(point, distance)
+        .into_iter()
+        .filter(|(i, _)| range.contains(i))
+        .min_by(|(a, da), (b, db)| da.total_cmp(db).then(a.cmp(b)))
+        .map(|(&i, _)| i)
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop_at(lon: f64, lat: f64) -> RawStop {
+        RawStop {
+            node_id: String::new(),
+            node_nm: String::new(),
+            node_ord: 0,
+            node_no: String::new(),
+            gps_lat: lat,
+            gps_long: lon,
+            up_down_cd: 0,
+        }
+    }
+
+    fn index(path: &[(f64, f64)]) -> PointIndex<usize> {
+        PointIndex::new(path.iter().enumerate().map(|(i, &c)| (c, i)))
+    }
+
+    #[test]
+    fn nearest_coord_index_takes_the_first_visit_of_a_revisited_point() {
+        // Out along a street and back over the same coordinates
+        let path = [
+            (127.900, 37.30),
+            (127.901, 37.30),
+            (127.902, 37.30),
+            (127.901, 37.30),
+            (127.900, 37.30),
+        ];
+        let index = index(&path);
+        let stop = stop_at(127.901, 37.3001);
+
+        assert_eq!(nearest_coord_index(&index, &stop, 0..5), Some(1));
+        assert_eq!(nearest_coord_index(&index, &stop, 2..5), Some(3));
+    }
+
+    #[test]
+    fn nearest_coord_index_stays_within_its_range() {
+        let path = [(127.900, 37.30), (127.910, 37.30), (127.920, 37.30)];
+        let index = index(&path);
+        let stop = stop_at(127.9199, 37.30);
+
+        assert_eq!(nearest_coord_index(&index, &stop, 0..3), Some(2));
+        assert_eq!(nearest_coord_index(&index, &stop, 0..2), Some(1));
+        assert_eq!(nearest_coord_index(&index, &stop, 0..0), None);
     }
 }
@@ -1,10 +1,15 @@
 // src/route/model.rs
 
-use std::path::PathBuf;
+use std::sync::Arc;
 
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::config::model::RouteSettings;
+use crate::report::RunRecorder;
+use crate::storage::Storage;
+
 // ============================================================================
 // Raw Data Models (Saved to raw_routes/)
 // ============================================================================
@@ -35,14 +40,14 @@ pub struct RawRouteFile {
 // ============================================================================
 
 /// GeoJSON structure for Frontend
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct DerivedFeatureCollection {
     #[serde(rename = "type")]
     pub type_: String, // "FeatureCollection"
     pub features: Vec<DerivedFeature>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct DerivedFeature {
     #[serde(rename = "type")]
     pub type_: String, // "Feature"
@@ -52,7 +57,7 @@ pub struct DerivedFeature {
     pub geometry: RouteGeometry,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct RouteGeometry {
     #[serde(rename = "type")]
     pub type_: String, // "LineString"
@@ -60,7 +65,7 @@ pub struct RouteGeometry {
 }
 
 /// [Core] Lightweight Properties containing only essential info for Frontend
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct FrontendProperties {
     // Basic Info
     pub route_id: String,
@@ -78,7 +83,7 @@ pub struct FrontendProperties {
     pub meta: FrontendMeta,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct FrontendStop {
     pub id: String,
     pub name: String,
@@ -86,20 +91,20 @@ pub struct FrontendStop {
     pub up_down: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct RouteIndices {
     pub turn_idx: usize, // Index of the turning point coordinate
     // Mapping: Station ID -> Index on the full route path (coordinates)
     pub stop_to_coord: Vec<usize>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct FrontendMeta {
     pub total_dist: f64,
     pub source_ver: String, // e.g., "raw-20260121"
 }
 
-/// Internal processing structure
+/// Station and route metadata of one route, for routeMap.json
 pub struct RouteProcessData {
     pub route_id: String,
     pub route_no: String,
@@ -107,13 +112,20 @@ pub struct RouteProcessData {
     pub stops_map: Vec<(String, Value)>,
 }
 
-/// Main processor structure
+/// Fetches routes from TAGO and snaps them to the road network with OSRM.
+///
+/// Build one with `BusRouteProcessor::new`; the fields can be replaced to inject an HTTP
+/// client (proxies, timeouts) or a run recorder shared with other processors.
 pub struct BusRouteProcessor {
     pub service_key: String,
     pub city_code: String,
-    pub raw_dir: PathBuf,
-    pub derived_dir: PathBuf,
-    pub mapping_file: PathBuf,
     pub tago_base_url: String,
     pub osrm_base_url: String,
+    pub settings: RouteSettings,
+    // Client for TAGO and OSRM requests
+    pub client: Client,
+    // Output files: raw_routes/, derived_routes/ and routeMap.json
+    pub storage: Arc<dyn Storage>,
+    // Statistics of the current run (run_report.json)
+    pub report: Arc<RunRecorder>,
 }
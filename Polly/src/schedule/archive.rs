@@ -0,0 +1,98 @@
+// src/schedule/archive.rs
+
+use anyhow::{Context, Result};
+use chrono::Local;
+
+use crate::error::StorageError;
+use crate::schedule::model::{ArchiveManifest, ArchivedPage, FetchedPage, SnapshotArchive};
+use crate::storage::{Storage, read_json, read_text, write_json};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MAIN_PAGE_FILE: &str = "main.html";
+
+impl SnapshotArchive {
+    /// Creates the archive of a new run under `base_dir/<YYYYMMDD-HHMMSS>/`.
+    pub fn create(base_dir: &str) -> Self {
+        let now = Local::now();
+
+        Self {
+            dir: format!("{}/{}", base_dir, now.format("%Y%m%d-%H%M%S")),
+            manifest: ArchiveManifest {
+                fetched_at: now.to_rfc3339(),
+                main_page: MAIN_PAGE_FILE.to_string(),
+                pages: Vec::new(),
+            },
+        }
+    }
+
+    /// Opens the archive of a previous run in `dir` (empty for the storage root).
+    pub fn open(storage: &dyn Storage, dir: &str) -> Result<Self> {
+        let dir = dir.trim_end_matches('/').to_string();
+        let key = file_key(&dir, MANIFEST_FILE);
+        let manifest = read_json(storage, &key)
+            .with_context(|| format!("Failed to read archive manifest {:?}", key))?;
+
+        Ok(Self { dir, manifest })
+    }
+
+    pub fn save_main(&self, storage: &dyn Storage, html: &str) -> Result<()> {
+        storage.write(&self.key(&self.manifest.main_page), html.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn read_main(&self, storage: &dyn Storage) -> Result<String> {
+        Ok(read_text(storage, &self.key(&self.manifest.main_page))?)
+    }
+
+    /// Saves a detail page as `detail/<route id>.html` and returns its key.
+    pub fn save_detail(
+        &mut self,
+        storage: &dyn Storage,
+        route_id: &str,
+        page: &FetchedPage,
+    ) -> Result<String> {
+        let safe_name = route_id.replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_");
+        let file = format!("detail/{}.html", safe_name);
+        let key = self.key(&file);
+        storage.write(&key, page.html.as_bytes())?;
+
+        self.manifest.pages.push(ArchivedPage {
+            route_id: route_id.to_string(),
+            file,
+            fetched_at: page.fetched_at.to_rfc3339(),
+        });
+        Ok(key)
+    }
+
+    /// Reads the archived detail page of a route, if it was fetched in this run.
+    pub fn read_detail(
+        &self,
+        storage: &dyn Storage,
+        route_id: &str,
+    ) -> Option<Result<String, StorageError>> {
+        let page = self
+            .manifest
+            .pages
+            .iter()
+            .find(|p| p.route_id == route_id)?;
+        Some(read_text(storage, &self.key(&page.file)))
+    }
+
+    /// Writes the manifest, listing the pages saved so far.
+    pub fn finish(&self, storage: &dyn Storage) -> Result<()> {
+        write_json(storage, &self.key(MANIFEST_FILE), &self.manifest, false)?;
+        Ok(())
+    }
+
+    fn key(&self, file: &str) -> String {
+        file_key(&self.dir, file)
+    }
+}
+
+/// Storage key of a file in the archive directory `dir`
+fn file_key(dir: &str, file: &str) -> String {
+    match dir.is_empty() {
+        true => file.to_string(),
+        false => format!("{}/{}", dir, file),
+    }
+}
@@ -0,0 +1,280 @@
+// src/schedule/crawler.rs
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Local;
+use futures::stream::{self, StreamExt};
+use percent_encoding::{NON_ALPHANUMERIC, percent_encode};
+use reqwest::{Client, Response, StatusCode, header};
+use tokio::sync::Mutex;
+use tokio::time::{Instant, sleep, sleep_until};
+use tracing::{Instrument, debug, error, info_span, warn};
+use url::Url;
+
+use crate::config::model::PollyConfig;
+use crate::error::ErrorKind;
+use crate::report::{RunRecorder, logging};
+use crate::schedule::model::{CrawlOptions, FetchFailure, FetchedPage, ScheduleCrawler};
+
+/// Why a single request attempt failed
+enum AttemptError {
+    // Network errors, timeouts, 5xx and 429: retried after a backoff
+    Transient(String),
+    // The server answered without a schedule table: re-prime the session, then retry
+    SessionExpired,
+    // Other client errors: not retried
+    Permanent(String),
+}
+
+impl CrawlOptions {
+    /// Crawl settings from the `[schedule]` and `[endpoints]` configuration.
+    pub fn from_config(config: &PollyConfig) -> Self {
+        let settings = &config.schedule;
+        Self {
+            concurrency: settings.concurrency,
+            request_interval: Duration::from_millis(settings.request_interval_ms),
+            max_retries: settings.max_retries,
+            retry_base: Duration::from_millis(settings.retry_base_ms),
+            user_agent: settings.user_agent.clone(),
+            main_url: config.endpoints.schedule_main.clone(),
+            detail_url: config.endpoints.schedule_detail.clone(),
+        }
+    }
+}
+
+impl ScheduleCrawler {
+    /// Builds a crawler with an HTTP client that mimics a web browser.
+    ///
+    /// The cookie store keeps the session cookie (JSESSIONID) from the main page,
+    /// which the detail page requires.
+    pub fn new(options: CrawlOptions, report: Arc<RunRecorder>) -> Result<Self> {
+        let client = Client::builder()
+            .cookie_store(true)
+            .user_agent(options.user_agent.as_str())
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self::with_client(client, options, report))
+    }
+
+    /// Builds a crawler on a caller-provided HTTP client.
+    ///
+    /// The client must keep cookies (`cookie_store(true)`); `options.user_agent` is not
+    /// applied to it.
+    pub fn with_client(client: Client, options: CrawlOptions, report: Arc<RunRecorder>) -> Self {
+        Self {
+            client,
+            options,
+            next_request_at: Mutex::new(Instant::now()),
+            session_generation: Mutex::new(0),
+            report,
+        }
+    }
+
+    /// Fetches the main schedule page, (re)initializing the session cookies.
+    pub async fn fetch_main_page(&self) -> Result<String> {
+        self.wait_turn().await;
+
+        let started = Instant::now();
+        let resp = self.client.get(&self.options.main_url).send().await;
+        self.record_request(&resp, started);
+
+        Ok(resp?.error_for_status()?.text().await?)
+    }
+
+    /// Fetches the detail pages of all routes with bounded concurrency, handing each to
+    /// `on_page` as soon as it is fetched.
+    ///
+    /// Pages are handed over in the order of `route_ids`, so merging stays deterministic.
+    /// Routes that still fail after retries are recorded in the run report; the crawl
+    /// stops when they exceed the failure policy, or when `on_page` fails.
+    pub async fn fetch_all_details(
+        &self,
+        route_ids: &[String],
+        mut on_page: impl FnMut(String, FetchedPage) -> Result<()>,
+    ) -> Result<()> {
+        let progress = logging::progress_bar(route_ids.len(), "Fetching");
+
+        let mut results = stream::iter(route_ids)
+            .map(|route_id| {
+                async move {
+                    let result = self.fetch_detail(route_id).await;
+                    if result.is_ok() {
+                        debug!("Fetched {}", route_id);
+                    }
+                    (route_id.clone(), result)
+                }
+                .instrument(info_span!("route", route = %route_id))
+            })
+            .buffered(self.options.concurrency.max(1));
+
+        while let Some((route_id, result)) = results.next().await {
+            progress.inc(1);
+            match result {
+                Ok(page) => {
+                    if let Err(e) = on_page(route_id, page) {
+                        progress.abandon();
+                        return Err(e);
+                    }
+                }
+                Err(f) => {
+                    let failed = self.report.fail(
+                        &f.route_id,
+                        "fetch",
+                        ErrorKind::Upstream,
+                        &f.reason,
+                        Some(f.attempts),
+                    );
+                    if let Err(limit) = failed {
+                        progress.abandon();
+                        return Err(limit.into());
+                    }
+                }
+            }
+        }
+
+        progress.finish_and_clear();
+        Ok(())
+    }
+
+    /// Fetches the detail page of a route, retrying transient failures with
+    /// exponential backoff and re-priming the session when it has expired.
+    async fn fetch_detail(&self, route_id: &str) -> Result<FetchedPage, FetchFailure> {
+        let max_attempts = self.options.max_retries + 1;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let generation = *self.session_generation.lock().await;
+
+            let reason = match self.try_fetch_detail(route_id).await {
+                Ok(html) => {
+                    return Ok(FetchedPage {
+                        html,
+                        fetched_at: Local::now(),
+                    });
+                }
+                Err(AttemptError::Permanent(reason)) => {
+                    return Err(FetchFailure {
+                        route_id: route_id.to_string(),
+                        reason,
+                        attempts: attempt,
+                    });
+                }
+                Err(AttemptError::Transient(reason)) => reason,
+                Err(AttemptError::SessionExpired) => {
+                    self.reprime_session(generation).await;
+                    "Session expired".to_string()
+                }
+            };
+
+            if attempt >= max_attempts {
+                return Err(FetchFailure {
+                    route_id: route_id.to_string(),
+                    reason,
+                    attempts: attempt,
+                });
+            }
+
+            let backoff = self.options.retry_base * (1u32 << (attempt - 1).min(6));
+            warn!(
+                "Retrying {} in {:.1}s ({}, attempt {}/{})",
+                route_id,
+                backoff.as_secs_f64(),
+                reason,
+                attempt,
+                max_attempts
+            );
+            self.report.retry("schedule");
+            sleep(backoff).await;
+        }
+    }
+
+    async fn try_fetch_detail(&self, route_id: &str) -> Result<String, AttemptError> {
+        self.wait_turn().await;
+
+        // The website expects the route ID in the POST body to be percent-encoded UTF-8.
+        let encoded_val = percent_encode(route_id.as_bytes(), NON_ALPHANUMERIC).to_string();
+        let body_str = format!("no={}", encoded_val);
+
+        // Set the headers (Referer, Origin, Content-Type) of a request made from the website.
+        let mut request = self
+            .client
+            .post(&self.options.detail_url)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::REFERER, &self.options.main_url);
+        if let Some(origin) = origin_of(&self.options.main_url) {
+            request = request.header(header::ORIGIN, origin);
+        }
+
+        let started = Instant::now();
+        let resp = request.body(body_str).send().await;
+        self.record_request(&resp, started);
+
+        let resp = resp.map_err(|e| AttemptError::Transient(format!("Network: {}", e)))?;
+
+        let status = resp.status();
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(AttemptError::Transient(format!("Status: {}", status)));
+        }
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(AttemptError::SessionExpired);
+        }
+        if !status.is_success() {
+            return Err(AttemptError::Permanent(format!("Status: {}", status)));
+        }
+
+        let html = resp
+            .text()
+            .await
+            .map_err(|e| AttemptError::Transient(format!("Network: {}", e)))?;
+
+        // Without a valid session the site answers with a page that has no timetable.
+        if !html.contains("<table") {
+            return Err(AttemptError::SessionExpired);
+        }
+
+        Ok(html)
+    }
+
+    /// Re-fetches the main page for fresh session cookies.
+    ///
+    /// Workers that saw the same expired session re-prime it only once: `generation` is
+    /// the session a worker used, and a newer session means another worker already did.
+    async fn reprime_session(&self, generation: u64) {
+        let mut current = self.session_generation.lock().await;
+        if *current != generation {
+            return;
+        }
+
+        warn!("Session expired; re-initializing session...");
+        match self.fetch_main_page().await {
+            Ok(_) => *current += 1,
+            Err(e) => error!("✗ Failed to re-initialize session: {}", e),
+        }
+    }
+
+    fn record_request(&self, resp: &reqwest::Result<Response>, started: Instant) {
+        let status = resp.as_ref().ok().map(|r| r.status().as_u16());
+        self.report.request("schedule", status, started.elapsed());
+    }
+
+    /// Waits until the request interval since the previous request has passed.
+    async fn wait_turn(&self) {
+        let at = {
+            let mut next = self.next_request_at.lock().await;
+            let at = (*next).max(Instant::now());
+            *next = at + self.options.request_interval;
+            at
+        };
+        sleep_until(at).await;
+    }
+}
+
+/// Origin ("http://its.wonju.go.kr") of the page that submits a request to the site
+fn origin_of(page_url: &str) -> Option<String> {
+    let origin = Url::parse(page_url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
@@ -0,0 +1,202 @@
+// src/schedule/day_type.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+// ============================================================================
+// Day Types
+// ============================================================================
+
+pub const WEEKDAY: &str = "weekday";
+pub const SATURDAY: &str = "saturday";
+pub const SUNDAY: &str = "sunday";
+pub const HOLIDAY: &str = "holiday";
+pub const WEEKEND: &str = "weekend";
+pub const VACATION_WEEKDAY: &str = "vacation_weekday";
+pub const VACATION_WEEKEND: &str = "vacation_weekend";
+pub const GENERAL: &str = "general";
+
+/// Component of a raw day type label (e.g., "토" and "공휴일" in "토·공휴일")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LabelPart {
+    Weekday,
+    Saturday,
+    Sunday,
+    Holiday,
+    Weekend,
+    Vacation,
+}
+
+/// Maps raw Korean day type labels from the schedule site to output day types.
+///
+/// Labels listed in the mapping file are used verbatim; all other labels are split
+/// into parts ("토·공휴일" -> Saturday, Holiday) and resolved by `resolve_parts`.
+#[derive(Debug, Clone, Default)]
+pub struct DayTypeMapping {
+    pub overrides: HashMap<String, String>,
+}
+
+impl DayTypeMapping {
+    /// Loads label overrides from a JSON object (`{ "raw label": "day type" }`).
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read day type mapping {:?}", path))?;
+        let overrides: HashMap<String, String> = serde_json::from_str(&content)?;
+
+        Ok(Self {
+            overrides: overrides
+                .into_iter()
+                .map(|(k, v)| (k.trim().to_string(), v))
+                .collect(),
+        })
+    }
+
+    /// Resolves a raw label (e.g., "평일", "토요일", "방학(주말)") to a day type.
+    pub fn resolve(&self, raw: &str) -> String {
+        let raw = raw.trim();
+        if let Some(day_type) = self.overrides.get(raw) {
+            return day_type.clone();
+        }
+
+        let parts: Vec<LabelPart> = raw
+            .split(|c: char| "/·,()·・&+ ".contains(c) || c.is_whitespace())
+            .flat_map(|token| token.split("및"))
+            .filter_map(classify_token)
+            .collect();
+
+        resolve_parts(&parts).to_string()
+    }
+}
+
+fn classify_token(token: &str) -> Option<LabelPart> {
+    let token = token.trim();
+    match token {
+        "" => None,
+        "평일" | "주중" => Some(LabelPart::Weekday),
+        "토" | "토요일" => Some(LabelPart::Saturday),
+        "일" | "일요일" => Some(LabelPart::Sunday),
+        "휴일" | "공휴" | "공휴일" | "법정공휴일" => Some(LabelPart::Holiday),
+        "주말" => Some(LabelPart::Weekend),
+        _ if token.contains("방학") => Some(LabelPart::Vacation),
+        _ if token.contains("평일") => Some(LabelPart::Weekday),
+        _ if token.contains("주말") => Some(LabelPart::Weekend),
+        _ if token.contains("공휴") || token.contains("휴일") => Some(LabelPart::Holiday),
+        _ if token.contains("토요") => Some(LabelPart::Saturday),
+        _ if token.contains("일요") => Some(LabelPart::Sunday),
+        _ => None,
+    }
+}
+
+/// Combines label parts into a day type.
+///
+/// - Vacation labels become `vacation_weekend` when they mention any non-weekday
+///   part, otherwise `vacation_weekday`.
+/// - Saturday combined with Sunday or holidays ("토, 일", "토·공휴일") becomes `weekend`,
+///   so the timetable is found from the Saturday, Sunday and holiday chains alike.
+/// - Otherwise the first part wins ("일·공휴일" -> `sunday`).
+fn resolve_parts(parts: &[LabelPart]) -> &'static str {
+    let has = |p: LabelPart| parts.contains(&p);
+
+    if has(LabelPart::Vacation) {
+        let off_day = has(LabelPart::Saturday)
+            || has(LabelPart::Sunday)
+            || has(LabelPart::Holiday)
+            || has(LabelPart::Weekend);
+        return if off_day {
+            VACATION_WEEKEND
+        } else {
+            VACATION_WEEKDAY
+        };
+    }
+
+    if has(LabelPart::Saturday) && (has(LabelPart::Sunday) || has(LabelPart::Holiday)) {
+        return WEEKEND;
+    }
+
+    match parts.first() {
+        Some(LabelPart::Weekday) => WEEKDAY,
+        Some(LabelPart::Saturday) => SATURDAY,
+        Some(LabelPart::Sunday) => SUNDAY,
+        Some(LabelPart::Holiday) => HOLIDAY,
+        Some(LabelPart::Weekend) => WEEKEND,
+        Some(LabelPart::Vacation) | None => GENERAL,
+    }
+}
+
+/// Day types to try, in order, when looking up the timetable for `day_type`.
+///
+/// A route without a dedicated holiday timetable runs its Sunday or weekend one, and
+/// every chain ends with `general` (routes with a single timetable).
+pub fn fallback_chain(day_type: &str) -> Vec<&str> {
+    let rest: &[&str] = match day_type {
+        SATURDAY => &[WEEKEND],
+        SUNDAY => &[WEEKEND],
+        HOLIDAY => &[SUNDAY, WEEKEND],
+        VACATION_WEEKDAY => &[WEEKDAY],
+        VACATION_WEEKEND => &[WEEKEND],
+        _ => &[],
+    };
+
+    let mut chain = vec![day_type];
+    chain.extend_from_slice(rest);
+    if day_type != GENERAL {
+        chain.push(GENERAL);
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(raw: &str) -> String {
+        DayTypeMapping::default().resolve(raw)
+    }
+
+    #[test]
+    fn resolves_single_labels() {
+        assert_eq!(resolve("평일"), WEEKDAY);
+        assert_eq!(resolve("토요일"), SATURDAY);
+        assert_eq!(resolve("일요일"), SUNDAY);
+        assert_eq!(resolve("공휴일"), HOLIDAY);
+        assert_eq!(resolve("주말"), WEEKEND);
+        assert_eq!(resolve("방학(평일)"), VACATION_WEEKDAY);
+        assert_eq!(resolve("방학(주말)"), VACATION_WEEKEND);
+    }
+
+    #[test]
+    fn resolves_combined_saturday_labels_to_weekend() {
+        assert_eq!(resolve("토·공휴일"), WEEKEND);
+        assert_eq!(resolve("토요일 및 공휴일"), WEEKEND);
+        assert_eq!(resolve("토, 일"), WEEKEND);
+        assert_eq!(resolve("토/일/공휴일"), WEEKEND);
+        assert_eq!(resolve("일·공휴일"), SUNDAY);
+    }
+
+    #[test]
+    fn combined_labels_are_reached_from_every_off_day() {
+        for raw in ["토·공휴일", "토, 일"] {
+            let day_type = resolve(raw);
+            for off_day in [SATURDAY, SUNDAY, HOLIDAY] {
+                assert!(
+                    fallback_chain(off_day).contains(&day_type.as_str()),
+                    "{} ({}) is not reached from {}",
+                    raw,
+                    day_type,
+                    off_day
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn overrides_win_over_parsing() {
+        let mapping = DayTypeMapping {
+            overrides: HashMap::from([("토·공휴일".to_string(), SATURDAY.to_string())]),
+        };
+        assert_eq!(mapping.resolve(" 토·공휴일 "), SATURDAY);
+    }
+}
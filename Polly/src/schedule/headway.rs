@@ -0,0 +1,159 @@
+// src/schedule/headway.rs
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use crate::config::{
+    HEADWAY_MIN_REGULAR_TRIPS, HEADWAY_REGULAR_TOLERANCE_MINS, HEADWAY_TIME_BANDS,
+};
+use crate::schedule::model::{HeadwayBand, HeadwaySummary, RegularPeriod};
+use crate::schedule::service_time::ServiceTime;
+
+/// Adds `headways` (day type -> direction -> summary) to a schedule JSON object
+/// (a route file, or its `upcoming` timetable).
+pub fn describe(route_json: &mut Value) {
+    let mut headways: BTreeMap<String, BTreeMap<String, HeadwaySummary>> = BTreeMap::new();
+
+    for (day_type, times) in departures_by_day_type(&route_json["schedule"]) {
+        for (direction, mut times) in times {
+            times.sort();
+            if let Some(summary) = summarize(&times) {
+                headways
+                    .entry(day_type.clone())
+                    .or_default()
+                    .insert(direction, summary);
+            }
+        }
+    }
+
+    route_json["headways"] = json!(headways);
+}
+
+/// Departure times per day type and direction of a `schedule` object.
+fn departures_by_day_type(
+    schedule: &Value,
+) -> BTreeMap<String, BTreeMap<String, Vec<ServiceTime>>> {
+    let mut result: BTreeMap<String, BTreeMap<String, Vec<ServiceTime>>> = BTreeMap::new();
+
+    for (day_type, table) in schedule.as_object().into_iter().flatten() {
+        for (hour, by_direction) in table.as_object().into_iter().flatten() {
+            for (direction, minutes) in by_direction.as_object().into_iter().flatten() {
+                for m in minutes.as_array().into_iter().flatten() {
+                    if let Some(time) = m["minute"]
+                        .as_str()
+                        .and_then(|min| ServiceTime::from_hour_minute(hour, min).ok())
+                    {
+                        result
+                            .entry(day_type.clone())
+                            .or_default()
+                            .entry(direction.clone())
+                            .or_default()
+                            .push(time);
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Summarizes sorted departure times. Returns `None` when there are none.
+///
+/// Headways are counted in the band of the earlier departure. Regular periods are the
+/// longest runs of at least `HEADWAY_MIN_REGULAR_TRIPS` departures whose headways stay
+/// within `HEADWAY_REGULAR_TOLERANCE_MINS` of each other.
+pub fn summarize(times: &[ServiceTime]) -> Option<HeadwaySummary> {
+    let (first, last) = (times.first()?, times.last()?);
+
+    let mut trips_per_hour: BTreeMap<String, usize> = BTreeMap::new();
+    for t in times {
+        *trips_per_hour
+            .entry(format!("{:02}", t.hour()))
+            .or_default() += 1;
+    }
+
+    let bands = HEADWAY_TIME_BANDS
+        .iter()
+        .filter_map(|&(name, from, to)| {
+            let in_band = |t: &ServiceTime| (from..to).contains(&t.minutes());
+            let trips = times.iter().filter(|t| in_band(t)).count();
+            if trips == 0 {
+                return None;
+            }
+
+            let mut headways: Vec<u32> = times
+                .windows(2)
+                .filter(|w| in_band(&w[0]))
+                .map(|w| w[1].minutes() - w[0].minutes())
+                .collect();
+            headways.sort_unstable();
+
+            Some(HeadwayBand {
+                band: name.to_string(),
+                from: ServiceTime::from_minutes(from).to_string(),
+                to: ServiceTime::from_minutes(to).to_string(),
+                trips,
+                min_headway: headways.first().copied(),
+                median_headway: median(&headways),
+                max_headway: headways.last().copied(),
+            })
+        })
+        .collect();
+
+    Some(HeadwaySummary {
+        first_departure: first.to_string(),
+        last_departure: last.to_string(),
+        trips: times.len(),
+        trips_per_hour,
+        bands,
+        regular_periods: regular_periods(times),
+    })
+}
+
+fn regular_periods(times: &[ServiceTime]) -> Vec<RegularPeriod> {
+    let mut periods = Vec::new();
+    let mut start = 0usize;
+
+    while start + 1 < times.len() {
+        // Extend the run while the spread of its headways stays within the tolerance.
+        let mut end = start;
+        let (mut lo, mut hi) = (u32::MAX, 0u32);
+        while end + 1 < times.len() {
+            let headway = times[end + 1].minutes() - times[end].minutes();
+            let (new_lo, new_hi) = (lo.min(headway), hi.max(headway));
+            if headway == 0 || new_hi - new_lo > HEADWAY_REGULAR_TOLERANCE_MINS {
+                break;
+            }
+            (lo, hi) = (new_lo, new_hi);
+            end += 1;
+        }
+
+        let trips = end - start + 1;
+        if trips >= HEADWAY_MIN_REGULAR_TRIPS {
+            let span = times[end].minutes() - times[start].minutes();
+            periods.push(RegularPeriod {
+                from: times[start].to_string(),
+                to: times[end].to_string(),
+                headway_mins: (span as f64 / (trips - 1) as f64).round() as u32,
+                exact: lo == hi,
+                trips,
+            });
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+
+    periods
+}
+
+fn median(sorted: &[u32]) -> Option<f64> {
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2] as f64),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0),
+    }
+}
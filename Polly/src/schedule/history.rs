@@ -0,0 +1,238 @@
+// src/schedule/history.rs
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde_json::{Map, Value, json};
+
+use crate::schedule::model::{ScheduleHistory, ScheduleVersion};
+use crate::storage::{Storage, read_json, write_json};
+
+const INDEX_FILE: &str = "index.json";
+
+// Fields of a schedule file that make up its timetable. Everything else (featured stops,
+// route details, dates) is derived and does not create a new version.
+const TIMETABLE_FIELDS: [&str; 4] = ["directions", "dayTypeLabels", "schedule", "notes"];
+
+/// What recording a freshly parsed timetable did to a route's history
+pub enum HistoryUpdate {
+    /// The route had no history yet.
+    First,
+    Unchanged,
+    /// The timetable differs from the latest version.
+    Changed {
+        previous: u32,
+        version: u32,
+    },
+}
+
+/// Records the timetable in `data` as of `as_of` and rewrites `data` for publishing.
+///
+/// 1. Compares the timetable with the latest version in `history_dir/index.json` and
+///    saves it as a new version (`v<n>.json`) when it differs.
+/// 2. Picks the current version: the latest one whose effective date has been reached.
+/// 3. Replaces the timetable in `data` with the current version, sets `effectiveFrom`
+///    and `lastUpdated` (the date the current version was first seen), and lists a
+///    newer version that is not in effect yet under `upcoming`.
+///
+pub fn record(
+    storage: &dyn Storage,
+    history_dir: &str,
+    route_number: &str,
+    data: &mut Value,
+    as_of: NaiveDate,
+) -> Result<HistoryUpdate> {
+    let index_key = format!("{}/{}", history_dir, INDEX_FILE);
+    let mut history = if storage.list(history_dir)?.contains(&index_key) {
+        read_json(storage, &index_key)
+            .with_context(|| format!("Failed to read schedule history {:?}", index_key))?
+    } else {
+        ScheduleHistory {
+            route_id: route_number.to_string(),
+            versions: Vec::new(),
+        }
+    };
+
+    let timetable = timetable_of(data);
+    let stated = data["effectiveFrom"]
+        .as_str()
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+
+    let latest = history.versions.last().cloned();
+    let unchanged = match &latest {
+        Some(v) => read_version(storage, history_dir, v)? == timetable,
+        None => false,
+    };
+
+    let update = if unchanged {
+        if let Some(v) = history.versions.last_mut() {
+            v.last_seen = v.last_seen.max(as_of);
+            // A date stated later (or corrected) on the page replaces the recorded one.
+            if let Some(date) = stated {
+                v.effective_from = date;
+            }
+        }
+        HistoryUpdate::Unchanged
+    } else {
+        let version = latest.as_ref().map_or(1, |v| v.version + 1);
+        let entry = ScheduleVersion {
+            version,
+            effective_from: stated.unwrap_or(as_of),
+            first_seen: as_of,
+            last_seen: as_of,
+            file: format!("v{}.json", version),
+        };
+        let key = format!("{}/{}", history_dir, entry.file);
+        write_json(storage, &key, &timetable, false)?;
+        history.versions.push(entry);
+
+        match latest {
+            Some(previous) => HistoryUpdate::Changed {
+                previous: previous.version,
+                version,
+            },
+            None => HistoryUpdate::First,
+        }
+    };
+
+    write_json(storage, &index_key, &history, false)?;
+
+    // The latest version that is in effect; with none yet, the oldest known one.
+    let current = history
+        .versions
+        .iter()
+        .rev()
+        .find(|v| v.effective_from <= as_of)
+        .or(history.versions.first())
+        .cloned()
+        .context("Schedule history has no versions")?;
+    let upcoming = history
+        .versions
+        .last()
+        .filter(|v| v.version > current.version && v.effective_from > as_of)
+        .cloned();
+
+    let current_timetable = read_version(storage, history_dir, &current)?;
+    for field in TIMETABLE_FIELDS {
+        data[field] = current_timetable.get(field).cloned().unwrap_or(Value::Null);
+    }
+    data["effectiveFrom"] = json!(current.effective_from);
+    data["lastUpdated"] = json!(current.first_seen);
+
+    let data = data
+        .as_object_mut()
+        .context("Schedule is not a JSON object")?;
+    match upcoming {
+        Some(v) => {
+            let mut upcoming = read_version(storage, history_dir, &v)?;
+            upcoming.insert("effectiveFrom".to_string(), json!(v.effective_from));
+            data.insert("upcoming".to_string(), Value::Object(upcoming));
+        }
+        None => {
+            data.remove("upcoming");
+        }
+    }
+
+    Ok(update)
+}
+
+/// Storage key of the directory holding the history of a route (`schedules/history/<route>`)
+pub fn history_dir(schedule_dir: &str, file_name: &str) -> String {
+    format!(
+        "{}/history/{}",
+        schedule_dir,
+        file_name.trim_end_matches(".json")
+    )
+}
+
+fn timetable_of(data: &Value) -> Map<String, Value> {
+    TIMETABLE_FIELDS
+        .iter()
+        .map(|field| (field.to_string(), data[*field].clone()))
+        .collect()
+}
+
+fn read_version(
+    storage: &dyn Storage,
+    history_dir: &str,
+    version: &ScheduleVersion,
+) -> Result<Map<String, Value>> {
+    let key = format!("{}/{}", history_dir, version.file);
+    read_json(storage, &key).with_context(|| format!("Failed to read schedule version {:?}", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    const DIR: &str = "schedules/history/34-1";
+
+    fn schedule(minute: &str, effective_from: Option<&str>) -> Value {
+        let mut data = json!({
+            "routeId": "34-1",
+            "directions": ["회촌"],
+            "schedule": { "weekday": { "06": { "회촌": [{ "minute": minute }] } } },
+        });
+        if let Some(date) = effective_from {
+            data["effectiveFrom"] = json!(date);
+        }
+        data
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn records_versions_in_storage() {
+        let storage = MemoryStorage::new();
+
+        let mut data = schedule("10", None);
+        let update = record(&storage, DIR, "34-1", &mut data, date("2025-03-01")).unwrap();
+        assert!(matches!(update, HistoryUpdate::First));
+
+        let mut data = schedule("10", None);
+        let update = record(&storage, DIR, "34-1", &mut data, date("2025-03-02")).unwrap();
+        assert!(matches!(update, HistoryUpdate::Unchanged));
+
+        let mut data = schedule("20", None);
+        let update = record(&storage, DIR, "34-1", &mut data, date("2025-03-03")).unwrap();
+        assert!(matches!(
+            update,
+            HistoryUpdate::Changed {
+                previous: 1,
+                version: 2
+            }
+        ));
+        assert_eq!(data["lastUpdated"], json!("2025-03-03"));
+
+        let keys: Vec<String> = storage.files().into_keys().collect();
+        assert_eq!(
+            keys,
+            [
+                "schedules/history/34-1/index.json",
+                "schedules/history/34-1/v1.json",
+                "schedules/history/34-1/v2.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn publishes_the_version_in_effect_and_lists_the_upcoming_one() {
+        let storage = MemoryStorage::new();
+
+        let mut data = schedule("10", None);
+        record(&storage, DIR, "34-1", &mut data, date("2025-03-01")).unwrap();
+
+        let mut data = schedule("20", Some("2025-04-01"));
+        record(&storage, DIR, "34-1", &mut data, date("2025-03-10")).unwrap();
+
+        assert_eq!(data["schedule"]["weekday"]["06"]["회촌"][0]["minute"], "10");
+        assert_eq!(data["effectiveFrom"], json!("2025-03-01"));
+        assert_eq!(data["upcoming"]["effectiveFrom"], json!("2025-04-01"));
+        assert_eq!(
+            data["upcoming"]["schedule"]["weekday"]["06"]["회촌"][0]["minute"],
+            "20"
+        );
+    }
+}
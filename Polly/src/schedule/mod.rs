@@ -1,147 +1,314 @@
 // src/schedule/mod.rs
 
-mod model;
+mod archive;
+mod crawler;
+pub mod day_type;
+mod headway;
+mod history;
+pub mod model;
+mod notes;
+mod route_info;
+pub mod service_time;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::fs;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use anyhow::{Context, Result};
-use percent_encoding::{NON_ALPHANUMERIC, percent_encode};
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
 use regex::Regex;
-use reqwest::{Client, header};
 use scraper::{Html, Selector};
 use serde_json::json;
-use tokio::time::sleep;
-
-use crate::config::{BASE_URL, DETAIL_URL};
-use crate::schedule::model::{ParsedSchedule, RouteMeta, TimeEntry};
-use crate::utils;
+use tracing::{debug, info, info_span, warn};
+
+use crate::config::SERVICE_DAY_START_MINUTES;
+use crate::config::model::PollyConfig;
+use crate::error::{ErrorKind, ParseError, describe};
+use crate::report::{FailurePolicy, RUN_REPORT_FILE, RunRecorder, RunStatus};
+use crate::schedule::day_type::DayTypeMapping;
+use crate::schedule::history::{HistoryUpdate, history_dir};
+use crate::schedule::model::{
+    CrawlOptions, ParsedSchedule, RouteMeta, ScheduleCrawler, SnapshotArchive, TimeEntry,
+    TripAttributes,
+};
+use crate::schedule::notes::parse_note;
+use crate::schedule::route_info::RouteStopIndex;
+use crate::schedule::service_time::ServiceTime;
+use crate::storage::{FsStorage, Storage, write_json};
+
+/// Published schedules, relative to the output directory
+pub const SCHEDULE_DIR: &str = "schedules";
+/// Archived pages of every crawler run, relative to the output directory
+pub const ARCHIVE_DIR: &str = "schedule_archive";
 
 /// Main entry point for the schedule crawler.
 ///
 /// This function orchestrates the entire crawling process:
 /// 1. Initializes an HTTP client with cookie storage to maintain session.
 /// 2. Fetches the main schedule page to get a list of all bus routes.
-/// 3. For each route, it fetches the detailed schedule.
+/// 3. Fetches the detailed schedules concurrently (see `CrawlOptions`), retrying
+///    transient failures and re-initializing the session when it expires.
 /// 4. Parses the HTML response for each detail page.
-/// 5. Merges the various schedules (e.g., weekday, weekend) for each route.
-/// 6. Saves the final, structured data as JSON files.
+/// 5. Merges the various schedules (e.g., weekday, saturday, holiday) for each route, and
+///    describes it from the TAGO stop sequences in `routes_dir/raw_routes/`.
+/// 6. Saves the final, structured data as JSON files, and lists routes that failed.
 ///
-pub async fn run(specific_route: Option<String>, output_dir: PathBuf) -> Result<()> {
-    let schedule_dir = output_dir.join("schedules");
-
-    utils::ensure_dir(&schedule_dir)?;
-
-    println!("\n============================================================");
-    println!("Starting Bus Schedule Crawler (Browser Mimic Mode)");
-    println!("============================================================\n");
-
-    // Initialize an HTTP client that mimics a web browser.
-    // Cookie store is enabled to automatically handle session cookies (JSESSIONID),
-    // which is crucial for making subsequent requests to the detail page.
-    let client = Client::builder()
-        .cookie_store(true)
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .timeout(Duration::from_secs(30))
-        .build()?;
-
-    // Fetch the main schedule page to acquire session cookies and the list of all routes.
-    println!("Fetching main page (Initializing Session)...");
-
-    let resp = client.get(BASE_URL).send().await?.text().await?;
-    let document = Html::parse_document(&resp);
-
-    // Extract basic route information and the target route IDs to crawl.
-    let (route_meta_map, targets) = extract_route_info(&document, specific_route.as_deref())?;
+/// Every fetched page is archived per run; with `from_archive` (a run directory of the
+/// archive), steps 1-3 are replaced by reading the archived pages without network access.
+///
+/// Raw day type labels are mapped to day types with `day_types_path` (a JSON object of
+/// label overrides), falling back to the built-in label heuristics.
+///
+pub async fn run(
+    config: &PollyConfig,
+    specific_route: Option<String>,
+    output_dir: PathBuf,
+    day_types_path: Option<PathBuf>,
+    from_archive: Option<PathBuf>,
+    routes_dir: PathBuf,
+    policy: FailurePolicy,
+) -> Result<RunStatus> {
+    let report = Arc::new(RunRecorder::new("schedule", policy));
+
+    let result = process(
+        config,
+        specific_route,
+        &output_dir,
+        day_types_path,
+        from_archive,
+        &routes_dir,
+        &report,
+    )
+    .await;
+
+    let status = report.finish(&output_dir.join(RUN_REPORT_FILE), &result)?;
+    result.map(|()| status)
+}
 
-    println!("✓ Found info for {} routes", route_meta_map.len());
-    println!("✓ Found {} route schedules to process", targets.len());
+async fn process(
+    config: &PollyConfig,
+    specific_route: Option<String>,
+    output_dir: &Path,
+    day_types_path: Option<PathBuf>,
+    from_archive: Option<PathBuf>,
+    routes_dir: &Path,
+    report: &Arc<RunRecorder>,
+) -> Result<()> {
+    let storage = FsStorage::new(output_dir);
 
-    let mut collected_schedules: Vec<ParsedSchedule> = Vec::new();
+    let day_types = match day_types_path {
+        Some(path) => DayTypeMapping::load(&path)?,
+        None => DayTypeMapping::default(),
+    };
 
-    // Iterate through each target route and fetch its detailed schedule.
-    for (i, route_id) in targets.iter().enumerate() {
-        print!(
-            "\r   [/{}/{}] Fetching {}... ",
-            i + 1,
-            targets.len(),
-            route_id
-        );
-        sleep(Duration::from_millis(300)).await; // Politeness delay.
-
-        // The website expects the route ID in the POST body to be percent-encoded UTF-8.
-        let encoded_val = percent_encode(route_id.as_bytes(), NON_ALPHANUMERIC).to_string();
-        let body_str = format!("no={}", encoded_val);
-
-        // Send a POST request to get the detailed schedule for the specific route_id.
-        // It's crucial to set the correct headers (Referer, Origin, Content-Type)
-        // to simulate a legitimate request originating from the website.
-        let detail_resp = match client
-            .post(DETAIL_URL)
-            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-            .header(header::REFERER, BASE_URL)
-            .header(header::ORIGIN, "http://its.wonju.go.kr")
-            .body(body_str)
-            .send()
-            .await
-        {
-            Ok(r) => r,
-            Err(_) => {
-                println!("✗ Failed (Network)");
-                continue;
+    // Timetable versions are dated by the day the pages were fetched.
+    let mut as_of = Local::now().date_naive();
+
+    // Pages come either from the website (archived as they are fetched) or from the
+    // archive of a previous run, in which case the network is never touched.
+    let phase = report.phase("fetch");
+    let (route_meta_map, targets, pages) = match from_archive {
+        Some(dir) => {
+            info!("[Re-parsing archived pages from {:?}]", dir);
+
+            let archive_storage = FsStorage::new(&dir);
+            let archive = SnapshotArchive::open(&archive_storage, "")?;
+            as_of = DateTime::parse_from_rfc3339(&archive.manifest.fetched_at)
+                .map_or(as_of, |t| t.date_naive());
+            let (route_meta_map, targets) = parse_main_page(
+                &archive.read_main(&archive_storage)?,
+                specific_route.as_deref(),
+            )?;
+
+            let mut pages = Vec::new();
+            for route_id in &targets {
+                match archive.read_detail(&archive_storage, route_id) {
+                    Some(Ok(html)) => pages.push((route_id.clone(), (html, dir.clone()))),
+                    Some(Err(e)) => {
+                        report.fail(route_id, "fetch", ErrorKind::Io, &describe(&e), None)?
+                    }
+                    None => {
+                        report.fail(route_id, "fetch", ErrorKind::Io, "Not in archive", None)?
+                    }
+                }
             }
-        };
 
-        if !detail_resp.status().is_success() {
-            println!("✗ Failed (Status: {})", detail_resp.status());
-            continue;
+            (route_meta_map, targets, pages)
         }
+        None => {
+            let options = CrawlOptions::from_config(config);
+            crawl(specific_route.as_deref(), &storage, options, report).await?
+        }
+    };
+    drop(phase);
+    report.add("routesTargeted", targets.len());
 
-        let detail_html = detail_resp.text().await?;
+    let phase = report.phase("parse");
+    let mut collected_schedules: Vec<ParsedSchedule> = Vec::new();
 
+    // Parse the detail pages in route order.
+    for (route_id, (detail_html, archived_at)) in pages {
         // The route number is the part of the route_id before any parentheses.
-        let route_number = route_id.split('(').next().unwrap_or(route_id).to_string();
+        let route_number = route_id.split('(').next().unwrap_or(&route_id).to_string();
         let meta = route_meta_map.get(&route_number);
 
         // Parse the returned HTML to extract the schedule.
-        match parse_detail_schedule(&detail_html, route_id, meta) {
+        let _span = info_span!("route", route = %route_id).entered();
+        match parse_detail_page(&detail_html, &route_id, meta, &day_types) {
             Ok(parsed) => {
                 let count: usize = parsed.times_by_direction.values().map(|v| v.len()).sum();
                 if count > 0 {
-                    println!("✓ ({} times)", count);
                     collected_schedules.push(parsed);
                 } else {
-                    // If parsing yields no times, save the HTML for debugging.
-                    println!("Warning: 0 times. (HTML Check Saved)");
-                    fs::write(format!("debug_empty_{}.html", i), &detail_html).ok();
+                    // The page is kept in the archive for debugging.
+                    let cause = format!("0 times (HTML in {:?})", archived_at);
+                    report.fail(&route_id, "parse", ErrorKind::Parse, &cause, None)?;
                 }
             }
             Err(e) => {
-                println!("✗ Error: {}", e);
+                let cause = format!("{} (HTML in {:?})", e, archived_at);
+                report.fail(&route_id, "parse", ErrorKind::Parse, &cause, None)?;
             }
         }
     }
+    drop(phase);
 
     // Merge the collected schedules and save them to JSON files.
-    println!("\nOrganizing and saving schedules...");
+    let _phase = report.phase("save");
+    info!("Organizing and saving schedules...");
+
+    let collected_schedules_len = collected_schedules.len();
+    report.add("routesParsed", collected_schedules_len);
+    let mut merged_routes = merge_schedules(collected_schedules, &route_meta_map);
+
+    // Describe each route from its TAGO stop sequences (featuredStops, routeDetails).
+    let raw_dir = routes_dir.join("raw_routes");
+    match RouteStopIndex::load(&raw_dir) {
+        Ok(index) => {
+            for (route_number, data) in merged_routes.iter_mut() {
+                if !index.describe(data) {
+                    warn!(
+                        route = %route_number,
+                        "{}: no TAGO route for featured stops", route_number
+                    );
+                }
+            }
+        }
+        Err(e) => warn!("Skipping featured stops: {:?}: {}", raw_dir, e),
+    }
 
-    let merged_routes = merge_schedules(collected_schedules, &route_meta_map);
+    // Record timetable versions; the published file holds the version in effect.
+    let mut changed = Vec::new();
+    for (route_number, mut data) in merged_routes {
+        let _span = info_span!("route", route = %route_number).entered();
 
-    for (route_number, data) in merged_routes {
-        save_route_schedule(&schedule_dir, &route_number, &data)?;
+        let history = history_dir(SCHEDULE_DIR, &schedule_file_name(&route_number));
+        match history::record(&storage, &history, &route_number, &mut data, as_of)? {
+            HistoryUpdate::Changed { previous, version } => {
+                changed.push(format!("{} (v{} -> v{})", route_number, previous, version));
+            }
+            HistoryUpdate::First | HistoryUpdate::Unchanged => {}
+        }
+        // Frequencies are derived from the published timetables.
+        headway::describe(&mut data);
+        if let Some(upcoming) = data.get_mut("upcoming") {
+            headway::describe(upcoming);
+        }
+
+        if let Some(date) = data["upcoming"]["effectiveFrom"].as_str() {
+            info!("↻ {}: new timetable from {} (upcoming)", route_number, date);
+            report.add("routesUpcoming", 1);
+        }
+
+        save_route_schedule(&storage, &route_number, &data)?;
+        report.add("routesSaved", 1);
+    }
+
+    info!(
+        "✓ Parsed {} of {} route schedules.",
+        collected_schedules_len,
+        targets.len()
+    );
+    report.add("routesChanged", changed.len());
+    if !changed.is_empty() {
+        info!(
+            "↻ Timetable changed for {} routes: {}",
+            changed.len(),
+            changed.join(", ")
+        );
     }
 
     Ok(())
 }
 
+/// Route metadata, target route IDs, and (detail HTML, archive path) of the routes
+/// that were fetched
+type CrawlResult = (
+    HashMap<String, RouteMeta>,
+    Vec<String>,
+    Vec<(String, (String, PathBuf))>,
+);
+
+/// Fetches the main page and all detail pages, archiving every page under
+/// `schedule_archive/<run>/` in `storage`.
+async fn crawl(
+    specific_route: Option<&str>,
+    storage: &FsStorage,
+    options: CrawlOptions,
+    report: &Arc<RunRecorder>,
+) -> Result<CrawlResult> {
+    info!("Starting Bus Schedule Crawler (Browser Mimic Mode)");
+
+    let crawler = ScheduleCrawler::new(options, Arc::clone(report))?;
+    let mut archive = SnapshotArchive::create(ARCHIVE_DIR);
+
+    // Fetch the main schedule page to acquire session cookies and the list of all routes.
+    info!("Fetching main page (Initializing Session)...");
+
+    let resp = crawler.fetch_main_page().await?;
+    archive.save_main(storage, &resp)?;
+
+    // Extract basic route information and the target route IDs to crawl.
+    let (route_meta_map, targets) = parse_main_page(&resp, specific_route)?;
+
+    info!("✓ Found info for {} routes", route_meta_map.len());
+    info!(
+        "✓ Found {} route schedules to process ({} concurrent, {} ms apart)",
+        targets.len(),
+        crawler.options.concurrency,
+        crawler.options.request_interval.as_millis()
+    );
+
+    // Pages are archived as they arrive, and the manifest is written even when the crawl
+    // stops early, so whatever was fetched can be re-parsed with --from-archive.
+    let mut pages = Vec::new();
+    let fetched = crawler
+        .fetch_all_details(&targets, |route_id, page| {
+            let key = archive.save_detail(storage, &route_id, &page)?;
+            pages.push((route_id, (page.html, storage.root().join(key))));
+            Ok(())
+        })
+        .await;
+
+    archive.finish(storage)?;
+    info!(
+        "✓ Archived {} pages to {:?}",
+        archive.manifest.pages.len(),
+        storage.root().join(&archive.dir)
+    );
+    fetched?;
+
+    Ok((route_meta_map, targets, pages))
+}
+
 /// Parses the main schedule page to extract a list of all available routes.
-/// It creates a map of route metadata and a list of `route_id`s used for fetching details.
-fn extract_route_info(
-    document: &Html,
+/// It creates a map of route metadata (by route number) and a list of `route_id`s used
+/// for fetching details, keeping only the IDs that start with `filter`.
+pub fn parse_main_page(
+    html: &str,
     filter: Option<&str>,
 ) -> Result<(HashMap<String, RouteMeta>, Vec<String>)> {
+    let document = Html::parse_document(html);
     let mut route_meta_map = HashMap::new();
     let mut targets = Vec::new();
 
@@ -158,35 +325,35 @@ fn extract_route_info(
             let route_element = cells[0];
 
             // The route_id required for the POST request is in an `onclick` attribute.
-            if let Some(onclick) = route_element.value().attr("onclick") {
-                if let Some(caps) = onclick_re.captures(onclick) {
-                    let route_id = caps.get(1).unwrap().as_str().to_string();
+            if let Some(onclick) = route_element.value().attr("onclick")
+                && let Some(caps) = onclick_re.captures(onclick)
+            {
+                let route_id = caps.get(1).unwrap().as_str().to_string();
 
-                    // If a specific route is requested, filter out all others.
-                    if let Some(f) = filter {
-                        if !route_id.starts_with(f) {
-                            continue;
-                        }
-                    }
+                // If a specific route is requested, filter out all others.
+                if let Some(f) = filter
+                    && !route_id.starts_with(f)
+                {
+                    continue;
+                }
 
-                    targets.push(route_id.clone());
+                targets.push(route_id.clone());
 
-                    let route_no = route_id.split('(').next().unwrap_or(&route_id).to_string();
-                    let origin = cells[1].text().collect::<String>().trim().to_string();
-                    let dest = cells[2].text().collect::<String>().trim().to_string();
+                let route_no = route_id.split('(').next().unwrap_or(&route_id).to_string();
+                let origin = cells[1].text().collect::<String>().trim().to_string();
+                let dest = cells[2].text().collect::<String>().trim().to_string();
 
-                    // Collect all unique termini for this route number.
-                    let entry = temp_directions.entry(route_no.clone()).or_default();
-                    entry.insert(origin.clone());
-                    entry.insert(dest.clone());
+                // Collect all unique termini for this route number.
+                let entry = temp_directions.entry(route_no.clone()).or_default();
+                entry.insert(origin.clone());
+                entry.insert(dest.clone());
 
-                    // Store metadata for the route.
-                    route_meta_map.entry(route_no).or_insert(RouteMeta {
-                        origin,
-                        destination: dest,
-                        directions: Vec::new(),
-                    });
-                }
+                // Store metadata for the route.
+                route_meta_map.entry(route_no).or_insert(RouteMeta {
+                    origin,
+                    destination: dest,
+                    directions: Vec::new(),
+                });
             }
         }
     }
@@ -203,50 +370,35 @@ fn extract_route_info(
     Ok((route_meta_map, targets))
 }
 
-/// Normalizes Korean day type strings into a standard English identifier.
-fn normalize_day_type(raw: &str) -> String {
-    let lower = raw.to_lowercase();
-    if lower.contains("평일") || lower.contains("주중") {
-        // Weekday
-        "weekday".to_string()
-    } else if lower.contains("주말") // Weekend
-        || lower.contains("휴일") // Holiday
-        || lower.contains("토") // Saturday
-        || lower.contains("일") // Sunday
-        || lower.contains("방학") // Vacation
-        || lower.contains("공휴")
-    // Public Holiday
-    {
-        "weekend".to_string()
-    } else {
-        "general".to_string()
-    }
-}
-
 /// Parses the HTML of a schedule detail page for a single route.
-fn parse_detail_schedule(
+///
+/// `meta` (from `parse_main_page`) supplies the directions when the page has none.
+pub fn parse_detail_page(
     html: &str,
     route_id: &str,
     meta: Option<&RouteMeta>,
-) -> Result<ParsedSchedule> {
+    day_types: &DayTypeMapping,
+) -> Result<ParsedSchedule, ParseError> {
     let document = Html::parse_document(html);
 
     // Extract the route number and raw day type from the route_id string (e.g., "34-1(평일)").
-    let route_match_re = Regex::new(r"^(\S+?)(.*)?$").unwrap();
+    // The label keeps everything inside the parentheses, so "34-1(토·공휴일)" yields "토·공휴일".
+    let route_match_re = Regex::new(r"^([^(\s]+)\s*(?:\((.*)\))?").unwrap();
     let (route_number, raw_day_type) = if let Some(caps) = route_match_re.captures(route_id) {
         (
             caps.get(1).map_or("", |m| m.as_str()).to_string(),
             caps.get(2)
-                .map_or("general", |m| {
-                    m.as_str().trim_matches(|c| c == '(' || c == ')')
-                })
+                .map(|m| m.as_str().trim())
+                .filter(|s| !s.is_empty())
+                .unwrap_or("general")
                 .to_string(),
         )
     } else {
         (route_id.to_string(), "general".to_string())
     };
 
-    let day_type = normalize_day_type(&raw_day_type);
+    let day_type = day_types.resolve(&raw_day_type);
+    let effective_from = parse_effective_date(&document);
 
     let table_selector = Selector::parse("table").unwrap();
     let th_selector = Selector::parse("th").unwrap();
@@ -269,13 +421,15 @@ fn parse_detail_schedule(
         target_table = document.select(&table_selector).next();
     }
 
-    let table = target_table.context("No schedule table found in the HTML")?;
+    let table = target_table
+        .ok_or_else(|| ParseError::Content("No schedule table found in the HTML".to_string()))?;
 
     let mut col_map: HashMap<usize, String> = HashMap::new(); // Maps column index to direction name.
     let mut directions: Vec<String> = Vec::new();
     let mut note_col_idx = None;
 
     let tr_selector = Selector::parse("tr").unwrap();
+    let hour_header_re = Regex::new(r"^\d+시$").unwrap();
     let header_rows: Vec<_> = table.select(&tr_selector).collect();
 
     // Parse table headers to identify directions.
@@ -299,7 +453,7 @@ fn parse_detail_schedule(
             let clean_text = text.trim_end_matches('발').to_string();
             if !clean_text.is_empty()
                 && !["운행순번", "시", "분", "", "구분"].contains(&clean_text.as_str())
-                && !Regex::new(r"^\d+시$").unwrap().is_match(&clean_text)
+                && !hour_header_re.is_match(&clean_text)
             {
                 if !directions.contains(&clean_text) {
                     directions.push(clean_text.clone());
@@ -356,11 +510,18 @@ fn parse_detail_schedule(
             if let Some(dir_name) = col_map.get(&col_idx) {
                 let text = cell.text().collect::<String>().trim().to_string();
                 if let Some(caps) = time_re.captures(&text) {
-                    let clean_time = caps.get(1).unwrap().as_str().to_string();
+                    // Validate the time and move after-midnight times onto this service day.
+                    let time = match ServiceTime::parse(caps.get(1).unwrap().as_str()) {
+                        Ok(t) => t,
+                        Err(e) => {
+                            warn!("{} ({}): {}", route_number, dir_name, e);
+                            continue;
+                        }
+                    };
 
                     if let Some(list) = times_by_direction.get_mut(dir_name) {
                         list.push(TimeEntry {
-                            time: clean_time,
+                            time,
                             note: note.clone(),
                         });
                     }
@@ -369,16 +530,48 @@ fn parse_detail_schedule(
         }
     }
 
+    // Keep departures in service-day order (e.g., 23:50 before 24:20).
+    for list in times_by_direction.values_mut() {
+        list.sort_by_key(|e| e.time);
+    }
+
     Ok(ParsedSchedule {
         route_number,
         day_type,
+        raw_day_type,
         directions,
         times_by_direction,
+        effective_from,
     })
 }
 
+/// Finds the effective date (시행일) stated on a detail page, such as
+/// "시행일: 2026-03-02" or "2026. 3. 2.부터 시행".
+fn parse_effective_date(document: &Html) -> Option<NaiveDate> {
+    let text = document.root_element().text().collect::<Vec<_>>().join(" ");
+
+    let date = r"(\d{4})\s*[.\-/년]\s*(\d{1,2})\s*[.\-/월]\s*(\d{1,2})";
+    let effective_re = Regex::new(&format!(
+        r"시행일?\s*[:：]?\s*{date}|{date}\s*[.일]?\s*(?:부터\s*)?시행"
+    ))
+    .unwrap();
+
+    let caps = effective_re.captures(&text)?;
+    // Either the "시행일: <date>" groups (1-3) or the "<date> 시행" groups (4-6) matched.
+    let part = |i: usize| {
+        caps.get(i)
+            .or_else(|| caps.get(i + 3))
+            .and_then(|m| m.as_str().parse::<u32>().ok())
+    };
+
+    NaiveDate::from_ymd_opt(part(1)? as i32, part(2)?, part(3)?)
+}
+
 /// Merges multiple `ParsedSchedule` structs into a single, comprehensive JSON object per route.
-/// For example, it combines weekday and weekend schedules for the same bus route.
+/// For example, it combines weekday, saturday and holiday schedules for the same bus route.
+///
+/// When two raw day type labels of a route map to the same day type, the first timetable
+/// is kept and a warning is printed; adjust the day type mapping to keep both.
 fn merge_schedules(
     schedules: Vec<ParsedSchedule>,
     route_meta_map: &HashMap<String, RouteMeta>,
@@ -386,10 +579,23 @@ fn merge_schedules(
     let mut merged_routes: HashMap<String, serde_json::Value> = HashMap::new();
     let mut route_note_maps: HashMap<String, HashMap<String, String>> = HashMap::new();
     let mut route_note_counters: HashMap<String, usize> = HashMap::new();
+    let mut note_attributes: HashMap<String, TripAttributes> = HashMap::new();
 
     for schedule in schedules {
         let r_no = schedule.route_number.clone();
 
+        // Never let a second raw label overwrite a day type that is already filled.
+        if let Some(existing) = merged_routes
+            .get(&r_no)
+            .and_then(|r| r["dayTypeLabels"][&schedule.day_type].as_str())
+        {
+            warn!(
+                "{}: '{}' and '{}' both map to '{}'; keeping '{}'.",
+                r_no, existing, schedule.raw_day_type, schedule.day_type, existing
+            );
+            continue;
+        }
+
         // If this is the first time seeing this route, create the base JSON structure.
         if !merged_routes.contains_key(&r_no) {
             let meta = route_meta_map.get(&r_no);
@@ -410,6 +616,8 @@ fn merge_schedules(
                 "directions": dirs,
                 "routeDetails": [],
                 "featuredStops": { "general": [] },
+                "serviceDayStart": ServiceTime::from_minutes(SERVICE_DAY_START_MINUTES).to_string(),
+                "dayTypeLabels": {},
                 "schedule": {},
                 "notes": {}
             });
@@ -422,14 +630,37 @@ fn merge_schedules(
         let note_map = route_note_maps.get_mut(&r_no).unwrap();
         let note_counter = route_note_counters.get_mut(&r_no).unwrap();
 
-        // Create a schedule object for the current day type (e.g., "weekday").
-        let day_type_schedule = json!({});
-        route_json["schedule"][&schedule.day_type] = day_type_schedule;
+        // Create a schedule object for the current day type (e.g., "weekday"),
+        // remembering the raw label it came from.
+        route_json["dayTypeLabels"][&schedule.day_type] = json!(schedule.raw_day_type);
 
-        for (direction, entries) in schedule.times_by_direction {
+        // Pages of one route normally share the effective date; keep the latest one.
+        if let Some(date) = schedule.effective_from
+            && route_json["effectiveFrom"]
+                .as_str()
+                .is_none_or(|d| d < date.format("%Y-%m-%d").to_string().as_str())
+        {
+            route_json["effectiveFrom"] = json!(date);
+        }
+        route_json["schedule"][&schedule.day_type] = json!({});
+
+        // Directions in page order, so note IDs are the same on every run.
+        let mut times_by_direction = schedule.times_by_direction;
+        for direction in &schedule.directions {
+            let Some(entries) = times_by_direction.remove(direction) else {
+                continue;
+            };
             let mut times_by_hour: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
 
             for entry in entries {
+                // Interpret the note once per distinct text (e.g., "연세대 경유" -> via).
+                let attributes = entry.note.as_ref().map(|text| {
+                    note_attributes
+                        .entry(text.clone())
+                        .or_insert_with(|| parse_note(text))
+                        .clone()
+                });
+
                 // Handle notes: assign a unique ID to each note text.
                 let note_id = if let Some(note_text) = entry.note {
                     if !note_map.contains_key(&note_text) {
@@ -445,19 +676,22 @@ fn merge_schedules(
                     None
                 };
 
-                // Group times by the hour.
-                let parts: Vec<&str> = entry.time.split(':').collect();
-                if parts.len() == 2 {
-                    let hour = format!("{:0>2}", parts[0]);
-                    let minute = format!("{:0>2}", parts[1]);
-
-                    let mut minute_obj = json!({ "minute": minute });
-                    if let Some(nid) = note_id {
-                        minute_obj["noteId"] = json!(nid);
-                    }
+                // Group times by the service hour ("24" and later for after-midnight trips).
+                let hour = format!("{:02}", entry.time.hour());
+                let minute = format!("{:02}", entry.time.minute());
 
-                    times_by_hour.entry(hour).or_default().push(minute_obj);
+                let mut minute_obj = json!({ "minute": minute });
+                if let Some(nid) = note_id {
+                    minute_obj["noteId"] = json!(nid);
                 }
+                if let Some(attrs) = attributes
+                    && let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(attrs)
+                {
+                    // Structured attributes sit next to the raw note reference.
+                    minute_obj.as_object_mut().unwrap().extend(fields);
+                }
+
+                times_by_hour.entry(hour).or_default().push(minute_obj);
             }
 
             // Add the hour-grouped times to the final JSON structure.
@@ -465,7 +699,7 @@ fn merge_schedules(
                 if route_json["schedule"][&schedule.day_type][&hour].is_null() {
                     route_json["schedule"][&schedule.day_type][&hour] = json!({});
                 }
-                route_json["schedule"][&schedule.day_type][&hour][&direction] = json!(minutes);
+                route_json["schedule"][&schedule.day_type][&hour][direction] = json!(minutes);
             }
         }
     }
@@ -473,24 +707,81 @@ fn merge_schedules(
     merged_routes
 }
 
-/// Saves the final merged schedule data for a route to a JSON file.
+/// Saves the final merged schedule data for a route to `schedules/<route>.json`.
 fn save_route_schedule(
-    base_dir: &PathBuf,
+    storage: &dyn Storage,
     route_number: &str,
     data: &serde_json::Value,
 ) -> Result<()> {
+    let file_name = schedule_file_name(route_number);
+    write_json(
+        storage,
+        &format!("{}/{}", SCHEDULE_DIR, file_name),
+        data,
+        false,
+    )?;
+
+    debug!("✓ Saved {} to {:?}", route_number, file_name);
+    Ok(())
+}
+
+/// Returns the schedule filename of a route number (e.g., "34-1" -> "34-1.json").
+fn schedule_file_name(route_number: &str) -> String {
     // Sanitize the route number to create a valid filename.
     let safe_name = route_number.replace(|c: char| !c.is_alphanumeric() && c != '-', "_");
-    let filename = format!("{}.json", safe_name);
-    let path = base_dir.join(filename);
+    format!("{}.json", safe_name)
+}
 
-    let json_str = serde_json::to_string_pretty(data)?;
-    fs::write(&path, json_str)?;
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    // Each direction has a trip with a note of its own.
+    const DETAIL: &str = "<html><table>\
+        <tr><th>운행순번</th><th>장양리발</th><th>회촌발</th><th>비고</th></tr>\
+        <tr><td>1</td><td></td><td>06:10</td><td>연세대 경유</td></tr>\
+        <tr><td>2</td><td>06:40</td><td></td><td>저상버스</td></tr>\
+        <tr><td>3</td><td>07:00</td><td>07:30</td><td></td></tr>\
+        </table></html>";
+
+    fn merge_page() -> serde_json::Value {
+        let parsed =
+            parse_detail_page(DETAIL, "34-1(평일)", None, &DayTypeMapping::default()).unwrap();
+        merge_schedules(vec![parsed], &HashMap::new())
+            .remove("34-1")
+            .unwrap()
+    }
 
-    println!(
-        "   ✓ Saved {} to {:?}",
-        route_number,
-        path.file_name().unwrap()
-    );
-    Ok(())
+    #[test]
+    fn merging_the_same_page_assigns_the_same_note_ids() {
+        let first = merge_page();
+        assert_eq!(first["notes"]["1"], "저상버스");
+        assert_eq!(first["notes"]["2"], "연세대 경유");
+
+        for _ in 0..20 {
+            let again = merge_page();
+            assert_eq!(again["notes"], first["notes"]);
+            assert_eq!(again["schedule"], first["schedule"]);
+        }
+    }
+
+    #[test]
+    fn merging_the_same_page_records_no_new_version() {
+        let storage = MemoryStorage::new();
+        let dir = history_dir(SCHEDULE_DIR, &schedule_file_name("34-1"));
+        let as_of = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        let mut data = merge_page();
+        let update = history::record(&storage, &dir, "34-1", &mut data, as_of).unwrap();
+        assert!(matches!(update, HistoryUpdate::First));
+
+        for _ in 0..20 {
+            let mut data = merge_page();
+            let update = history::record(&storage, &dir, "34-1", &mut data, as_of).unwrap();
+            assert!(matches!(update, HistoryUpdate::Unchanged));
+        }
+    }
 }
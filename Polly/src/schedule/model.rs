@@ -1,6 +1,17 @@
-// src/schedule/mod.rs
+// src/schedule/model.rs
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::report::RunRecorder;
+use crate::schedule::service_time::ServiceTime;
 
 /// Holds metadata for a bus route, such as its start and end points
 /// and a list of all unique directions (termini) it serves.
@@ -14,7 +25,7 @@ pub struct RouteMeta {
 /// Represents a single departure time entry in the schedule.
 #[derive(Debug)]
 pub struct TimeEntry {
-    pub time: String,
+    pub time: ServiceTime,
     pub note: Option<String>,
 }
 
@@ -22,7 +33,177 @@ pub struct TimeEntry {
 #[derive(Debug)]
 pub struct ParsedSchedule {
     pub route_number: String,
-    pub day_type: String,
+    pub day_type: String,     // e.g., "saturday"
+    pub raw_day_type: String, // e.g., "토요일"
     pub directions: Vec<String>,
     pub times_by_direction: HashMap<String, Vec<TimeEntry>>,
+    // Effective date (시행일) stated on the page, if any
+    pub effective_from: Option<NaiveDate>,
+}
+
+/// Days a trip runs on, from its note (e.g., "방학 중 미운행" -> school days only)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatesOn {
+    SchoolDays,
+    Vacation,
+}
+
+/// Structured trip attributes interpreted from a schedule note (비고).
+/// The raw text stays in `notes`; empty attributes are omitted from the output.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TripAttributes {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub via: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub short_turn: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminates_at: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub low_floor: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operates_on: Option<OperatesOn>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub last_bus: bool,
+}
+
+// ============================================================================
+// Headways (Saved to schedules/ under "headways")
+// ============================================================================
+
+/// Service frequency of one direction on one day type
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadwaySummary {
+    pub first_departure: String,
+    pub last_departure: String,
+    pub trips: usize,
+    // Service hour ("07", "24") -> Departures
+    pub trips_per_hour: BTreeMap<String, usize>,
+    pub bands: Vec<HeadwayBand>,
+    pub regular_periods: Vec<RegularPeriod>,
+}
+
+/// Headways (minutes) within a time band, e.g., the morning peak
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadwayBand {
+    pub band: String,
+    pub from: String,
+    pub to: String,
+    pub trips: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_headway: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_headway: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_headway: Option<u32>,
+}
+
+/// A run of departures at a near-constant interval ("every 15 min 07:00–09:00")
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegularPeriod {
+    pub from: String, // First departure of the run
+    pub to: String,   // Last departure of the run
+    pub headway_mins: u32,
+    // All headways of the run are equal
+    pub exact: bool,
+    pub trips: usize,
+}
+
+// ============================================================================
+// Crawler
+// ============================================================================
+
+/// Politeness and retry settings of the schedule crawler
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    pub concurrency: usize,
+    pub request_interval: Duration,
+    pub max_retries: u32,
+    pub retry_base: Duration,
+    pub user_agent: String,
+    pub main_url: String,
+    pub detail_url: String,
+}
+
+/// A fetched detail page
+#[derive(Debug)]
+pub struct FetchedPage {
+    pub html: String,
+    pub fetched_at: DateTime<Local>,
+}
+
+/// A route whose detail page could not be fetched after all retries
+#[derive(Debug)]
+pub struct FetchFailure {
+    pub route_id: String,
+    pub reason: String,
+    pub attempts: u32,
+}
+
+/// Fetches schedule pages over one cookie session shared by all workers.
+pub struct ScheduleCrawler {
+    pub client: Client,
+    pub options: CrawlOptions,
+    // Earliest instant the next request may be sent
+    pub next_request_at: Mutex<Instant>,
+    // Incremented every time the session is re-primed
+    pub session_generation: Mutex<u64>,
+    // Statistics of the current run (run_report.json)
+    pub report: Arc<RunRecorder>,
+}
+
+// ============================================================================
+// Snapshot Archive (Saved to schedule_archive/<run>/)
+// ============================================================================
+
+/// Index of the pages archived in one crawler run
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveManifest {
+    pub fetched_at: String,
+    pub main_page: String,
+    pub pages: Vec<ArchivedPage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedPage {
+    pub route_id: String,
+    pub file: String,
+    pub fetched_at: String,
+}
+
+/// HTML pages of one crawler run, saved for offline re-parsing
+pub struct SnapshotArchive {
+    // Storage key of the run directory (e.g., "schedule_archive/20250301-060000")
+    pub dir: String,
+    pub manifest: ArchiveManifest,
+}
+
+// ============================================================================
+// Timetable History (Saved to schedules/history/<route>/)
+// ============================================================================
+
+/// Versions of a route's timetable, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleHistory {
+    pub route_id: String,
+    pub versions: Vec<ScheduleVersion>,
+}
+
+/// A distinct timetable and the dates it was seen on the website
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleVersion {
+    pub version: u32,
+    // 시행일 from the page, or the date the version was first seen
+    pub effective_from: NaiveDate,
+    pub first_seen: NaiveDate,
+    pub last_seen: NaiveDate,
+    pub file: String,
 }
@@ -0,0 +1,114 @@
+// src/schedule/notes.rs
+
+use regex::Regex;
+
+use crate::schedule::model::{OperatesOn, TripAttributes};
+
+// Words that describe a trip rather than name a stop (e.g., "막차" in "막차, 연세대 경유")
+const NON_STOP_WORDS: &[&str] = &[
+    "막차", "첫차", "저상", "운행", "방학", "학기", "회차", "단축",
+];
+
+/// Interprets common schedule note (비고) patterns as trip attributes.
+///
+/// - "연세대 경유", "(터미널, 원주역 경유)" -> `via`
+/// - "문막까지 운행", "매지리 회차", "단축운행" -> `shortTurn` (and `terminatesAt`)
+/// - "저상버스" -> `lowFloor`
+/// - "방학 중 미운행", "학기중 운행" / "방학 중 운행" -> `operatesOn`
+/// - "막차" -> `lastBus`
+///
+pub fn parse_note(note: &str) -> TripAttributes {
+    let mut attrs = TripAttributes::default();
+
+    // A list of stop names directly followed by "경유"
+    let via_re = Regex::new(r"((?:[^\s,·/()\[\]]+\s*[,·/]\s*)*[^\s,·/()\[\]]+)\s*경유").unwrap();
+    for caps in via_re.captures_iter(note) {
+        for stop in caps[1].split([',', '·', '/']) {
+            let stop = stop.trim();
+            if !stop.is_empty()
+                && !NON_STOP_WORDS.iter().any(|w| stop.contains(w))
+                && !attrs.via.iter().any(|s| s == stop)
+            {
+                attrs.via.push(stop.to_string());
+            }
+        }
+    }
+
+    // "N회차" after a number is the Nth run (e.g., "3회차"), not a turn-back point.
+    let terminates_re = Regex::new(
+        r"([^\s,·/()\[\]]+?)\s*까지(?:만)?\s*운행|([^\s,·/()\[\]]*?[^\s\d,·/()\[\]])\s*회차",
+    )
+    .unwrap();
+    if let Some(stop) = terminates_re
+        .captures(note)
+        .and_then(|caps| caps.get(1).or(caps.get(2)))
+    {
+        attrs.short_turn = true;
+        attrs.terminates_at = Some(stop.as_str().to_string());
+    }
+    if note.contains("단축") {
+        attrs.short_turn = true;
+    }
+
+    attrs.low_floor = note.contains("저상");
+    attrs.last_bus = note.contains("막차");
+
+    // "방학 중 미운행" runs on school days only, "학기 중 미운행" during vacations only.
+    let not_running = note.contains("미운행") || note.contains("제외");
+    let school_words = ["학기", "수업일", "등교", "개학"];
+    attrs.operates_on = if note.contains("방학") {
+        Some(if not_running {
+            OperatesOn::SchoolDays
+        } else {
+            OperatesOn::Vacation
+        })
+    } else if school_words.iter().any(|w| note.contains(w)) {
+        Some(if not_running {
+            OperatesOn::Vacation
+        } else {
+            OperatesOn::SchoolDays
+        })
+    } else {
+        None
+    };
+
+    attrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turn_back_point_is_a_short_turn() {
+        for note in ["매지리 회차", "매지리회차", "(매지리 회차)"] {
+            let attrs = parse_note(note);
+            assert!(attrs.short_turn, "{}", note);
+            assert_eq!(attrs.terminates_at.as_deref(), Some("매지리"), "{}", note);
+        }
+
+        let attrs = parse_note("문막까지 운행");
+        assert!(attrs.short_turn);
+        assert_eq!(attrs.terminates_at.as_deref(), Some("문막"));
+    }
+
+    #[test]
+    fn numbered_run_is_not_a_short_turn() {
+        for note in ["3회차", "12 회차", "2회차 저상버스"] {
+            let attrs = parse_note(note);
+            assert!(!attrs.short_turn, "{}", note);
+            assert_eq!(attrs.terminates_at, None, "{}", note);
+        }
+        assert!(parse_note("2회차 저상버스").low_floor);
+    }
+
+    #[test]
+    fn numbered_run_before_a_turn_back_point() {
+        let attrs = parse_note("3회차, 매지리 회차");
+        assert!(attrs.short_turn);
+        assert_eq!(attrs.terminates_at.as_deref(), Some("매지리"));
+
+        let attrs = parse_note("2회차 문막까지 운행");
+        assert_eq!(attrs.terminates_at.as_deref(), Some("문막"));
+    }
+}
@@ -0,0 +1,249 @@
+// src/schedule/route_info.rs
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::config::FEATURED_STOPS_PER_DIRECTION;
+use crate::registry::normalize_route_no;
+use crate::route::model::{RawRouteFile, RawStop};
+
+/// Stop sequences of the TAGO routes, used to describe the scraped schedules
+pub struct RouteStopIndex {
+    // Normalized route number -> Variants (one per TAGO route ID), sorted by route ID
+    routes: HashMap<String, Vec<RawRouteFile>>,
+    // Stop name -> Number of route numbers serving it
+    popularity: HashMap<String, usize>,
+}
+
+impl RouteStopIndex {
+    /// Loads every raw route from `raw_routes/` (written by the route processor).
+    pub fn load(raw_dir: &Path) -> Result<Self> {
+        let mut routes: HashMap<String, Vec<RawRouteFile>> = HashMap::new();
+
+        for entry in fs::read_dir(raw_dir)?.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let raw: RawRouteFile = match fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|c| Ok(serde_json::from_str(&c)?))
+            {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Skipping {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            routes
+                .entry(normalize_route_no(&raw.route_no))
+                .or_default()
+                .push(raw);
+        }
+
+        let mut served_by: HashMap<String, HashSet<&str>> = HashMap::new();
+        for (key, variants) in &routes {
+            for stop in variants.iter().flat_map(|v| &v.stops) {
+                served_by
+                    .entry(stop.node_nm.clone())
+                    .or_default()
+                    .insert(key.as_str());
+            }
+        }
+        let popularity = served_by
+            .into_iter()
+            .map(|(name, keys)| (name, keys.len()))
+            .collect();
+
+        for variants in routes.values_mut() {
+            variants.sort_by(|a, b| a.route_id.cmp(&b.route_id));
+            for v in variants.iter_mut() {
+                v.stops.sort_by_key(|s| s.node_ord);
+            }
+        }
+
+        Ok(Self { routes, popularity })
+    }
+
+    /// Fills `featuredStops` (key timing stops per schedule direction) and, for routes
+    /// with several TAGO route IDs, `routeDetails` (one description per variant).
+    ///
+    /// Returns `false` when no TAGO route matches the schedule's route number.
+    pub fn describe(&self, route_json: &mut Value) -> bool {
+        let route_no = route_json["routeId"].as_str().unwrap_or_default();
+        let Some(variants) = self.routes.get(&normalize_route_no(route_no)) else {
+            return false;
+        };
+        let Some(main) = variants.iter().max_by_key(|v| v.stops.len()) else {
+            return false;
+        };
+
+        let directions: Vec<String> = route_json["directions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.as_str().map(str::to_string))
+            .collect();
+
+        let mut featured: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (direction, segment) in match_directions(&directions, &split_directions(&main.stops)) {
+            featured.insert(direction, self.key_stops(segment));
+        }
+        if !featured.is_empty() {
+            route_json["featuredStops"] = json!(featured);
+        }
+
+        if variants.len() > 1 {
+            route_json["routeDetails"] = json!(describe_variants(variants));
+        }
+
+        true
+    }
+
+    /// Both terminals plus the most shared stops in between, in running order.
+    fn key_stops(&self, segment: &[RawStop]) -> Vec<String> {
+        let Some((first, rest)) = segment.split_first() else {
+            return Vec::new();
+        };
+        let Some((last, middle)) = rest.split_last() else {
+            return vec![first.node_nm.clone()];
+        };
+
+        // Transfer points (served by two or more route numbers), most shared first
+        let shared_by = |s: &RawStop| self.popularity.get(&s.node_nm).copied().unwrap_or(0);
+        let mut ranked: Vec<(usize, &RawStop)> = middle
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| shared_by(s) >= 2)
+            .collect();
+        ranked.sort_by_key(|(i, s)| (std::cmp::Reverse(shared_by(s)), *i));
+
+        let is_terminal = |name: &str| name == first.node_nm || name == last.node_nm;
+        let mut picked: Vec<usize> = Vec::new();
+        for (i, stop) in ranked {
+            if picked.len() + 2 >= FEATURED_STOPS_PER_DIRECTION {
+                break;
+            }
+            if !is_terminal(&stop.node_nm)
+                && !picked.iter().any(|p| middle[*p].node_nm == stop.node_nm)
+            {
+                picked.push(i);
+            }
+        }
+        picked.sort();
+
+        let mut ordered = vec![first.node_nm.clone()];
+        ordered.extend(picked.into_iter().map(|i| middle[i].node_nm.clone()));
+        ordered.push(last.node_nm.clone());
+        ordered
+    }
+}
+
+/// Splits a stop sequence at the turning point (first change of `up_down_cd`).
+/// The turning stop starts the return direction as well as ending the outbound one.
+fn split_directions(stops: &[RawStop]) -> Vec<&[RawStop]> {
+    match stops
+        .windows(2)
+        .position(|w| w[0].up_down_cd != w[1].up_down_cd)
+    {
+        Some(turn_idx) => vec![&stops[..=turn_idx], &stops[turn_idx..]],
+        None if stops.is_empty() => Vec::new(),
+        None => vec![stops],
+    }
+}
+
+/// Matches schedule directions (named after their departure terminal, e.g., "장양리")
+/// to direction segments by the name of the first stop. Unmatched directions take the
+/// remaining segments in order.
+fn match_directions<'a>(
+    directions: &[String],
+    segments: &[&'a [RawStop]],
+) -> Vec<(String, &'a [RawStop])> {
+    let mut assigned: Vec<Option<usize>> = vec![None; directions.len()];
+    let mut taken = vec![false; segments.len()];
+
+    for (d, direction) in directions.iter().enumerate() {
+        if let Some(s) = (0..segments.len())
+            .find(|&s| !taken[s] && names_match(&segments[s][0].node_nm, direction))
+        {
+            assigned[d] = Some(s);
+            taken[s] = true;
+        }
+    }
+
+    let mut free = (0..segments.len()).filter(|&s| !taken[s]);
+    for slot in assigned.iter_mut().filter(|a| a.is_none()) {
+        *slot = free.next();
+    }
+
+    directions
+        .iter()
+        .zip(assigned)
+        .filter_map(|(d, s)| s.map(|s| (d.clone(), segments[s])))
+        .collect()
+}
+
+/// Loose stop name comparison ("연세대" matches "연세대정문(미래캠퍼스)")
+fn names_match(stop_name: &str, direction: &str) -> bool {
+    let clean = |s: &str| -> String {
+        s.split('(')
+            .next()
+            .unwrap_or(s)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect()
+    };
+    let (a, b) = (clean(stop_name), clean(direction));
+    !a.is_empty() && !b.is_empty() && (a.contains(&b) || b.contains(&a))
+}
+
+/// One line per TAGO variant: its terminals, stop count, and the stops only it serves.
+fn describe_variants(variants: &[RawRouteFile]) -> Vec<String> {
+    let common: HashSet<&str> = variants
+        .iter()
+        .map(|v| {
+            v.stops
+                .iter()
+                .map(|s| s.node_nm.as_str())
+                .collect::<HashSet<_>>()
+        })
+        .reduce(|a, b| a.intersection(&b).copied().collect())
+        .unwrap_or_default();
+
+    variants
+        .iter()
+        .filter_map(|v| {
+            let segments = split_directions(&v.stops);
+            let first = segments.first()?.first()?;
+            let turn = segments.first()?.last()?;
+
+            let mut line = format!(
+                "{} ({}): {} ↔ {}, {}개 정류장",
+                v.route_no,
+                v.route_id,
+                first.node_nm,
+                turn.node_nm,
+                v.stops.len()
+            );
+
+            let mut only: Vec<&str> = Vec::new();
+            for s in &v.stops {
+                if !common.contains(s.node_nm.as_str()) && !only.contains(&s.node_nm.as_str()) {
+                    only.push(&s.node_nm);
+                }
+            }
+            match only.len() {
+                0 => {}
+                1..=3 => line.push_str(&format!(", {} 경유", only.join("·"))),
+                n => line.push_str(&format!(", {} 외 {}곳 경유", only[..3].join("·"), n - 3)),
+            }
+
+            Some(line)
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+// src/schedule/service_time.rs
+
+use std::fmt;
+
+use anyhow::{Result, bail};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::config::{SERVICE_DAY_MAX_MINUTES, SERVICE_DAY_START_MINUTES};
+
+/// A time of day on a service day, in minutes since the midnight that starts it.
+///
+/// Trips after midnight stay on the service day they started on, so values of 24:00 and
+/// later are valid: "24:10" (or "00:20" listed in a timetable) sorts after "23:50".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServiceTime(u32);
+
+impl ServiceTime {
+    pub fn from_minutes(minutes: u32) -> Self {
+        Self(minutes)
+    }
+
+    /// Parses a timetable time ("6:05", "23:50", "24:10", "00:20").
+    ///
+    /// Times before `SERVICE_DAY_START_MINUTES` are moved past midnight ("00:20" -> 24:20).
+    pub fn parse(text: &str) -> Result<Self> {
+        let Some((h, m)) = text.trim().split_once(':') else {
+            bail!("Invalid time '{}'", text);
+        };
+        let (Ok(hour), Ok(minute)) = (h.parse::<u32>(), m.parse::<u32>()) else {
+            bail!("Invalid time '{}'", text);
+        };
+        if minute >= 60 {
+            bail!("Invalid minute in '{}'", text);
+        }
+
+        let mut minutes = hour * 60 + minute;
+        if minutes < SERVICE_DAY_START_MINUTES {
+            minutes += 24 * 60;
+        }
+        if minutes >= SERVICE_DAY_MAX_MINUTES {
+            bail!("Time '{}' is beyond the end of the service day", text);
+        }
+
+        Ok(Self(minutes))
+    }
+
+    /// Parses a time from the schedule JSON (hour key and minute string).
+    pub fn from_hour_minute(hour: &str, minute: &str) -> Result<Self> {
+        Self::parse(&format!("{}:{}", hour, minute))
+    }
+
+    /// Minutes since the service-day midnight
+    pub fn minutes(self) -> u32 {
+        self.0
+    }
+
+    /// Service hour, 24 or more after midnight
+    pub fn hour(self) -> u32 {
+        self.0 / 60
+    }
+
+    pub fn minute(self) -> u32 {
+        self.0 % 60
+    }
+
+    /// The calendar date and time of this service time on `service_date`.
+    pub fn on(self, service_date: NaiveDate) -> NaiveDateTime {
+        service_date.and_hms_opt(0, 0, 0).unwrap() + Duration::minutes(self.0 as i64)
+    }
+
+    /// The service date a calendar date and time belongs to (01:30 belongs to the day before).
+    pub fn service_date_of(at: NaiveDateTime) -> NaiveDate {
+        (at - Duration::minutes(SERVICE_DAY_START_MINUTES as i64)).date()
+    }
+}
+
+impl fmt::Display for ServiceTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())
+    }
+}
@@ -0,0 +1,187 @@
+// src/serve/mod.rs
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::extract::{Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::DateTime;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tokio::time::{MissedTickBehavior, interval};
+use tracing::{error, info, warn};
+
+use crate::config::model::PollyConfig;
+use crate::error::NoServiceOnDay;
+use crate::planner::model::{Place, Planner};
+use crate::realtime::model::{FeedSnapshot, RealtimeProcessor};
+use crate::realtime::write_snapshot;
+use crate::schedule::day_type::WEEKDAY;
+use crate::schedule::service_time::ServiceTime;
+use crate::utils::ensure_dir;
+
+type SharedSnapshot = Arc<RwLock<Option<FeedSnapshot>>>;
+
+#[derive(Clone)]
+struct AppState {
+    snapshot: SharedSnapshot,
+    // None when no timetables were found
+    planner: Option<Arc<Planner>>,
+}
+
+/// Query of `GET /plan`
+#[derive(Deserialize)]
+struct PlanQuery {
+    from: String,
+    to: String,
+    at: String,
+    day: Option<String>,
+}
+
+/// Main entry point for the HTTP server.
+///
+/// 1. Loads route sequences from `routeMap.json`.
+/// 2. Polls TAGO every `interval_secs` seconds in the background and rebuilds the
+///    GTFS-Realtime feeds (optionally writing them to `write_dir`).
+/// 3. Serves the latest feeds:
+///    - `GET /gtfs-rt/vehicle-positions`
+///    - `GET /gtfs-rt/trip-updates`
+///    - `GET /plan?from=&to=&at=HH:MM&day=weekday` (journeys as JSON, when timetables
+///      were found in `stop_times_dir`)
+///
+pub async fn run(
+    config: &PollyConfig,
+    routes_dir: PathBuf,
+    stop_times_dir: PathBuf,
+    bind: String,
+    interval_secs: u64,
+    write_dir: Option<PathBuf>,
+) -> Result<()> {
+    if let Some(dir) = write_dir.as_ref() {
+        ensure_dir(dir)?;
+    }
+
+    let processor = RealtimeProcessor::load(&routes_dir.join("routeMap.json"), config)?;
+    info!(
+        "Loaded {} routes for real-time feeds.",
+        processor.routes.len()
+    );
+
+    let planner = if stop_times_dir.is_dir() {
+        let planner = Planner::load(&routes_dir, &stop_times_dir, config.planner.clone())?;
+        info!(
+            "Loaded {} route directions for journey planning.",
+            planner.patterns.len()
+        );
+        Some(Arc::new(planner))
+    } else {
+        info!(
+            "No timetables in {:?}; journey planning is disabled.",
+            stop_times_dir
+        );
+        None
+    };
+
+    let snapshot: SharedSnapshot = Arc::new(RwLock::new(None));
+
+    // Background polling loop
+    let poll_snapshot = Arc::clone(&snapshot);
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(interval_secs.max(1)));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            match processor.build_snapshot().await {
+                Ok(s) => {
+                    if let Some(dir) = write_dir.as_ref()
+                        && let Err(e) = write_snapshot(dir, &s)
+                    {
+                        error!(dir = ?dir, "Failed to write feeds: {:?}", e);
+                    }
+                    *poll_snapshot.write().await = Some(s);
+                }
+                Err(e) => warn!("Feed update failed; serving the previous snapshot: {:?}", e),
+            }
+        }
+    });
+
+    let app = Router::new()
+        .route("/gtfs-rt/vehicle-positions", get(vehicle_positions))
+        .route("/gtfs-rt/trip-updates", get(trip_updates))
+        .route("/plan", get(plan))
+        .with_state(AppState { snapshot, planner });
+
+    let listener = tokio::net::TcpListener::bind(&bind).await?;
+    info!("✓ Serving on http://{}", listener.local_addr()?);
+
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+async fn vehicle_positions(State(state): State<AppState>) -> Response {
+    feed_response(&state.snapshot, |s| s.vehicle_positions.clone()).await
+}
+
+async fn trip_updates(State(state): State<AppState>) -> Response {
+    feed_response(&state.snapshot, |s| s.trip_updates.clone()).await
+}
+
+async fn plan(State(state): State<AppState>, Query(query): Query<PlanQuery>) -> Response {
+    let Some(planner) = state.planner else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+
+    // A search scans every trip of the day, so keep it off the async workers.
+    let result = tokio::task::spawn_blocking(move || {
+        let day_type = query.day.as_deref().unwrap_or(WEEKDAY);
+        let from = Place::parse(&query.from)?;
+        let to = Place::parse(&query.to)?;
+        planner.plan(&from, &to, ServiceTime::parse(&query.at)?, day_type)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(result)) => Json(result).into_response(),
+        // A valid request for a day type the loaded timetables do not cover
+        Ok(Err(e)) if e.is::<NoServiceOnDay>() => {
+            (StatusCode::NOT_FOUND, e.to_string()).into_response()
+        }
+        Ok(Err(e)) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        Err(e) => {
+            error!(error = %e, "Journey planning task failed");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn feed_response(
+    snapshot: &SharedSnapshot,
+    select: impl Fn(&FeedSnapshot) -> Vec<u8>,
+) -> Response {
+    match snapshot.read().await.as_ref() {
+        Some(s) => {
+            let last_modified = DateTime::from_timestamp(s.generated_at as i64, 0)
+                .unwrap_or_default()
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string();
+            (
+                [
+                    (header::CONTENT_TYPE, "application/x-protobuf".to_string()),
+                    (header::LAST_MODIFIED, last_modified),
+                ],
+                select(s),
+            )
+                .into_response()
+        }
+        // The first polling cycle has not finished yet.
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
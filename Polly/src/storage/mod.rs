@@ -0,0 +1,177 @@
+// src/storage/mod.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::error::{ParseError, PipelineError, StorageError};
+
+// ============================================================================
+// Storage Backends
+// ============================================================================
+
+/// Where a processor reads and writes its files.
+///
+/// Keys are paths relative to the output root with `/` separators
+/// (e.g., `raw_routes/30_WJB251000068.json`).
+pub trait Storage: Send + Sync {
+    fn read(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    /// Writes a file, creating its parent directories.
+    fn write(&self, key: &str, contents: &[u8]) -> Result<(), StorageError>;
+
+    /// Keys of the files directly under `dir`, sorted; empty when `dir` does not exist.
+    fn list(&self, dir: &str) -> Result<Vec<String>, StorageError>;
+}
+
+/// Files under a directory on disk (what the CLI uses)
+pub struct FsStorage {
+    root: PathBuf,
+}
+
+/// Files kept in memory, for embedders that store the output themselves
+#[derive(Default)]
+pub struct MemoryStorage {
+    files: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl FsStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl Storage for FsStorage {
+    fn read(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.path(key);
+        fs::read(&path).map_err(|source| StorageError::Read { path, source })
+    }
+
+    fn write(&self, key: &str, contents: &[u8]) -> Result<(), StorageError> {
+        let path = self.path(key);
+        let result = match path.parent() {
+            Some(dir) => fs::create_dir_all(dir).and_then(|()| fs::write(&path, contents)),
+            None => fs::write(&path, contents),
+        };
+        result.map_err(|source| StorageError::Write { path, source })
+    }
+
+    fn list(&self, dir: &str) -> Result<Vec<String>, StorageError> {
+        let path = self.path(dir);
+        let entries = match fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(StorageError::Read { path, source }),
+        };
+
+        let mut keys: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_file())
+            .map(|e| format!("{}/{}", dir, e.file_name().to_string_lossy()))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All files written so far (key -> contents)
+    pub fn files(&self) -> BTreeMap<String, Vec<u8>> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.lock()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| StorageError::Read {
+                path: PathBuf::from(key),
+                source: io::ErrorKind::NotFound.into(),
+            })
+    }
+
+    fn write(&self, key: &str, contents: &[u8]) -> Result<(), StorageError> {
+        self.lock().insert(key.to_string(), contents.to_vec());
+        Ok(())
+    }
+
+    fn list(&self, dir: &str) -> Result<Vec<String>, StorageError> {
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
+        Ok(self
+            .lock()
+            .keys()
+            .filter(|k| {
+                k.strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains('/'))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+// ============================================================================
+// Text and JSON Helpers
+// ============================================================================
+
+/// Reads a UTF-8 text file (e.g., an archived HTML page).
+pub fn read_text(storage: &dyn Storage, key: &str) -> Result<String, StorageError> {
+    String::from_utf8(storage.read(key)?).map_err(|e| StorageError::Read {
+        path: PathBuf::from(key),
+        source: io::Error::new(io::ErrorKind::InvalidData, e),
+    })
+}
+
+pub fn read_json<T: DeserializeOwned>(
+    storage: &dyn Storage,
+    key: &str,
+) -> Result<T, PipelineError> {
+    let bytes = storage.read(key)?;
+    serde_json::from_slice(&bytes).map_err(|source| {
+        ParseError::Json {
+            what: key.to_string(),
+            source,
+        }
+        .into()
+    })
+}
+
+/// Writes `value` as JSON, pretty-printed unless `compact`.
+pub fn write_json<T: Serialize>(
+    storage: &dyn Storage,
+    key: &str,
+    value: &T,
+    compact: bool,
+) -> Result<(), StorageError> {
+    let json = match compact {
+        true => serde_json::to_vec(value),
+        false => serde_json::to_vec_pretty(value),
+    };
+    let json = json.map_err(|e| StorageError::Write {
+        path: PathBuf::from(key),
+        source: e.into(),
+    })?;
+    storage.write(key, &json)
+}
@@ -0,0 +1,107 @@
+// src/timetable/board.rs
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::Local;
+use tracing::warn;
+
+use crate::timetable::model::{BoardDeparture, DepartureBoard, StopTimesFile};
+use crate::utils::ensure_dir;
+
+/// Builds one departure board per station (`nodeid`) from every file in `stop_times_dir`
+/// and saves them to `departures/<nodeid>.json`. Returns the number of boards.
+///
+/// The last stop of a leg is an arrival and is left out. TAGO variants of the same
+/// route number share a schedule, so a trip listed by several variants appears once.
+pub fn write_departure_boards(stop_times_dir: &Path, departures_dir: &Path) -> Result<usize> {
+    ensure_dir(departures_dir)?;
+
+    let mut paths: Vec<_> = fs::read_dir(stop_times_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut boards: BTreeMap<String, DepartureBoard> = BTreeMap::new();
+    // (node ID, day type, route number, direction, time) of listed departures
+    let mut listed: HashSet<(String, String, String, String, String)> = HashSet::new();
+    let generated_at = Local::now().to_rfc3339();
+
+    for path in paths {
+        let file: StopTimesFile = match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(serde_json::from_str(&content)?))
+        {
+            Ok(f) => f,
+            Err(e) => {
+                warn!(path = ?path, "Skipping {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        for direction in &file.directions {
+            let departing = direction.stops.len().saturating_sub(1);
+
+            for (day_type, trips) in &direction.day_types {
+                for trip in trips {
+                    for (stop, time) in direction.stops[..departing].iter().zip(&trip.times) {
+                        let key = (
+                            stop.id.clone(),
+                            day_type.clone(),
+                            file.route_no.clone(),
+                            direction.direction.clone(),
+                            time.clone(),
+                        );
+                        if !listed.insert(key) {
+                            continue;
+                        }
+
+                        let board =
+                            boards
+                                .entry(stop.id.clone())
+                                .or_insert_with(|| DepartureBoard {
+                                    node_id: stop.id.clone(),
+                                    name: stop.name.clone(),
+                                    generated_at: generated_at.clone(),
+                                    day_types: BTreeMap::new(),
+                                });
+
+                        board
+                            .day_types
+                            .entry(day_type.clone())
+                            .or_default()
+                            .push(BoardDeparture {
+                                time: time.clone(),
+                                route_no: file.route_no.clone(),
+                                route_id: file.route_id.clone(),
+                                direction: direction.direction.clone(),
+                                headsign: direction.headsign.clone(),
+                                note: trip
+                                    .note_id
+                                    .as_ref()
+                                    .and_then(|id| file.notes.get(id).cloned()),
+                                attributes: trip.attributes.clone(),
+                            });
+                    }
+                }
+            }
+        }
+    }
+
+    for board in boards.values_mut() {
+        for departures in board.day_types.values_mut() {
+            // "HH:MM" with service hours past 23 sorts correctly as text.
+            departures.sort_by(|a, b| {
+                (&a.time, &a.route_no, &a.direction).cmp(&(&b.time, &b.route_no, &b.direction))
+            });
+        }
+
+        let path = departures_dir.join(format!("{}.json", board.node_id));
+        fs::write(&path, serde_json::to_string_pretty(board)?)?;
+    }
+
+    Ok(boards.len())
+}
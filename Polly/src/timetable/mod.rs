@@ -0,0 +1,305 @@
+// src/timetable/mod.rs
+
+mod board;
+pub mod model;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::Local;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::analysis::model::{SegmentTravelTime, TravelTimeFile};
+use crate::analysis::{
+    load_derived_route, load_schedule, scheduled_trips, stop_distances, terminal_directions,
+};
+use crate::config::{TIMETABLE_DEFAULT_SPEED_KMH, TIMETABLE_DWELL_SECS};
+use crate::registry::model::RouteRegistry;
+use crate::registry::normalize_route_no;
+use crate::route::model::DerivedFeature;
+use crate::schedule::day_type::fallback_chain;
+use crate::schedule::service_time::ServiceTime;
+use crate::timetable::board::write_departure_boards;
+use crate::timetable::model::{DirectionTimetable, StopTimesFile, TimetableStop, TripStopTimes};
+use crate::utils::ensure_dir;
+
+/// Main entry point for stop-level timetable interpolation.
+///
+/// 1. Links every TAGO route to its schedule through the route registry.
+/// 2. Splits the route's stop sequence into legs at its terminals, one per schedule
+///    direction (see `terminal_directions`).
+/// 3. Walks every scheduled departure of every day type along its leg, adding the
+///    travel time of each segment: the observed median for the day type and hour from
+///    `travel_times/`, else the overall observed median, else an estimate from the
+///    distance along the snapped route.
+/// 4. Saves the estimated time at every stop to `stop_times/<route_id>.json`.
+/// 5. Rebuilds the per-station departure boards in `departures/` from all stop times.
+///
+pub async fn run(
+    routes_dir: PathBuf,
+    schedules_dir: PathBuf,
+    travel_times_dir: PathBuf,
+    specific_route: Option<String>,
+    output_dir: PathBuf,
+) -> Result<()> {
+    let derived_dir = routes_dir.join("derived_routes");
+    let stop_times_dir = output_dir.join("stop_times");
+
+    ensure_dir(&stop_times_dir)?;
+
+    let registry = RouteRegistry::build(&routes_dir, &schedules_dir)?;
+
+    info!("[Interpolating stop times]");
+
+    let mut saved = 0usize;
+
+    for (route_no, entry) in &registry.routes {
+        let Some(schedule_file) = &entry.schedule_file else {
+            continue;
+        };
+
+        let tago_routes: Vec<_> = entry
+            .tago_routes
+            .iter()
+            .filter(|t| {
+                specific_route.as_ref().is_none_or(|target| {
+                    normalize_route_no(target) == *route_no || t.route_id == *target
+                })
+            })
+            .collect();
+        if tago_routes.is_empty() {
+            continue;
+        }
+
+        let schedule = match load_schedule(&schedules_dir.join(schedule_file)) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!(route = %route_no, "Skipping {}: no schedule ({})", route_no, e);
+                continue;
+            }
+        };
+
+        for tago in tago_routes {
+            let route = match load_derived_route(&derived_dir, &tago.route_id) {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!(route = %tago.route_id, "Skipping {}: {}", tago.route_id, e);
+                    continue;
+                }
+            };
+
+            let travel_times = load_travel_times(&travel_times_dir, &tago.route_id);
+            let file = build_stop_times(&route, &schedule, travel_times.as_ref());
+
+            if file.directions.is_empty() {
+                warn!(
+                    route = %tago.route_id,
+                    "Skipping {} ({}): no terminal matches a schedule direction",
+                    route.properties.route_no, tago.route_id
+                );
+                continue;
+            }
+
+            let path = stop_times_dir.join(format!("{}.json", tago.route_id));
+            fs::write(&path, serde_json::to_string_pretty(&file)?)?;
+            saved += 1;
+
+            let trips: usize = file
+                .directions
+                .iter()
+                .flat_map(|d| d.day_types.values())
+                .map(Vec::len)
+                .sum();
+            let observed: usize = file.directions.iter().map(|d| d.observed_segments).sum();
+            let segments: usize = file
+                .directions
+                .iter()
+                .map(|d| d.stops.len().saturating_sub(1))
+                .sum();
+            info!(
+                route = %tago.route_id,
+                "✓ {} ({}): {} trips, {} of {} segments observed",
+                route.properties.route_no, tago.route_id, trips, observed, segments
+            );
+        }
+    }
+
+    info!(
+        "✓ Saved stop times for {} routes to {:?}",
+        saved, stop_times_dir
+    );
+
+    info!("[Building departure boards]");
+
+    let departures_dir = output_dir.join("departures");
+    let boards = write_departure_boards(&stop_times_dir, &departures_dir)?;
+
+    info!(
+        "✓ Saved departure boards for {} stations to {:?}",
+        boards, departures_dir
+    );
+
+    Ok(())
+}
+
+/// Loads the travel times of a route, if the travel time analysis has been run.
+fn load_travel_times(dir: &Path, route_id: &str) -> Option<TravelTimeFile> {
+    let content = fs::read_to_string(dir.join(format!("{}.json", route_id))).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            warn!(route = %route_id, "Ignoring travel times of {}: {}", route_id, e);
+            None
+        }
+    }
+}
+
+fn build_stop_times(
+    route: &DerivedFeature,
+    schedule: &Value,
+    travel_times: Option<&TravelTimeFile>,
+) -> StopTimesFile {
+    let stops = &route.properties.stops;
+    let stop_dist = stop_distances(route);
+
+    // Observed travel times per segment (stop i -> stop i + 1), matched by stop IDs
+    let observed: Vec<Option<&SegmentTravelTime>> = (0..stops.len().saturating_sub(1))
+        .map(|i| {
+            travel_times.and_then(|t| {
+                t.segments
+                    .iter()
+                    .find(|s| s.from_stop == stops[i].id && s.to_stop == stops[i + 1].id)
+            })
+        })
+        .collect();
+
+    let day_keys: Vec<String> = schedule["schedule"]
+        .as_object()
+        .map(|table| table.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let terminals = terminal_directions(route, schedule);
+    let turn = (1..stops.len()).find(|&i| stops[i].up_down != stops[i - 1].up_down);
+    let mut directions = Vec::new();
+
+    for (leg, (start, direction)) in terminals.iter().enumerate() {
+        // A leg ends where the next one starts, or at the turning point when the return
+        // leg has no schedule direction (circular routes run to the last stop).
+        let end = match (terminals.get(leg + 1), turn) {
+            (Some((next, _)), _) => *next,
+            (None, Some(turn)) if turn > *start => turn,
+            _ => stops.len(),
+        };
+        if end <= *start {
+            continue;
+        }
+
+        let mut day_types = BTreeMap::new();
+        for day_key in &day_keys {
+            let Some(trips) = scheduled_trips(schedule, day_key).remove(direction) else {
+                continue;
+            };
+
+            let trips = trips
+                .into_iter()
+                .map(|trip| {
+                    let mut secs = trip.time.minutes() as f64 * 60.0;
+                    let mut times = vec![trip.time.to_string()];
+
+                    for i in *start..end - 1 {
+                        secs += segment_secs(
+                            observed[i],
+                            stop_dist[i + 1] - stop_dist[i],
+                            day_key,
+                            secs,
+                        );
+                        times.push(
+                            ServiceTime::from_minutes((secs / 60.0).round() as u32).to_string(),
+                        );
+                    }
+
+                    TripStopTimes {
+                        departure: trip.time.to_string(),
+                        note_id: trip.note_id,
+                        attributes: trip.attributes,
+                        times,
+                    }
+                })
+                .collect();
+
+            day_types.insert(day_key.clone(), trips);
+        }
+
+        directions.push(DirectionTimetable {
+            direction: direction.clone(),
+            headsign: stops[end - 1].name.clone(),
+            stops: stops[*start..end]
+                .iter()
+                .map(|s| TimetableStop {
+                    id: s.id.clone(),
+                    name: s.name.clone(),
+                    ord: s.ord,
+                })
+                .collect(),
+            observed_segments: observed[*start..end - 1]
+                .iter()
+                .filter(|s| s.is_some_and(|s| s.overall.is_some()))
+                .count(),
+            day_types,
+        });
+    }
+
+    let notes = schedule["notes"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(id, text)| Some((id.clone(), text.as_str()?.to_string())))
+        .collect();
+
+    StopTimesFile {
+        route_id: route.properties.route_id.clone(),
+        route_no: route.properties.route_no.clone(),
+        generated_at: Local::now().to_rfc3339(),
+        directions,
+        notes,
+    }
+}
+
+/// Travel time (seconds) of a segment entered at `at_secs` (since service-day midnight).
+///
+/// Observed buckets of every day type that runs the `day_key` timetable (e.g., saturday
+/// and sunday for `weekend`) are combined, weighted by their sample counts.
+fn segment_secs(
+    observed: Option<&SegmentTravelTime>,
+    distance: f64,
+    day_key: &str,
+    at_secs: f64,
+) -> f64 {
+    let estimate = distance.max(0.0) / (TIMETABLE_DEFAULT_SPEED_KMH / 3.6) + TIMETABLE_DWELL_SECS;
+    let Some(segment) = observed else {
+        return estimate;
+    };
+
+    // Observations are bucketed by calendar hour.
+    let hour = format!("{:02}", (at_secs / 3600.0) as u32 % 24);
+
+    let (mut weighted, mut samples) = (0.0, 0usize);
+    for (day_type, by_hour) in &segment.by_day_type {
+        if !fallback_chain(day_type).contains(&day_key) {
+            continue;
+        }
+        if let Some(stats) = by_hour.get(&hour) {
+            weighted += stats.median * stats.samples as f64;
+            samples += stats.samples;
+        }
+    }
+
+    if samples > 0 {
+        weighted / samples as f64
+    } else {
+        segment.overall.as_ref().map_or(estimate, |s| s.median)
+    }
+}
@@ -0,0 +1,88 @@
+// src/timetable/model.rs
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// ============================================================================
+// Output Models (Saved to stop_times/)
+// ============================================================================
+
+/// Estimated times at every stop for every scheduled trip of a TAGO route
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopTimesFile {
+    pub route_id: String,
+    pub route_no: String,
+    pub generated_at: String,
+    pub directions: Vec<DirectionTimetable>,
+    // Note ID -> Text, copied from the schedule
+    #[serde(default)]
+    pub notes: BTreeMap<String, String>,
+}
+
+/// The stop times of one schedule direction (one leg of the route)
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectionTimetable {
+    pub direction: String,
+    // Name of the last stop of the leg
+    pub headsign: String,
+    pub stops: Vec<TimetableStop>,
+    // Number of segments of the leg estimated from observed travel times
+    pub observed_segments: usize,
+    // Day type -> Trips sorted by departure
+    pub day_types: BTreeMap<String, Vec<TripStopTimes>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TimetableStop {
+    pub id: String,
+    pub name: String,
+    pub ord: i64,
+}
+
+/// A scheduled trip with its estimated time at every stop of the leg
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TripStopTimes {
+    pub departure: String, // "HH:MM"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note_id: Option<String>,
+    // Trip attributes copied from the schedule (e.g., "via", "lowFloor")
+    #[serde(flatten)]
+    pub attributes: Map<String, Value>,
+    // "HH:MM" per stop, in the order of `stops` (service hours may exceed 23)
+    pub times: Vec<String>,
+}
+
+// ============================================================================
+// Output Models (Saved to departures/)
+// ============================================================================
+
+/// Scheduled departures of every route serving a station
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepartureBoard {
+    pub node_id: String,
+    pub name: String,
+    pub generated_at: String,
+    // Day type -> Departures sorted by time
+    pub day_types: BTreeMap<String, Vec<BoardDeparture>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardDeparture {
+    pub time: String, // "HH:MM"
+    pub route_no: String,
+    pub route_id: String,
+    pub direction: String,
+    pub headsign: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    // Trip attributes (e.g., "lowFloor", "lastBus")
+    #[serde(flatten)]
+    pub attributes: Map<String, Value>,
+}
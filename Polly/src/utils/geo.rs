@@ -1,82 +1,274 @@
 // src/utils/geo.rs
 
-/// Calculate distance in meters between two GPS coordinates using Equirectangular approximation
+use rstar::RTree;
+use rstar::primitives::{GeomWithData, Line};
+
+// WGS84 ellipsoid
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F);
+
+/// Mean Earth radius (IUGG), for spherical distances
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+// ============================================================================
+// Distances
+// ============================================================================
+
+/// Calculate distance in meters between two GPS coordinates (haversine, on a sphere).
+///
+/// Within 0.6% of the ellipsoidal distance (under 0.25% in Wonju); use `vincenty_m` when that matters.
 pub fn meters_between(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
-    // Equirectangular approximation
-    let r = 6371000.0;
+    haversine_m(lon1, lat1, lon2, lat2)
+}
 
-    let x = (lon2 - lon1).to_radians() * ((lat1 + lat2) * 0.5).to_radians().cos();
-    let y = (lat2 - lat1).to_radians();
+/// Great-circle distance in meters on a sphere of radius `EARTH_RADIUS_M`
+pub fn haversine_m(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = phi2 - phi1;
+    let d_lambda = (lon2 - lon1).to_radians();
 
-    (x * x + y * y).sqrt() * r
+    let h = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
 }
 
-/// Find the closest point on a polyline to a given point
-pub fn closest_point_on_polyline(
-    point: (f64, f64),
-    line: &Vec<Vec<f64>>,
-) -> Option<((f64, f64), f64)> {
-    if line.len() < 2 {
-        return None;
-    }
+/// Distance in meters on the WGS84 ellipsoid (Vincenty's inverse formula, ~0.5 mm).
+///
+/// Returns `None` when the iteration does not converge (nearly antipodal points).
+pub fn vincenty_m(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> Option<f64> {
+    let l = (lon2 - lon1).to_radians();
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
 
-    let (px, py) = point;
-    let mut best = None;
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            return Some(0.0); // Coincident points
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
 
-    for seg in line.windows(2) {
-        let (x1, y1) = (seg[0][0], seg[0][1]);
-        let (x2, y2) = (seg[1][0], seg[1][1]);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Both points on the equator: cos(2 sigma_m) is 0
+        let cos_2sigma_m = match cos2_alpha {
+            0.0 => 0.0,
+            _ => cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha,
+        };
 
-        let dx = x2 - x1;
-        let dy = y2 - y1;
+        let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
 
-        let denom = dx * dx + dy * dy;
-        if denom == 0.0 {
-            continue;
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (WGS84_A.powi(2) - WGS84_B.powi(2)) / WGS84_B.powi(2);
+            let a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                            - b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+
+            return Some(WGS84_B * a * (sigma - delta_sigma));
         }
+    }
 
-        let t = ((px - x1) * dx + (py - y1) * dy) / denom;
+    None
+}
 
-        let cx = x1 + t.clamp(0.0, 1.0) * dx;
-        let cy = y1 + t.clamp(0.0, 1.0) * dy;
+/// Ellipsoidal distance in meters (Vincenty), falling back to haversine
+pub fn geodesic_m(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    vincenty_m(lon1, lat1, lon2, lat2).unwrap_or_else(|| haversine_m(lon1, lat1, lon2, lat2))
+}
 
-        let d = meters_between(px, py, cx, cy);
+/// Rounds a distance to 0.1 m, for output files
+pub fn round_m(meters: f64) -> f64 {
+    (meters * 10.0).round() / 10.0
+}
 
-        match best {
-            None => best = Some(((cx, cy), d)),
-            Some((_, bd)) if d < bd => best = Some(((cx, cy), d)),
-            _ => {}
+/// Rounds a coordinate to 6 decimal places (~0.1 m), for output files
+pub fn round_coord(degrees: f64) -> f64 {
+    (degrees * 1_000_000.0).round() / 1_000_000.0
+}
+
+// ============================================================================
+// Local Tangent Plane (ENU)
+// ============================================================================
+
+/// Position in meters east, north and up of a `LocalProjection` origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Enu {
+    pub east: f64,
+    pub north: f64,
+    pub up: f64,
+}
+
+/// East-North-Up tangent plane of the WGS84 ellipsoid at an origin.
+///
+/// Planar geometry in (east, north) is isotropic in meters, unlike raw lon/lat degrees
+/// (a degree of longitude is ~20% shorter than a degree of latitude in Wonju). Distortion
+/// stays below 1 mm per km within 10 km of the origin.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalProjection {
+    origin: [f64; 3],
+    sin_lat: f64,
+    cos_lat: f64,
+    sin_lon: f64,
+    cos_lon: f64,
+}
+
+impl LocalProjection {
+    pub fn new(lon: f64, lat: f64) -> Self {
+        let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = lon.to_radians().sin_cos();
+        Self {
+            origin: to_ecef(lon, lat),
+            sin_lat,
+            cos_lat,
+            sin_lon,
+            cos_lon,
         }
     }
 
-    best
+    pub fn to_enu(&self, lon: f64, lat: f64) -> Enu {
+        let p = to_ecef(lon, lat);
+        let (dx, dy, dz) = (
+            p[0] - self.origin[0],
+            p[1] - self.origin[1],
+            p[2] - self.origin[2],
+        );
+
+        Enu {
+            east: -self.sin_lon * dx + self.cos_lon * dy,
+            north: -self.sin_lat * self.cos_lon * dx - self.sin_lat * self.sin_lon * dy
+                + self.cos_lat * dz,
+            up: self.cos_lat * self.cos_lon * dx
+                + self.cos_lat * self.sin_lon * dy
+                + self.sin_lat * dz,
+        }
+    }
+
+    /// Longitude and latitude of the ellipsoid point below (or above) `enu`
+    pub fn to_lonlat(&self, enu: Enu) -> (f64, f64) {
+        let Enu { east, north, up } = enu;
+        let x = self.origin[0] - self.sin_lon * east - self.sin_lat * self.cos_lon * north
+            + self.cos_lat * self.cos_lon * up;
+        let y = self.origin[1] + self.cos_lon * east - self.sin_lat * self.sin_lon * north
+            + self.cos_lat * self.sin_lon * up;
+        let z = self.origin[2] + self.cos_lat * north + self.sin_lat * up;
+
+        from_ecef(x, y, z)
+    }
+}
+
+/// Earth-centered coordinates of a point on the ellipsoid surface
+fn to_ecef(lon: f64, lat: f64) -> [f64; 3] {
+    let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = lon.to_radians().sin_cos();
+    let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+
+    [
+        n * cos_lat * cos_lon,
+        n * cos_lat * sin_lon,
+        n * (1.0 - WGS84_E2) * sin_lat,
+    ]
+}
+
+/// Longitude and latitude of Earth-centered coordinates (the height is dropped)
+fn from_ecef(x: f64, y: f64, z: f64) -> (f64, f64) {
+    let lon = y.atan2(x);
+    let p = (x * x + y * y).sqrt();
+
+    // Converges to sub-millimeter in a few iterations away from the poles
+    let mut lat = z.atan2(p * (1.0 - WGS84_E2));
+    for _ in 0..5 {
+        let sin_lat = lat.sin();
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        let h = p / lat.cos() - n;
+        lat = z.atan2(p * (1.0 - WGS84_E2 * n / (n + h)));
+    }
+
+    (lon.to_degrees(), lat.to_degrees())
 }
 
-/// Find the index of the coordinate in `line` closest to `point`
-pub fn find_nearest_coord_index(point: (f64, f64), line: &Vec<Vec<f64>>) -> Option<usize> {
-    if line.is_empty() {
+/// Closest point of segment `a`-`b` to the origin of their projection.
+///
+/// Returns the position along the segment (0 at `a`, 1 at `b`) and the point.
+fn closest_to_origin(a: Enu, b: Enu) -> (f64, Enu) {
+    let (dx, dy) = (b.east - a.east, b.north - a.north);
+    let denom = dx * dx + dy * dy;
+    let t = if denom == 0.0 {
+        0.0
+    } else {
+        (-(a.east * dx + a.north * dy) / denom).clamp(0.0, 1.0)
+    };
+
+    let point = Enu {
+        east: a.east + t * dx,
+        north: a.north + t * dy,
+        up: a.up + t * (b.up - a.up),
+    };
+    (t, point)
+}
+
+// ============================================================================
+// Polylines
+// ============================================================================
+
+/// Find the closest point on a polyline to a given point, and its distance in meters.
+///
+/// Segments are projected on the tangent plane at `point`.
+pub fn closest_point_on_polyline(
+    point: (f64, f64),
+    line: &[Vec<f64>],
+) -> Option<((f64, f64), f64)> {
+    if line.len() < 2 {
         return None;
     }
 
-    let (px, py) = point;
+    let projection = LocalProjection::new(point.0, point.1);
+    let mut best: Option<(Enu, f64)> = None;
 
-    let mut best_idx = 0;
-    let mut min_dist = f64::MAX;
+    for seg in line.windows(2) {
+        if seg[0][..2] == seg[1][..2] {
+            continue;
+        }
+        let a = projection.to_enu(seg[0][0], seg[0][1]);
+        let b = projection.to_enu(seg[1][0], seg[1][1]);
 
-    for (i, coord) in line.iter().enumerate() {
-        let d = meters_between(px, py, coord[0], coord[1]);
+        let (_, c) = closest_to_origin(a, b);
+        let d = c.east.hypot(c.north);
 
-        if d < min_dist {
-            min_dist = d;
-            best_idx = i;
+        match best {
+            Some((_, bd)) if d >= bd => {}
+            _ => best = Some((c, d)),
         }
     }
 
-    Some(best_idx)
+    best.map(|(c, d)| (projection.to_lonlat(c), d))
 }
 
-/// Calculate bounding box and total distance of a series of coordinates
-pub fn calculate_metrics(coords: &Vec<Vec<f64>>) -> ([f64; 4], f64) {
+/// Calculate bounding box and total (ellipsoidal) distance of a series of coordinates
+pub fn calculate_metrics(coords: &[Vec<f64>]) -> ([f64; 4], f64) {
     let mut min_lon = 180.0;
     let mut min_lat = 90.0;
 
@@ -103,9 +295,441 @@ pub fn calculate_metrics(coords: &Vec<Vec<f64>>) -> ([f64; 4], f64) {
         }
 
         if i > 0 {
-            dist += meters_between(coords[i - 1][0], coords[i - 1][1], c[0], c[1]);
+            dist += geodesic_m(coords[i - 1][0], coords[i - 1][1], c[0], c[1]);
         }
     }
 
     ([min_lon, min_lat, max_lon, max_lat], dist)
 }
+
+/// Calculate the cumulative (ellipsoidal) distance in meters at each coordinate of a
+/// polyline; the last one is the length `calculate_metrics` reports.
+pub fn cumulative_distances(line: &[Vec<f64>]) -> Vec<f64> {
+    let mut cumulative = Vec::with_capacity(line.len());
+    let mut dist = 0.0;
+
+    for (i, c) in line.iter().enumerate() {
+        if i > 0 {
+            dist += geodesic_m(line[i - 1][0], line[i - 1][1], c[0], c[1]);
+        }
+        cumulative.push(dist);
+    }
+
+    cumulative
+}
+
+/// Locate a point along a polyline, searching only the segments between coordinate
+/// indices `from` and `to` (inclusive).
+///
+/// Returns the distance along the line (meters from its first coordinate) of the closest
+/// point, and the distance in meters between `point` and the line. Segments are
+/// projected on the tangent plane at `point`.
+pub fn locate_on_polyline(
+    point: (f64, f64),
+    line: &[Vec<f64>],
+    cumulative: &[f64],
+    from: usize,
+    to: usize,
+) -> Option<(f64, f64)> {
+    let to = to.min(line.len().saturating_sub(1));
+    if from >= to {
+        return None;
+    }
+
+    let projection = LocalProjection::new(point.0, point.1);
+    let mut best: Option<(f64, f64)> = None;
+
+    for i in from..to {
+        let a = projection.to_enu(line[i][0], line[i][1]);
+        let b = projection.to_enu(line[i + 1][0], line[i + 1][1]);
+
+        let (t, c) = closest_to_origin(a, b);
+
+        let offset = c.east.hypot(c.north);
+        let along = cumulative[i] + t * (cumulative[i + 1] - cumulative[i]);
+
+        match best {
+            Some((_, bo)) if offset >= bo => {}
+            _ => best = Some((along, offset)),
+        }
+    }
+
+    best
+}
+
+// ============================================================================
+// Spatial Indexes
+// ============================================================================
+
+// Queries run on straight lines through the Earth (ECEF), whose order follows surface
+// distance up to the ellipsoid's flattening; the margin covers it for radius searches.
+const CHORD_MARGIN: f64 = 1.01;
+
+/// Upper bound of the chord length between two points `distance_m` apart on the surface
+fn chord_m(distance_m: f64) -> f64 {
+    let angle = (distance_m / EARTH_RADIUS_M).min(std::f64::consts::PI);
+    2.0 * EARTH_RADIUS_M * (angle / 2.0).sin() * CHORD_MARGIN
+}
+
+type IndexedPoint<T> = GeomWithData<[f64; 3], ((f64, f64), T)>;
+
+/// R-tree over points (e.g., stations or path coordinates), each with an item `T`
+pub struct PointIndex<T> {
+    tree: RTree<IndexedPoint<T>>,
+}
+
+impl<T> PointIndex<T> {
+    /// Builds the index from `((lon, lat), item)` pairs.
+    pub fn new(points: impl IntoIterator<Item = ((f64, f64), T)>) -> Self {
+        let entries = points
+            .into_iter()
+            .map(|((lon, lat), item)| GeomWithData::new(to_ecef(lon, lat), ((lon, lat), item)))
+            .collect();
+        Self {
+            tree: RTree::bulk_load(entries),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Items by increasing distance from `point`, with their distance in meters
+    pub fn nearest_iter(&self, point: (f64, f64)) -> impl Iterator<Item = (&T, f64)> + '_ {
+        let query = to_ecef(point.0, point.1);
+        self.tree.nearest_neighbor_iter(&query).map(move |entry| {
+            let ((lon, lat), item) = &entry.data;
+            (item, meters_between(point.0, point.1, *lon, *lat))
+        })
+    }
+
+    pub fn nearest(&self, point: (f64, f64)) -> Option<(&T, f64)> {
+        self.nearest_iter(point).next()
+    }
+
+    /// Items within `radius_m` meters of `point`, nearest first
+    pub fn within(&self, point: (f64, f64), radius_m: f64) -> Vec<(&T, f64)> {
+        let query = to_ecef(point.0, point.1);
+        let chord = chord_m(radius_m);
+
+        let mut found: Vec<(&T, f64)> = self
+            .tree
+            .locate_within_distance(query, chord * chord)
+            .map(|entry| {
+                let ((lon, lat), item) = &entry.data;
+                (item, meters_between(point.0, point.1, *lon, *lat))
+            })
+            .filter(|(_, d)| *d <= radius_m)
+            .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found
+    }
+}
+
+/// Closest point of an indexed polyline segment to a query point
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentMatch<'a, T> {
+    pub item: &'a T,
+    // Index of the segment's first coordinate in the polyline
+    pub segment: usize,
+    // (lon, lat) of the closest point
+    pub point: (f64, f64),
+    pub distance_m: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SegmentRef {
+    line: usize,
+    segment: usize,
+    ends: [(f64, f64); 2],
+}
+
+/// R-tree over the segments of polylines (e.g., route paths), each polyline with an item `T`
+pub struct SegmentIndex<T> {
+    items: Vec<T>,
+    tree: RTree<GeomWithData<Line<[f64; 3]>, SegmentRef>>,
+}
+
+impl<T> SegmentIndex<T> {
+    /// Builds the index from `(item, polyline)` pairs; polylines are `[lon, lat]` lists.
+    pub fn new<'a>(lines: impl IntoIterator<Item = (T, &'a [Vec<f64>])>) -> Self {
+        let mut items = Vec::new();
+        let mut entries = Vec::new();
+
+        for (item, line) in lines {
+            for (segment, seg) in line.windows(2).enumerate() {
+                let ends = [(seg[0][0], seg[0][1]), (seg[1][0], seg[1][1])];
+                if ends[0] == ends[1] {
+                    continue;
+                }
+                let chord = Line::new(to_ecef(ends[0].0, ends[0].1), to_ecef(ends[1].0, ends[1].1));
+                let data = SegmentRef {
+                    line: items.len(),
+                    segment,
+                    ends,
+                };
+                entries.push(GeomWithData::new(chord, data));
+            }
+            items.push(item);
+        }
+
+        Self {
+            items,
+            tree: RTree::bulk_load(entries),
+        }
+    }
+
+    /// Segments by increasing distance from `point`
+    pub fn nearest_iter(
+        &self,
+        point: (f64, f64),
+    ) -> impl Iterator<Item = SegmentMatch<'_, T>> + '_ {
+        let query = to_ecef(point.0, point.1);
+        let projection = LocalProjection::new(point.0, point.1);
+        self.tree
+            .nearest_neighbor_iter(&query)
+            .map(move |entry| self.project(&projection, &entry.data))
+    }
+
+    pub fn nearest(&self, point: (f64, f64)) -> Option<SegmentMatch<'_, T>> {
+        self.nearest_iter(point).next()
+    }
+
+    /// Segments within `radius_m` meters of `point`, nearest first
+    pub fn within(&self, point: (f64, f64), radius_m: f64) -> Vec<SegmentMatch<'_, T>> {
+        let query = to_ecef(point.0, point.1);
+        let projection = LocalProjection::new(point.0, point.1);
+        let chord = chord_m(radius_m);
+
+        let mut found: Vec<SegmentMatch<'_, T>> = self
+            .tree
+            .locate_within_distance(query, chord * chord)
+            .map(|entry| self.project(&projection, &entry.data))
+            .filter(|m| m.distance_m <= radius_m)
+            .collect();
+        found.sort_by(|a, b| a.distance_m.total_cmp(&b.distance_m));
+        found
+    }
+
+    fn project(&self, projection: &LocalProjection, seg: &SegmentRef) -> SegmentMatch<'_, T> {
+        let [(lon1, lat1), (lon2, lat2)] = seg.ends;
+        let a = projection.to_enu(lon1, lat1);
+        let b = projection.to_enu(lon2, lat2);
+        let (_, c) = closest_to_origin(a, b);
+
+        SegmentMatch {
+            item: &self.items[seg.line],
+            segment: seg.segment,
+            point: projection.to_lonlat(c),
+            distance_m: c.east.hypot(c.north),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)
+    }
+
+    /// Point `t` of the way from `a` to `b`, on the straight segment in the tangent plane
+    /// at `point` (the segments the polyline functions measure against)
+    fn on_segment(point: (f64, f64), a: &[f64], b: &[f64], t: f64) -> (f64, f64) {
+        let projection = LocalProjection::new(point.0, point.1);
+        let (a, b) = (projection.to_enu(a[0], a[1]), projection.to_enu(b[0], b[1]));
+        projection.to_lonlat(Enu {
+            east: a.east + t * (b.east - a.east),
+            north: a.north + t * (b.north - a.north),
+            up: a.up + t * (b.up - a.up),
+        })
+    }
+
+    /// Smallest ellipsoidal distance from `point` to a dense sampling of `line`
+    fn brute_force_closest(point: (f64, f64), line: &[Vec<f64>]) -> f64 {
+        const SAMPLES: usize = 4000;
+
+        line.windows(2)
+            .flat_map(|seg| {
+                (0..=SAMPLES).map(move |k| {
+                    let (lon, lat) = on_segment(point, &seg[0], &seg[1], k as f64 / SAMPLES as f64);
+                    geodesic_m(point.0, point.1, lon, lat)
+                })
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    // Points and polylines within ~5 km around Wonju
+    fn wonju_point() -> impl Strategy<Value = (f64, f64)> {
+        (127.88..127.98f64, 37.30..37.38f64)
+    }
+
+    fn wonju_line() -> impl Strategy<Value = Vec<Vec<f64>>> {
+        prop::collection::vec(wonju_point().prop_map(|(lon, lat)| vec![lon, lat]), 2..6)
+    }
+
+    #[test]
+    fn vincenty_matches_flinders_peak_to_buninyong() {
+        // Geoscience Australia reference pair (Vincenty, 1975)
+        let flinders = (dms(144.0, 25.0, 29.52440), dms(-37.0, 57.0, 3.72030));
+        let buninyong = (dms(143.0, 55.0, 35.38390), dms(-37.0, 39.0, 10.15610));
+
+        let d = vincenty_m(flinders.0, flinders.1, buninyong.0, buninyong.1).unwrap();
+        assert!((d - 54_972.271).abs() < 1e-3, "{}", d);
+    }
+
+    #[test]
+    fn vincenty_handles_coincident_and_equatorial_points() {
+        assert_eq!(vincenty_m(127.9, 37.3, 127.9, 37.3), Some(0.0));
+
+        // A degree of longitude on the equator is a / 180 * pi
+        let d = vincenty_m(0.0, 0.0, 1.0, 0.0).unwrap();
+        assert!((d - WGS84_A.to_radians()).abs() < 1e-3, "{}", d);
+    }
+
+    // A degree of longitude along the equator, then a degree of latitude along the
+    // meridian at 1°E (published WGS84 lengths: 111 319.491 m and 110 574.4 m)
+    fn equator_then_meridian() -> Vec<Vec<f64>> {
+        vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![1.0, 1.0]]
+    }
+
+    #[test]
+    fn polyline_lengths_match_published_distances() {
+        let line = equator_then_meridian();
+
+        let cumulative = cumulative_distances(&line);
+        assert_eq!(cumulative[0], 0.0);
+        assert!(
+            (cumulative[1] - 111_319.491).abs() < 1e-3,
+            "{:?}",
+            cumulative
+        );
+        assert!(
+            (cumulative[2] - cumulative[1] - 110_574.4).abs() < 0.5,
+            "{:?}",
+            cumulative
+        );
+
+        let (bbox, length) = calculate_metrics(&line);
+        assert_eq!(bbox, [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(length, cumulative[2]);
+
+        // Flinders Peak to Buninyong as a two-point line
+        let flinders = vec![dms(144.0, 25.0, 29.52440), dms(-37.0, 57.0, 3.72030)];
+        let buninyong = vec![dms(143.0, 55.0, 35.38390), dms(-37.0, 39.0, 10.15610)];
+        let cumulative = cumulative_distances(&[flinders, buninyong]);
+        assert!(
+            (cumulative[1] - 54_972.271).abs() < 1e-3,
+            "{:?}",
+            cumulative
+        );
+    }
+
+    #[test]
+    fn polyline_point_matches_published_distances() {
+        let line = equator_then_meridian();
+        // 0.001° north of the middle of the equator segment: 110.574 m off the line (the
+        // 111 km segment is straight in the tangent plane, a few mm off the equator)
+        let point = (0.5, 0.001);
+
+        let ((lon, lat), d) = closest_point_on_polyline(point, &line).unwrap();
+        assert!(
+            (lon - 0.5).abs() < 1e-9 && lat.abs() < 1e-9,
+            "{}, {}",
+            lon,
+            lat
+        );
+        assert!((d - 110.574).abs() < 0.01, "{}", d);
+
+        let cumulative = cumulative_distances(&line);
+        let (along, offset) = locate_on_polyline(point, &line, &cumulative, 0, 2).unwrap();
+        assert!((along - 111_319.491 / 2.0).abs() < 1e-3, "{}", along);
+        assert!((offset - 110.574).abs() < 0.01, "{}", offset);
+    }
+
+    proptest! {
+        #[test]
+        fn haversine_is_within_error_bound_of_vincenty(
+            lon1 in -180.0..180.0f64,
+            lat1 in -85.0..85.0f64,
+            lon2 in -180.0..180.0f64,
+            lat2 in -85.0..85.0f64,
+        ) {
+            let vincenty = vincenty_m(lon1, lat1, lon2, lat2);
+            prop_assume!(vincenty.is_some());
+            let vincenty = vincenty.unwrap();
+            prop_assume!(vincenty > 1.0);
+
+            let haversine = haversine_m(lon1, lat1, lon2, lat2);
+            prop_assert!(
+                (haversine - vincenty).abs() / vincenty < 0.006,
+                "haversine {} vs vincenty {}", haversine, vincenty
+            );
+        }
+
+        #[test]
+        fn local_projection_round_trips(
+            origin in (-180.0..180.0f64, -80.0..80.0f64),
+            offset in (-0.1..0.1f64, -0.1..0.1f64),
+        ) {
+            let projection = LocalProjection::new(origin.0, origin.1);
+            let (lon, lat) = (origin.0 + offset.0, origin.1 + offset.1);
+
+            let enu = projection.to_enu(lon, lat);
+            let (lon2, lat2) = projection.to_lonlat(enu);
+            // Longitudes past ±180° come back wrapped
+            let d_lon = (lon2 - lon).rem_euclid(360.0);
+            prop_assert!(d_lon.min(360.0 - d_lon) < 1e-9, "{} vs {}", lon2, lon);
+            prop_assert!((lat2 - lat).abs() < 1e-9, "{} vs {}", lat2, lat);
+
+            // Below 1 mm per km of distortion within 10 km of the origin (the plane
+            // shortens distances by about (d / R)² / 6)
+            let planar = enu.east.hypot(enu.north);
+            let geodesic = vincenty_m(origin.0, origin.1, lon, lat).unwrap();
+            if geodesic <= 10_000.0 {
+                prop_assert!(
+                    (planar - geodesic).abs() <= 1e-6 * geodesic + 1e-6,
+                    "{} vs {}", planar, geodesic
+                );
+            }
+        }
+
+        #[test]
+        fn closest_point_matches_brute_force(point in wonju_point(), line in wonju_line()) {
+            let ((lon, lat), d) = closest_point_on_polyline(point, &line).unwrap();
+            let brute = brute_force_closest(point, &line);
+
+            // Samples are at most ~3 m apart, so the sampled distance may be up to ~1.5 m longer
+            prop_assert!(d <= brute + 0.01, "{} vs brute force {}", d, brute);
+            prop_assert!(d >= brute - 1.5, "{} vs brute force {}", d, brute);
+            prop_assert!((geodesic_m(point.0, point.1, lon, lat) - d).abs() <= 0.01);
+        }
+
+        #[test]
+        fn locate_matches_brute_force(point in wonju_point(), line in wonju_line()) {
+            let cumulative = cumulative_distances(&line);
+            let (along, offset) = locate_on_polyline(point, &line, &cumulative, 0, line.len() - 1)
+                .unwrap();
+            let brute = brute_force_closest(point, &line);
+
+            prop_assert!(offset <= brute + 0.01, "{} vs brute force {}", offset, brute);
+            prop_assert!(offset >= brute - 1.5, "{} vs brute force {}", offset, brute);
+
+            // The point `along` the line is `offset` away from the query
+            let total = *cumulative.last().unwrap();
+            prop_assert!((0.0..=total + 1e-6).contains(&along));
+            let i = cumulative.partition_point(|&c| c <= along).clamp(1, line.len() - 1) - 1;
+            let length = cumulative[i + 1] - cumulative[i];
+            let t = if length > 0.0 { (along - cumulative[i]) / length } else { 0.0 };
+            let (lon, lat) = on_segment(point, &line[i], &line[i + 1], t);
+            prop_assert!((geodesic_m(point.0, point.1, lon, lat) - offset).abs() <= 0.01);
+        }
+    }
+}
@@ -8,6 +8,8 @@ use std::path::Path;
 use anyhow::Result;
 use serde_json::Value;
 
+use crate::error::{ApiError, PipelineError, StorageError, UpstreamError};
+
 pub fn ensure_dir(path: &Path) -> Result<()> {
     if !path.exists() {
         fs::create_dir_all(path)?;
@@ -15,16 +17,65 @@ pub fn ensure_dir(path: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn get_env(key: &str) -> String {
-    std::env::var(key).unwrap_or_else(|_| "".to_string())
+pub fn read_file(path: &Path) -> Result<String, StorageError> {
+    fs::read_to_string(path).map_err(|source| StorageError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
 }
 
-pub fn resolve_url(key: &str, default: &str) -> String {
-    let v = get_env(key);
-    if v.is_empty() { default.to_string() } else { v }
+/// JSON body of a TAGO response, after checking its HTTP status.
+///
+/// The portal answers key and quota errors with an XML envelope, even when JSON was
+/// requested; such bodies are reported as `ApiError::NotJson`.
+pub async fn tago_json(resp: reqwest::Result<reqwest::Response>) -> Result<Value, PipelineError> {
+    // The URL holds the service key; keep it out of errors (and the run report).
+    let network = |e: reqwest::Error| UpstreamError::Network {
+        service: "TAGO",
+        source: e.without_url(),
+    };
+
+    let resp = resp.map_err(network)?;
+    if !resp.status().is_success() {
+        return Err(UpstreamError::Status {
+            service: "TAGO",
+            status: resp.status().as_u16(),
+        }
+        .into());
+    }
+
+    let body = resp.text().await.map_err(network)?;
+    serde_json::from_str(&body).map_err(|_| {
+        ApiError::NotJson {
+            service: "TAGO",
+            snippet: snippet(&body),
+        }
+        .into()
+    })
 }
 
-pub fn extract_items(json: &Value) -> Result<Vec<Value>> {
+/// The start of a response body, for error messages
+pub fn snippet(body: &str) -> String {
+    let text = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(120) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+/// Items of a TAGO response, after checking its result code ("00" is normal service).
+pub fn extract_items(json: &Value) -> Result<Vec<Value>, ApiError> {
+    let header = &json["response"]["header"];
+    if let Some(code) = header["resultCode"].as_str()
+        && code != "00"
+    {
+        return Err(ApiError::ResultCode {
+            service: "TAGO",
+            code: code.to_string(),
+            message: header["resultMsg"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+
     let items = &json["response"]["body"]["items"]["item"];
     if let Some(arr) = items.as_array() {
         Ok(arr.clone())
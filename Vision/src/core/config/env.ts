@@ -101,7 +101,23 @@ export const STORAGE_KEYS = {
 
 export const DAY_TYPES = {
   WEEKDAY: 'weekday',
-  WEEKEND: 'weekend', // Covers weekends and public holidays
+  SATURDAY: 'saturday',
+  SUNDAY: 'sunday',
+  HOLIDAY: 'holiday',
+  WEEKEND: 'weekend', // Saturdays, Sundays and public holidays without their own timetable
+  VACATION_WEEKDAY: 'vacation_weekday',
+  VACATION_WEEKEND: 'vacation_weekend',
 } as const;
 
 export type DayType = typeof DAY_TYPES[keyof typeof DAY_TYPES];
+
+// Timetables to try when a route has no timetable for a day type (mirrors Polly)
+export const DAY_TYPE_FALLBACKS: Record<DayType, DayType[]> = {
+  weekday: [],
+  saturday: ['weekend'],
+  sunday: ['weekend'],
+  holiday: ['sunday', 'weekend'],
+  weekend: [],
+  vacation_weekday: ['weekday'],
+  vacation_weekend: ['weekend'],
+};
@@ -115,6 +115,10 @@ export const ARRIVAL_STATUS_LABELS = {
 
 export const DAY_LABELS = {
     WEEKDAY: '평일',
-    WEEKEND: '주말/공휴일',
+    SATURDAY: '토요일',
     SUNDAY: '일요일',
+    HOLIDAY: '공휴일',
+    WEEKEND: '주말/공휴일',
+    VACATION_WEEKDAY: '방학(평일)',
+    VACATION_WEEKEND: '방학(주말)',
 } as const;
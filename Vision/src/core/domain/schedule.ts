@@ -3,24 +3,85 @@
 export interface RowItem {
     minute: string;  // Minute
     noteId?: string; // Note identifier
+    // Attributes interpreted from the note (raw text stays in `notes`)
+    via?: string[];
+    shortTurn?: boolean;
+    terminatesAt?: string;
+    lowFloor?: boolean;
+    operatesOn?: 'school_days' | 'vacation';
+    lastBus?: boolean;
 }
 
 export interface HourlySchedule {
     [destination: string]: RowItem[];
 }
 
+// Day type -> Hour -> Times
+export interface DayTypeSchedules {
+    general?: { [hour: string]: HourlySchedule };
+    [dayType: string]: { [hour: string]: HourlySchedule } | undefined;
+}
+
+// Headways (minutes) within a time band (early, am_peak, midday, pm_peak, evening)
+export interface HeadwayBand {
+    band: string;
+    from: string;
+    to: string;
+    trips: number;
+    minHeadway?: number;
+    medianHeadway?: number;
+    maxHeadway?: number;
+}
+
+// Departures at a near-constant interval ("every 15 min 07:00–09:00")
+export interface RegularPeriod {
+    from: string;
+    to: string;
+    headwayMins: number;
+    exact: boolean;
+    trips: number;
+}
+
+export interface HeadwaySummary {
+    firstDeparture: string;
+    lastDeparture: string;
+    trips: number;
+    tripsPerHour: { [hour: string]: number };
+    bands: HeadwayBand[];
+    regularPeriods: RegularPeriod[];
+}
+
+// Day type -> Direction -> Summary
+export interface Headways {
+    [dayType: string]: { [direction: string]: HeadwaySummary };
+}
+
+// A timetable published with a later effective date (시행일)
+export interface UpcomingSchedule {
+    effectiveFrom: string;
+    directions: string[];
+    schedule: DayTypeSchedules;
+    dayTypeLabels?: { [dayType: string]: string };
+    notes?: { [key: string]: string };
+    headways?: Headways;
+}
+
 export interface BusSchedule {
     routeId: string;
     routeName: string;
     description: string;
-    lastUpdated: string;
+    lastUpdated: string; // Date the current timetable was first seen
+    effectiveFrom?: string; // Date the current timetable took effect
     directions: string[];
     routeDetails?: string[];
     featuredStops?: { [key: string]: string[] };
-    schedule: {
-        general?: { [hour: string]: HourlySchedule };
-        weekday?: { [hour: string]: HourlySchedule };
-        weekend?: { [hour: string]: HourlySchedule };
-    };
+    // Start of the service day ("HH:MM"); earlier trips are listed under hour "24" and later
+    serviceDayStart?: string;
+    // Day type (general, weekday, saturday, sunday, holiday, weekend, vacation_weekday, ...) -> Hour -> Times
+    schedule: DayTypeSchedules;
+    // Day type -> Raw label on the source timetable (e.g., "토·공휴일")
+    dayTypeLabels?: { [dayType: string]: string };
     notes?: { [key: string]: string };
+    headways?: Headways;
+    upcoming?: UpcomingSchedule;
 }
\ No newline at end of file
@@ -180,7 +180,7 @@ export default function BusList({ routeNames, allRoutes, selectedRoute, onRouteC
   const isScheduleExpanded = expandedPanel === "schedule";
   const schedulePayload = scheduleData?.schedule;
   const hasScheduleData = Boolean(
-    schedulePayload && (schedulePayload.general || schedulePayload.weekday || schedulePayload.weekend)
+    schedulePayload && Object.values(schedulePayload).some(Boolean)
   );
   const showSchedule = !scheduleMissing && (scheduleLoading || hasScheduleData);
 
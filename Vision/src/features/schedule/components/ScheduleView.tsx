@@ -7,7 +7,14 @@ import { useState, useMemo, useEffect, memo } from "react";
 import { DAY_TYPES, DayType } from "@core/config/env";
 import { UI_TEXT, DAY_LABELS } from "@core/config/locale";
 
-import { getCurrentDayType } from "@schedule/utils/time";
+import {
+    DEFAULT_SERVICE_DAY_START,
+    getAvailableDayTypes,
+    getCurrentDayType,
+    getServiceDate,
+    getServiceMinutes,
+    resolveDayType,
+} from "@schedule/utils/time";
 
 import type { BusSchedule, RowItem } from "@core/domain/schedule";
 
@@ -26,7 +33,12 @@ interface NextBusInfo {
  */
 const dayTypeToLabel = {
     [DAY_TYPES.WEEKDAY]: DAY_LABELS.WEEKDAY,
+    [DAY_TYPES.SATURDAY]: DAY_LABELS.SATURDAY,
+    [DAY_TYPES.SUNDAY]: DAY_LABELS.SUNDAY,
+    [DAY_TYPES.HOLIDAY]: DAY_LABELS.HOLIDAY,
     [DAY_TYPES.WEEKEND]: DAY_LABELS.WEEKEND,
+    [DAY_TYPES.VACATION_WEEKDAY]: DAY_LABELS.VACATION_WEEKDAY,
+    [DAY_TYPES.VACATION_WEEKEND]: DAY_LABELS.VACATION_WEEKEND,
 } as const;
 
 /**
@@ -34,44 +46,36 @@ const dayTypeToLabel = {
  */
 function getFeaturedStopsLabel(key: string): string {
     if (key === 'general') return '';
-    if (key === 'weekday') return DAY_LABELS.WEEKDAY;
-    if (key === 'sunday') return DAY_LABELS.SUNDAY;
+    if (key in dayTypeToLabel) return dayTypeToLabel[key as DayType];
     return key;
 }
 
 /**
  * Pure function to calculate the very next bus based on current time.
+ * Hours are service-day hours ("24" and later after midnight), compared against the service time of `now`.
  */
 function findNextBus(
     schedule: Record<string, Record<string, RowItem[]>>,
     hours: string[],
     direction: string,
-    now: Date
+    now: Date,
+    serviceDayStart: string
 ): NextBusInfo | null {
-    const currentHour = now.getHours().toString().padStart(2, "0");
-    const currentMinute = now.getMinutes();
+    const nowMs = getServiceMinutes(now, serviceDayStart) * 60000
+        + now.getSeconds() * 1000
+        + now.getMilliseconds();
 
     for (const hour of hours) {
         const buses = schedule[hour]?.[direction];
         if (!buses?.length) continue;
 
         const hourNum = parseInt(hour, 10);
-        const currentHourNum = parseInt(currentHour, 10);
-
-        // Skip past hours
-        if (hourNum < currentHourNum) continue;
 
         for (const bus of buses) {
             const busMinute = parseInt(bus.minute, 10);
 
-            // If it's the current hour, skip past minutes
-            if (hourNum === currentHourNum && busMinute < currentMinute) continue;
-
-            // Calculate exact time difference
-            const busTime = new Date(now);
-            busTime.setHours(hourNum, busMinute, 0, 0);
-
-            const diff = busTime.getTime() - now.getTime();
+            // Calculate exact time difference, skipping past buses
+            const diff = (hourNum * 60 + busMinute) * 60000 - nowMs;
             if (diff < 0) continue;
 
             return {
@@ -94,9 +98,13 @@ function findNextBus(
 
 function useScheduleLogic(data: BusSchedule) {
     const isGeneralSchedule = !!data.schedule.general;
+    const serviceDayStart = data.serviceDayStart ?? DEFAULT_SERVICE_DAY_START;
+    const availableDayTypes = useMemo(() => getAvailableDayTypes(data.schedule), [data.schedule]);
 
     // State
-    const [dayType, setDayType] = useState<DayType>(() => getCurrentDayType());
+    const [dayType, setDayType] = useState<DayType>(
+        () => resolveDayType(data.schedule, getCurrentDayType(getServiceDate(new Date(), serviceDayStart))) ?? availableDayTypes[0] ?? DAY_TYPES.WEEKDAY
+    );
     const [direction, setDirection] = useState(data.directions[0]);
     const [now, setNow] = useState(() => new Date());
 
@@ -113,7 +121,7 @@ function useScheduleLogic(data: BusSchedule) {
 
     // Derived State: Current active schedule based on day type
     const activeSchedule = useMemo(
-        () => (isGeneralSchedule ? data.schedule.general! : data.schedule[dayType]!),
+        () => (isGeneralSchedule ? data.schedule.general! : data.schedule[dayType] ?? {}),
         [data.schedule, dayType, isGeneralSchedule]
     );
 
@@ -125,15 +133,17 @@ function useScheduleLogic(data: BusSchedule) {
 
     // Derived State: Next bus info
     const nextBus = useMemo(
-        () => findNextBus(activeSchedule, hours, resolvedDirection, now),
-        [activeSchedule, hours, resolvedDirection, now]
+        () => findNextBus(activeSchedule, hours, resolvedDirection, now, serviceDayStart),
+        [activeSchedule, hours, resolvedDirection, now, serviceDayStart]
     );
 
     // Determine which hour to highlight (Next bus hour OR current hour)
-    const highlightedHour = nextBus?.hour ?? now.getHours().toString().padStart(2, "0");
+    const highlightedHour = nextBus?.hour
+        ?? String(Math.floor(getServiceMinutes(now, serviceDayStart) / 60)).padStart(2, "0");
 
     return {
         isGeneralSchedule,
+        availableDayTypes,
         dayType,
         setDayType,
         direction: resolvedDirection,
@@ -179,9 +189,17 @@ const RouteInfo = ({ details, featuredStops }: { details?: string[]; featuredSto
     </>
 );
 
-const DayTypeSelector = ({ current, onChange }: { current: DayType; onChange: (t: DayType) => void }) => (
+const DayTypeSelector = ({
+    dayTypes,
+    current,
+    onChange
+}: {
+    dayTypes: DayType[];
+    current: DayType;
+    onChange: (t: DayType) => void;
+}) => (
     <div className="flex bg-slate-200 p-1 rounded-xl">
-        {Object.values(DAY_TYPES).map((t) => (
+        {dayTypes.map((t) => (
             <button
                 key={t}
                 onClick={() => onChange(t)}
@@ -317,6 +335,7 @@ function ScheduleView({ data, mode = "full" }: { data: BusSchedule; mode?: "full
 
     const {
         isGeneralSchedule,
+        availableDayTypes,
         dayType,
         setDayType,
         direction,
@@ -337,7 +356,7 @@ function ScheduleView({ data, mode = "full" }: { data: BusSchedule; mode?: "full
 
             {/* 2. Day Type Tabs - Full Mode Only (if applicable) */}
             {!isCompact && !isGeneralSchedule && (
-                <DayTypeSelector current={dayType} onChange={setDayType} />
+                <DayTypeSelector dayTypes={availableDayTypes} current={dayType} onChange={setDayType} />
             )}
 
             {/* 3. Direction Buttons */}
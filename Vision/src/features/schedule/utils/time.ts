@@ -1,5 +1,6 @@
 // src/features/schedule/utils/time.ts
 
+import { DAY_TYPES, DAY_TYPE_FALLBACKS, DayType } from '@core/config/env';
 import type { BusSchedule } from '@core/domain/schedule';
 
 // ----------------------------------------------------------------------
@@ -8,6 +9,10 @@ import type { BusSchedule } from '@core/domain/schedule';
 
 const MINUTES_IN_HOUR = 60;
 const MINUTES_IN_DAY = 1440; // 24 * 60
+const HOURS_IN_DAY = 24;
+
+/** Start of the service day when a schedule does not record `serviceDayStart` */
+export const DEFAULT_SERVICE_DAY_START = '03:00';
 
 export interface NearestBusInfo {
     /** formatted time string (HH:mm) */
@@ -31,13 +36,31 @@ export function getCurrentHour(date: Date = new Date()): string {
 }
 
 /**
- * Determine if the current day is a 'weekday' or 'weekend'.
- * Note: Does not currently account for public holidays.
+ * Determine if the current day is a 'weekday', 'saturday' or 'sunday'.
+ * Note: Does not currently account for public holidays or vacations.
  * @param date - Optional date object (defaults to now)
  */
-export function getCurrentDayType(date: Date = new Date()): 'weekday' | 'weekend' {
+export function getCurrentDayType(date: Date = new Date()): DayType {
     const day = date.getDay(); // 0 is Sunday, 6 is Saturday
-    return day === 0 || day === 6 ? 'weekend' : 'weekday';
+    if (day === 0) return DAY_TYPES.SUNDAY;
+    if (day === 6) return DAY_TYPES.SATURDAY;
+    return DAY_TYPES.WEEKDAY;
+}
+
+/**
+ * Resolve the day type whose timetable runs on `dayType`, following the fallbacks
+ * (e.g., 'saturday' -> 'weekend'). Returns null if the route has none of them.
+ */
+export function resolveDayType(schedule: BusSchedule['schedule'], dayType: DayType): DayType | null {
+    const candidates = [dayType, ...DAY_TYPE_FALLBACKS[dayType]];
+    return candidates.find((t) => schedule[t]) ?? null;
+}
+
+/**
+ * List the day types a route has timetables for, in display order.
+ */
+export function getAvailableDayTypes(schedule: BusSchedule['schedule']): DayType[] {
+    return Object.values(DAY_TYPES).filter((t) => schedule[t]);
 }
 
 /**
@@ -55,6 +78,42 @@ export function getCurrentMinutes(date: Date = new Date()): number {
     return date.getHours() * MINUTES_IN_HOUR + date.getMinutes();
 }
 
+/**
+ * Get the minutes since midnight of the service day `date` belongs to.
+ * Times before `serviceDayStart` belong to the previous service day and count past 24:00
+ * (e.g., 00:20 -> 1460), matching the "24", "25", ... hour keys of the schedule.
+ * @param date - Optional date object (defaults to now)
+ */
+export function getServiceMinutes(
+    date: Date = new Date(),
+    serviceDayStart: string = DEFAULT_SERVICE_DAY_START
+): number {
+    const minutes = getCurrentMinutes(date);
+    return minutes < timeToMinutes(serviceDayStart) ? minutes + MINUTES_IN_DAY : minutes;
+}
+
+/**
+ * Get the calendar date of the service day `date` belongs to
+ * (the previous day before `serviceDayStart`).
+ * @param date - Optional date object (defaults to now)
+ */
+export function getServiceDate(
+    date: Date = new Date(),
+    serviceDayStart: string = DEFAULT_SERVICE_DAY_START
+): Date {
+    if (getCurrentMinutes(date) >= timeToMinutes(serviceDayStart)) return date;
+    const previous = new Date(date);
+    previous.setDate(previous.getDate() - 1);
+    return previous;
+}
+
+/**
+ * Map a service-day hour key to the clock hour (e.g., "24" -> "00", "25" -> "01").
+ */
+export function toClockHour(hour: string): string {
+    return String(parseInt(hour, 10) % HOURS_IN_DAY).padStart(2, '0');
+}
+
 /**
  * Convert a time string (HH:mm or HH) to total minutes since midnight.
  * @param timeStr - e.g., "14:30" or "14"
@@ -76,22 +135,29 @@ export function timeToMinutes(timeStr: string): number {
 
 /**
  * Find the nearest upcoming bus time from the provided schedule data.
- * This function handles wrapping around midnight (finding the first bus of tomorrow if none are left today).
+ * Times are compared on the service day, so late-night trips (hour "24" and later) come after 23:xx,
+ * and the first bus of the next service day is found if none are left today.
  * * @param busData - The full bus schedule object
  * @returns The nearest bus info or null if no schedule is available
  */
 export function getNearestBusTime(busData: BusSchedule): NearestBusInfo | null {
-    // Priority: General Schedule -> Day-specific Schedule (Weekday/Weekend)
-    const schedule = busData.schedule?.general || busData.schedule?.[getCurrentDayType()];
+    const serviceDayStart = busData.serviceDayStart ?? DEFAULT_SERVICE_DAY_START;
+    const now = new Date();
+
+    // Priority: General Schedule -> Day-specific Schedule (Weekday/Saturday/Sunday, with fallbacks)
+    const dayType = busData.schedule
+        ? resolveDayType(busData.schedule, getCurrentDayType(getServiceDate(now, serviceDayStart)))
+        : null;
+    const schedule = busData.schedule?.general || (dayType ? busData.schedule[dayType] : undefined);
 
     if (!schedule) return null;
 
-    const currentTotalMinutes = getCurrentMinutes();
+    const currentServiceMinutes = getServiceMinutes(now, serviceDayStart);
 
     let minDifference = Infinity;
     let nearestBus: NearestBusInfo | null = null;
 
-    // Iterate through hours (Keys are "06", "07", etc.)
+    // Iterate through hours (Keys are "06", "07", ..., "24", "25" for late-night trips)
     for (const [hourStr, hourlySchedule] of Object.entries(schedule)) {
         const hourNum = parseInt(hourStr, 10);
         const baseHourMinutes = hourNum * MINUTES_IN_HOUR;
@@ -100,19 +166,19 @@ export function getNearestBusTime(busData: BusSchedule): NearestBusInfo | null {
         for (const [destination, busTimes] of Object.entries(hourlySchedule)) {
             // Iterate through specific bus times
             for (const { minute } of busTimes) {
-                const busTotalMinutes = baseHourMinutes + parseInt(minute, 10);
+                const busServiceMinutes = baseHourMinutes + parseInt(minute, 10);
 
                 // Calculate time difference
-                // If the bus time is earlier than now, we assume it's for the next day (wrap around)
-                // e.g. Now: 23:50, Bus: 06:00 -> difference is (06:00 + 24h) - 23:50
-                const difference = busTotalMinutes >= currentTotalMinutes
-                    ? busTotalMinutes - currentTotalMinutes
-                    : MINUTES_IN_DAY + busTotalMinutes - currentTotalMinutes;
+                // If the bus time is earlier than now, we assume it's for the next service day (wrap around)
+                // e.g. Now: 24:50 (00:50), Bus: 06:00 -> difference is (06:00 + 24h) - 24:50
+                const difference = busServiceMinutes >= currentServiceMinutes
+                    ? busServiceMinutes - currentServiceMinutes
+                    : MINUTES_IN_DAY + busServiceMinutes - currentServiceMinutes;
 
                 if (difference < minDifference) {
                     minDifference = difference;
                     nearestBus = {
-                        time: `${hourStr}:${minute}`,
+                        time: `${toClockHour(hourStr)}:${minute}`,
                         minutesUntil: difference,
                         destination
                     };